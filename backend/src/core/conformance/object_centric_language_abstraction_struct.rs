@@ -7,7 +7,7 @@ use crate::models::ocel::{EventIndex, ObjectIndex};
 use crate::models::ocel::{IndexLinkedOCEL, LinkedOCELAccess};
 use itertools::MultiUnzip;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::ops::{AddAssign, DivAssign};
 use uuid::Uuid;
 
@@ -573,6 +573,50 @@ impl OCLanguageAbstraction {
     }
 }
 
+///
+/// The language of a single object type in an [`OCLanguageAbstraction`], with sorted event types
+///
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct ObTypeLanguage {
+    pub start: BTreeSet<EventType>,
+    pub end: BTreeSet<EventType>,
+    pub directly_follows: BTreeSet<(EventType, EventType)>,
+    pub related: BTreeSet<EventType>,
+    pub divergent: BTreeSet<EventType>,
+    pub convergent: BTreeSet<EventType>,
+    pub deficient: BTreeSet<EventType>,
+    pub optional: BTreeSet<EventType>,
+}
+
+impl OCLanguageAbstraction {
+    ///
+    /// The abstraction per object type, sorted by object type and event types so that it
+    /// serializes deterministically
+    ///
+    pub fn per_ob_type(&self) -> BTreeMap<ObjectType, ObTypeLanguage> {
+        fn fill<T: Clone + Ord>(
+            result: &mut BTreeMap<ObjectType, ObTypeLanguage>,
+            per_ob_type: &HashMap<ObjectType, HashSet<T>>,
+            field: fn(&mut ObTypeLanguage) -> &mut BTreeSet<T>,
+        ) {
+            per_ob_type.iter().for_each(|(ob_type, values)| {
+                field(result.entry(ob_type.clone()).or_default()).extend(values.iter().cloned());
+            });
+        }
+
+        let mut result = BTreeMap::new();
+        fill(&mut result, &self.start_ev_type_per_ob_type, |l| &mut l.start);
+        fill(&mut result, &self.end_ev_type_per_ob_type, |l| &mut l.end);
+        fill(&mut result, &self.directly_follows_ev_types_per_ob_type, |l| &mut l.directly_follows);
+        fill(&mut result, &self.related_ev_type_per_ob_type, |l| &mut l.related);
+        fill(&mut result, &self.divergent_ev_type_per_ob_type, |l| &mut l.divergent);
+        fill(&mut result, &self.convergent_ev_type_per_ob_type, |l| &mut l.convergent);
+        fill(&mut result, &self.deficient_ev_type_per_ob_type, |l| &mut l.deficient);
+        fill(&mut result, &self.optional_ev_type_per_ob_type, |l| &mut l.optional);
+        result
+    }
+}
+
 ///
 /// Reverses a `HashMap<EventType, HashSet<ObjectType>>` to `HashMap<ObjectType, HashSet<EventType>>`
 /// and inserts missing object types given in `ob_types`
@@ -850,4 +894,35 @@ pub fn compute_fitness_precision(
         (fitness_weighted_ev_types + fitness_weighted_dfr) / 2.0,
         (precision_weighted_ev_types + precision_weighted_dfr) / 2.0,
     )
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ocpt::OCPTOperatorType;
+
+    #[test]
+    fn test_per_ob_type_of_ocpt() {
+        let leaf = |label: &str, related: &[&str]| {
+            let mut node = OCPTNode::new_leaf(Some(label.to_string()));
+            related.iter().for_each(|ot| node.add_related_ob_type(&ot.to_string()));
+            node
+        };
+        let mut choice = OCPTNode::new_operator(OCPTOperatorType::ExclusiveChoice);
+        choice.add_child(leaf("pay", &["order"]));
+        choice.add_child(OCPTNode::new_leaf(None));
+        let mut root = OCPTNode::new_operator(OCPTOperatorType::Sequence);
+        root.add_child(leaf("place", &["order", "item"]));
+        root.add_child(choice);
+
+        let languages = OCLanguageAbstraction::create_from_oc_process_tree(&OCPT::new(root)).per_ob_type();
+        assert_eq!(languages.keys().collect::<Vec<_>>(), ["item", "order"]);
+        let order = &languages["order"];
+        let set = |evs: &[&str]| evs.iter().map(|ev| ev.to_string()).collect::<BTreeSet<_>>();
+        assert_eq!(order.start, set(&["place"]));
+        assert_eq!(order.end, set(&["pay", "place"]));
+        assert_eq!(order.related, set(&["pay", "place"]));
+        assert_eq!(order.optional, set(&["pay"]));
+        assert!(order.directly_follows.contains(&("place".to_string(), "pay".to_string())));
+        assert_eq!(languages["item"].related, set(&["place"]));
+    }
+}
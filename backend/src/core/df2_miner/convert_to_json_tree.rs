@@ -1,5 +1,5 @@
 use serde::Serialize;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use crate::models::ocpt::{TreeNode, ProcessForest};
 use itertools::Itertools;
 use log::info;
@@ -114,7 +114,7 @@ pub fn build_output(
     div: &HashMap<String, Vec<String>>,
 ) -> OutputJson {
     // Determine unique OTs
-    let all_ots: HashSet<_> = con.values()
+    let all_ots: BTreeSet<_> = con.values()
         .chain(defi.values())
         .chain(div.values())
         .flatten()
@@ -0,0 +1,396 @@
+//! Cut detection of the df2 miner.
+//!
+//! A cut splits the activities of a DFG into two sets joined by an operator. Two cut finders
+//! share the `Cut` output:
+//! - `CutFinder::Exact` checks every bipartition against the cut definitions in `start_cuts.rs`,
+//!   which is exponential in the number of activities;
+//! - `CutFinder::Optimised` (default) builds one candidate per cut kind with the heuristics in
+//!   `start_cuts_opti.rs`.
+//!
+//! `compare_cut_finders` and `differential_run` cross-check both finders on random small DFGs.
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use log::info;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
+
+use crate::core::df2_miner::start_cuts::{
+    find_cut_exact, is_exclusive_choice_cut_possible, is_parallel_cut_possible, is_redo_cut_possible,
+    is_sequence_cut_possible,
+};
+use crate::core::df2_miner::start_cuts_opti::find_cut_optimised;
+use crate::models::ocpt::{ProcessForest, TreeNode};
+
+/// Above this number of activities the exact cut finder falls back to the optimised one
+pub const EXACT_CUT_MAX_ACTIVITIES: usize = 12;
+
+/// A cut of a set of activities into two non-empty sets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cut {
+    Exclusive(HashSet<String>, HashSet<String>),
+    Sequence(HashSet<String>, HashSet<String>),
+    Parallel(HashSet<String>, HashSet<String>),
+    /// Do part and redo part
+    Redo(HashSet<String>, HashSet<String>),
+}
+
+impl Cut {
+    /// The operator label of the cut in the mined `TreeNode`s
+    pub fn label(&self) -> &'static str {
+        match self {
+            Cut::Exclusive(..) => "excl",
+            Cut::Sequence(..) => "seq",
+            Cut::Parallel(..) => "para",
+            Cut::Redo(..) => "redo",
+        }
+    }
+
+    pub fn sets(&self) -> (&HashSet<String>, &HashSet<String>) {
+        match self {
+            Cut::Exclusive(set_1, set_2)
+            | Cut::Sequence(set_1, set_2)
+            | Cut::Parallel(set_1, set_2)
+            | Cut::Redo(set_1, set_2) => (set_1, set_2),
+        }
+    }
+
+    /// Whether the cut satisfies the exact definition of its kind (see `start_cuts.rs`)
+    pub fn is_valid(
+        &self,
+        filtered_dfg: &HashMap<(String, String), usize>,
+        start_activities: &HashSet<String>,
+        end_activities: &HashSet<String>,
+    ) -> bool {
+        let (set_1, set_2) = self.sets();
+        if set_1.is_empty() || set_2.is_empty() || !set_1.is_disjoint(set_2) {
+            return false;
+        }
+        match self {
+            Cut::Exclusive(..) => is_exclusive_choice_cut_possible(filtered_dfg, set_1, set_2),
+            Cut::Sequence(..) => is_sequence_cut_possible(filtered_dfg, set_1, set_2),
+            Cut::Parallel(..) => {
+                is_parallel_cut_possible(filtered_dfg, set_1, set_2, start_activities, end_activities)
+            }
+            Cut::Redo(..) => is_redo_cut_possible(filtered_dfg, set_1, set_2, start_activities, end_activities),
+        }
+    }
+}
+
+impl fmt::Display for Cut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn sorted(set: &HashSet<String>) -> Vec<&String> {
+            let mut activities: Vec<&String> = set.iter().collect();
+            activities.sort();
+            activities
+        }
+        let (set_1, set_2) = self.sets();
+        write!(f, "{} {:?} {:?}", self.label(), sorted(set_1), sorted(set_2))
+    }
+}
+
+/// How cuts are detected while mining
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CutFinder {
+    /// Exhaustive search over all bipartitions (up to `EXACT_CUT_MAX_ACTIVITIES` activities)
+    Exact,
+    /// Heuristic candidates per cut kind
+    #[default]
+    Optimised,
+}
+
+impl CutFinder {
+    /// Parses a cut finder name as given in the `cuts` query parameter
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "exact" => Some(Self::Exact),
+            "optimised" | "optimized" => Some(Self::Optimised),
+            _ => None,
+        }
+    }
+
+    /// Finds a cut of the (already filtered) DFG, `None` if there is none
+    pub fn find_cut(
+        &self,
+        filtered_dfg: &HashMap<(String, String), usize>,
+        all_activities: &HashSet<String>,
+        start_activities: &HashSet<String>,
+        end_activities: &HashSet<String>,
+    ) -> Option<Cut> {
+        match self {
+            CutFinder::Exact if all_activities.len() <= EXACT_CUT_MAX_ACTIVITIES => {
+                find_cut_exact(filtered_dfg, all_activities, start_activities, end_activities)
+            }
+            CutFinder::Exact => {
+                info!(
+                    "{} activities exceed the exact cut search limit, using the optimised cut finder",
+                    all_activities.len()
+                );
+                find_cut_optimised(filtered_dfg, all_activities, start_activities, end_activities)
+            }
+            CutFinder::Optimised => {
+                find_cut_optimised(filtered_dfg, all_activities, start_activities, end_activities)
+            }
+        }
+    }
+}
+
+/// Recursively cuts the activities of the DFG into a process forest
+pub fn find_cuts_start(
+    dfg: &HashMap<(String, String), usize>,
+    all_activities: &HashSet<String>,
+    start_activities: &HashSet<String>,
+    end_activities: &HashSet<String>,
+    cut_finder: CutFinder,
+) -> ProcessForest {
+    let mut activities: Vec<String> = all_activities.iter().cloned().collect();
+    activities.sort();
+
+    if activities.len() == 1 {
+        // Base case: single activity, create a leaf node
+        return vec![TreeNode {
+            label: activities[0].clone(),
+            children: Vec::new(),
+        }];
+    }
+
+    let filtered_dfg = filter_keep_dfg(dfg, all_activities);
+    let (start_activities, end_activities) =
+        get_start_and_end_activities(dfg, all_activities, start_activities, end_activities);
+
+    if let Some(cut) = cut_finder.find_cut(&filtered_dfg, all_activities, &start_activities, &end_activities) {
+        info!("Cut found: {cut}");
+        let (set_1, set_2) = cut.sets();
+        let mut node = TreeNode {
+            label: cut.label().to_string(),
+            children: Vec::new(),
+        };
+        node.children.extend(find_cuts_start(dfg, set_1, &start_activities, &end_activities, cut_finder));
+        node.children.extend(find_cuts_start(dfg, set_2, &start_activities, &end_activities, cut_finder));
+        return vec![node];
+    }
+
+    info!(
+        "No further cuts found for the current set of activities: {:?}",
+        all_activities
+    );
+    // If no valid cuts are found, return disjoint trees
+    activities
+        .into_iter()
+        .map(|activity| TreeNode {
+            label: activity,
+            children: Vec::new(),
+        })
+        .collect()
+}
+
+fn filter_keep_dfg(
+    dfg: &HashMap<(String, String), usize>,
+    keep_list: &HashSet<String>,
+) -> HashMap<(String, String), usize> {
+    dfg.iter()
+        .filter(|((from, to), _)| keep_list.contains(from) && keep_list.contains(to))
+        .map(|(k, v)| (k.clone(), *v))
+        .collect()
+}
+
+/// Start and end activities of a subset of the activities: the global ones in the subset and
+/// those entered from or left to activities outside of it
+fn get_start_and_end_activities(
+    dfg: &HashMap<(String, String), usize>,
+    filtered_activities: &HashSet<String>,
+    global_start_activities: &HashSet<String>,
+    global_end_activities: &HashSet<String>,
+) -> (HashSet<String>, HashSet<String>) {
+    let mut start_activities = HashSet::new();
+    let mut end_activities = HashSet::new();
+
+    for (a, b) in dfg.keys() {
+        let a_in = filtered_activities.contains(a);
+        let b_in = filtered_activities.contains(b);
+
+        if !a_in && b_in {
+            // 'a' is outside and 'b' is inside → 'b' is a start activity
+            start_activities.insert(b.clone());
+        }
+
+        if a_in && !b_in {
+            // 'a' is inside and 'b' is outside → 'a' is an end activity
+            end_activities.insert(a.clone());
+        }
+    }
+
+    // Add common activities from global sets
+    for activity in filtered_activities {
+        if global_start_activities.contains(activity) {
+            start_activities.insert(activity.clone());
+        }
+        if global_end_activities.contains(activity) {
+            end_activities.insert(activity.clone());
+        }
+    }
+
+    (start_activities, end_activities)
+}
+
+// --------------------- differential testing ---------------------
+
+/// A DFG on which the exact and optimised cut finders disagree
+#[derive(Debug, Clone)]
+pub struct CutDisagreement {
+    pub dfg: Vec<(String, String)>,
+    pub start_activities: Vec<String>,
+    pub end_activities: Vec<String>,
+    pub exact: Option<Cut>,
+    pub optimised: Option<Cut>,
+    /// Whether the optimised cut satisfies the exact definition of its kind
+    pub optimised_valid: bool,
+}
+
+impl fmt::Display for CutDisagreement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cut = |cut: &Option<Cut>| cut.as_ref().map_or("none".to_string(), Cut::to_string);
+        writeln!(f, "dfg: {:?}", self.dfg)?;
+        writeln!(f, "  start: {:?}, end: {:?}", self.start_activities, self.end_activities)?;
+        writeln!(f, "  exact:     {}", cut(&self.exact))?;
+        write!(f, "  optimised: {}", cut(&self.optimised))?;
+        if !self.optimised_valid {
+            write!(f, " (violates the exact cut definition)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs both cut finders on one DFG. They disagree if they find cuts of different kinds (or only
+/// one finds a cut), or if the optimised cut does not satisfy the exact cut definition.
+/// Cuts of the same kind with different sets are not reported.
+pub fn compare_cut_finders(
+    dfg: &HashMap<(String, String), usize>,
+    all_activities: &HashSet<String>,
+    start_activities: &HashSet<String>,
+    end_activities: &HashSet<String>,
+) -> Option<CutDisagreement> {
+    let exact = find_cut_exact(dfg, all_activities, start_activities, end_activities);
+    let optimised = find_cut_optimised(dfg, all_activities, start_activities, end_activities);
+    let optimised_valid = optimised
+        .as_ref()
+        .is_none_or(|cut| cut.is_valid(dfg, start_activities, end_activities));
+    let same_kind = exact.as_ref().map(Cut::label) == optimised.as_ref().map(Cut::label);
+    if same_kind && optimised_valid {
+        return None;
+    }
+
+    let sorted = |set: &HashSet<String>| {
+        let mut activities: Vec<String> = set.iter().cloned().collect();
+        activities.sort();
+        activities
+    };
+    let mut edges: Vec<(String, String)> = dfg.keys().cloned().collect();
+    edges.sort();
+    Some(CutDisagreement {
+        dfg: edges,
+        start_activities: sorted(start_activities),
+        end_activities: sorted(end_activities),
+        exact,
+        optimised,
+        optimised_valid,
+    })
+}
+
+/// A DFG with its activities and its start and end activities
+pub struct RandomDfg {
+    pub dfg: HashMap<(String, String), usize>,
+    pub activities: HashSet<String>,
+    pub start_activities: HashSet<String>,
+    pub end_activities: HashSet<String>,
+}
+
+/// A random DFG over the activities `a0`, `a1`, ... with non-empty start and end activities.
+/// Every edge (self-loops included) is present with probability `density`.
+pub fn random_dfg(rng: &mut StdRng, activity_count: usize, density: f64) -> RandomDfg {
+    let activities: Vec<String> = (0..activity_count).map(|i| format!("a{i}")).collect();
+    let mut dfg = HashMap::new();
+    for from in &activities {
+        for to in &activities {
+            if rng.gen_bool(density) {
+                dfg.insert((from.clone(), to.clone()), rng.gen_range(1..10));
+            }
+        }
+    }
+    let random_subset = |rng: &mut StdRng| {
+        let mut subset: HashSet<String> = activities.iter().filter(|_| rng.gen_bool(0.3)).cloned().collect();
+        if subset.is_empty() {
+            subset.insert(activities[rng.gen_range(0..activities.len())].clone());
+        }
+        subset
+    };
+    let start_activities = random_subset(rng);
+    let end_activities = random_subset(rng);
+    RandomDfg { dfg, activities: activities.into_iter().collect(), start_activities, end_activities }
+}
+
+/// Compares both cut finders on `runs` random DFGs with 2 to `max_activities` activities
+pub fn differential_run(runs: usize, max_activities: usize, seed: u64) -> Vec<CutDisagreement> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..runs)
+        .filter_map(|_| {
+            let activity_count = rng.gen_range(2..=max_activities.max(2));
+            let density = rng.gen_range(0.1..0.7);
+            let random = random_dfg(&mut rng, activity_count, density);
+            compare_cut_finders(&random.dfg, &random.activities, &random.start_activities, &random.end_activities)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(activities: &[&str]) -> HashSet<String> {
+        activities.iter().map(|a| a.to_string()).collect()
+    }
+
+    fn dfg(edges: &[(&str, &str)]) -> HashMap<(String, String), usize> {
+        edges.iter().map(|(a, b)| ((a.to_string(), b.to_string()), 1)).collect()
+    }
+
+    #[test]
+    fn test_both_finders_find_the_same_cut_kinds() {
+        let cases = [
+            (dfg(&[("a", "b"), ("c", "d")]), set(&["a", "c"]), set(&["b", "d"]), "excl"),
+            (dfg(&[("a", "b"), ("b", "c")]), set(&["a"]), set(&["c"]), "seq"),
+            (dfg(&[("a", "b"), ("b", "a")]), set(&["a", "b"]), set(&["a", "b"]), "para"),
+            (dfg(&[("a", "c"), ("c", "b"), ("b", "a")]), set(&["a"]), set(&["c"]), "redo"),
+        ];
+        for (dfg, start, end, label) in cases {
+            let activities: HashSet<String> = dfg.keys().flat_map(|(a, b)| [a.clone(), b.clone()]).collect();
+            for finder in [CutFinder::Exact, CutFinder::Optimised] {
+                let cut = finder.find_cut(&dfg, &activities, &start, &end).unwrap();
+                assert_eq!(cut.label(), label, "{finder:?}");
+                assert!(cut.is_valid(&dfg, &start, &end), "{finder:?}: {cut}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_exact_finder_mines_sequence_of_choice() {
+        let dfg = dfg(&[("a", "b"), ("a", "c"), ("b", "d"), ("c", "d")]);
+        let forest = find_cuts_start(&dfg, &set(&["a", "b", "c", "d"]), &set(&["a"]), &set(&["d"]), CutFinder::Exact);
+        assert_eq!(forest.len(), 1);
+        assert_eq!(forest[0].label, "seq");
+    }
+
+    /// Cross-checks the finders on random DFGs. Both may legitimately pick different cut kinds,
+    /// so disagreements are only reported; optimised cuts must satisfy the exact definitions.
+    #[test]
+    fn test_differential_random_dfgs() {
+        let disagreements = differential_run(300, 6, 7);
+        for disagreement in &disagreements {
+            eprintln!("{disagreement}");
+        }
+        eprintln!("{} of 300 random DFGs with disagreeing cut finders", disagreements.len());
+        assert!(disagreements.iter().all(|d| d.optimised_valid));
+    }
+}
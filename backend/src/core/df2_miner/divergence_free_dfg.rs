@@ -2,8 +2,6 @@ use std::collections::{HashMap, HashSet};
 use chrono::NaiveDateTime;
 use chrono::{DateTime, Utc};
 
-use std::fs::File;
-use std::io::Write;
 use std::io::BufReader;
 use serde_json::Value;
 
@@ -16,12 +14,6 @@ pub fn get_divergence_free_graph_v2(
     HashSet<String>,
 ) {
 
-    // // Convert to a serializable Vec of Vecs
-    let serializable: Vec<&(String, String, String, String, String)> = relations.iter().collect();
-    let json = serde_json::to_string_pretty(&serializable).unwrap();
-    let mut file = File::create("relations2.json").expect("Unable to create file");
-    file.write_all(json.as_bytes()).expect("Unable to write data");
-
     // Initialize return structures
     let mut dfg: HashMap<(String, String), usize> = HashMap::new();
     let mut start_activities: HashSet<String> = HashSet::new();
@@ -10,6 +10,7 @@ mod build_relations_fns;
 mod types;
 mod interaction_patterns;
 mod divergence_free_dfg;
+mod cuts;
 mod start_cuts;
 mod start_cuts_opti;
 use log::info;
@@ -58,11 +59,9 @@ fn main() {
     let filtered_dfg = filter_dfg(&dfg, &remove_list);
     let filtered_activities = filter_activities(&all_activities, &remove_list);
 
-    //// The function in starts_cuts file implements the exact mathematical formula of Inductive miner, so it can be very slow if there are a large number of activities.
-    // let process_forest = start_cuts::find_cuts(&filtered_dfg, &filtered_dfg, filtered_activities, &start_acts, &end_acts);
-
-    //// The function in starts_cuts_opti file implements optimised algorithms for finding cuts in Inductive miner, so it is very fast.
-    let process_forest = start_cuts_opti::find_cuts_start(&filtered_dfg, &filtered_activities, &start_acts, &end_acts);
+    //// CutFinder::Exact implements the exact mathematical formula of Inductive miner (start_cuts), so it can be very slow if there are a large number of activities.
+    //// CutFinder::Optimised implements optimised algorithms for finding cuts in Inductive miner (start_cuts_opti), so it is very fast.
+    let process_forest = cuts::find_cuts_start(&filtered_dfg, &filtered_activities, &start_acts, &end_acts, cuts::CutFinder::Optimised);
 
     // println!("\nStart Activities: {:?}", start_acts);
     // println!("End Activities: {:?}", end_acts);
@@ -1,6 +1,7 @@
 pub mod build_relations_fns;
 pub mod interaction_patterns;
 pub mod divergence_free_dfg;
+pub mod cuts;
 pub mod start_cuts;
 pub mod start_cuts_opti;
 pub mod convert_to_json_tree;
@@ -7,28 +7,64 @@ use simplelog::*;
 use std::io::Write;
 
 use crate::models::ocel_sid::{OcelJson, Event, Object};
+use process_mining::OCEL;
 use crate::core::df2_miner::{
     build_relations_fns,
     interaction_patterns,
     divergence_free_dfg,
-    start_cuts_opti,
 };
+use crate::core::df2_miner::cuts::{find_cuts_start, CutFinder};
 use crate::models::ocpt::{ProcessForest, TreeNode};
-use crate::core::df2_miner::convert_to_json_tree::{build_output}; // << your new module
+use crate::core::df2_miner::convert_to_json_tree::{build_output};
+use crate::core::struct_converters::ocpt_storage::parse_ocpt;
 use uuid::Uuid;
 use log::info;
 
-pub fn generate_ocpt_from_fileid(file_id: &str) -> String {
+/// Options of the df2 miner
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct MiningOptions {
+    /// Exact or optimised (default) cut detection, see `cuts.rs`
+    pub cut_finder: CutFinder,
+}
+
+/// A mined OCPT stored in `./temp`
+pub struct MinedOcpt {
+    pub file_id: String,
+    /// See `OCPT::content_hash`
+    pub content_hash: String,
+}
+
+pub fn generate_ocpt_from_ocel(stored: &OCEL, options: &MiningOptions) -> MinedOcpt {
     // Setup logging (ignore if already initialized)
     CombinedLogger::init(vec![
         TermLogger::new(LevelFilter::Info, Config::default(), TerminalMode::Mixed, ColorChoice::Auto),
         WriteLogger::new(LevelFilter::Info, Config::default(), File::create("process.log").unwrap()),
     ]).ok();
 
-    // Load OCEL from temp
-    let file_path = format!("./temp/ocel_v2_{}.json", file_id);
-    let file_content = stdfs::read_to_string(&file_path).unwrap();
-    let ocel: OcelJson = serde_json::from_str(&file_content).unwrap();
+    let ocpt_json = mine_ocpt_json(stored, options);
+    let content_hash = parse_ocpt(&ocpt_json)
+        .expect("mined OCPT is a frontend OCPT")
+        .content_hash();
+
+    // Generate new unique file_id
+    let new_file_id = Uuid::new_v4().to_string();
+
+    // Write result
+    let out_path = format!("./temp/ocpt_{}.json", new_file_id);
+    stdfs::write(&out_path, ocpt_json).unwrap();
+
+    println!("✅ OCPT saved to {} (new file_id = {})", out_path, new_file_id);
+
+    // Return the new id so caller can propagate it
+    MinedOcpt { file_id: new_file_id, content_hash }
+}
+
+/// Mines an OCPT from the OCEL and returns it as frontend OCPT JSON, without storing it.
+/// Mining is deterministic: the same log always yields the same JSON.
+pub fn mine_ocpt_json(stored: &OCEL, options: &MiningOptions) -> String {
+    // Miner view of the OCEL
+    let ocel = OcelJson::from_ocel(stored);
 
     // Build relations
     let relations = build_relations_fns::build_relations(&ocel.events, &ocel.objects);
@@ -44,28 +80,17 @@ pub fn generate_ocpt_from_fileid(file_id: &str) -> String {
     let filtered_activities = filter_activities(&all_activities, &remove_list);
 
     // Mine the process forest
-    let process_forest = start_cuts_opti::find_cuts_start(
+    let process_forest = find_cuts_start(
         &filtered_dfg,
         &filtered_activities,
         &start_acts,
         &end_acts,
+        options.cut_finder,
     );
 
     // Convert to OCPT output format
     let ocpt_output = build_output(&process_forest, &con, &defi, &div);
-
-    // Generate new unique file_id
-    let new_file_id = Uuid::new_v4().to_string();
-
-    // Serialize and write result
-    let ocpt_json = serde_json::to_string_pretty(&ocpt_output).unwrap();
-    let out_path = format!("./temp/ocpt_{}.json", new_file_id);
-    stdfs::write(&out_path, ocpt_json).unwrap();
-
-    println!("✅ OCPT saved to {} (new file_id = {})", out_path, new_file_id);
-
-    // Return the new id so caller can propagate it
-    new_file_id
+    serde_json::to_string_pretty(&ocpt_output).unwrap()
 }
 
 fn filter_dfg(
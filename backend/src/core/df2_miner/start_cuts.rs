@@ -1,109 +1,50 @@
 use std::collections::{HashMap, HashSet};
-use crate::models::ocpt::{TreeNode, ProcessForest};
+use crate::core::df2_miner::cuts::Cut;
 use itertools::Itertools;
-use log::info;
 
-pub fn find_cuts(
-    dfg: &HashMap<(String, String), usize>,
+/// Finds a cut of the (already filtered) DFG by checking every bipartition of the activities
+/// against the exact cut definitions below. Exclusive choice, sequence, parallel and redo cuts
+/// are tried in this order, like in `start_cuts_opti::find_cut_optimised`.
+///
+/// The search is exponential in the number of activities.
+pub fn find_cut_exact(
     filtered_dfg: &HashMap<(String, String), usize>,
-    all_activities: HashSet<String>,
+    all_activities: &HashSet<String>,
     start_activities: &HashSet<String>,
-    end_activities: &HashSet<String>
-) -> ProcessForest {
-    let mut forest = Vec::new();
-
-    let activities: Vec<String> = all_activities.clone().into_iter().collect();
-    let n = activities.len();
-
-    if n == 1 {
-        // Base case: single activity, create a leaf node
-        let node = TreeNode {
-            label: activities[0].clone(),
-            children: Vec::new(),
-        };
-        forest.push(node);
-        return forest;
-    }
-
-    for i in 1..n {
-        for combo in activities.iter().combinations(i) {
+    end_activities: &HashSet<String>,
+) -> Option<Cut> {
+    let mut activities: Vec<String> = all_activities.iter().cloned().collect();
+    activities.sort();
+    let bipartitions = || {
+        (1..activities.len()).flat_map(|i| activities.iter().combinations(i)).map(|combo| {
             let combo_set: HashSet<String> = combo.into_iter().cloned().collect();
-            let complement_set: HashSet<String> = all_activities
-                .difference(&combo_set)
-                .cloned()
-                .collect();
-
-            let combined_activites: HashSet<String> = combo_set.union(&complement_set).cloned().collect();
-            let filtered_dfg = filter_keep_dfg(&dfg, &combined_activites);
-
-            let (filtered_start_activites,filtered_end_activites)=get_start_and_end_activities_v2(&dfg, &combined_activites, start_activities, end_activities);
-            
-            // info!("Checking cut: {:?} (.....) {:?}", combo_set, complement_set);
-
-            let excl_cut = is_exclusive_choice_cut_possible(&filtered_dfg, &combo_set, &complement_set);
-            if excl_cut {
-                info!("Excl parallel cut found: {:?} (X) {:?}", combo_set, complement_set);
-                let mut node = TreeNode {
-                    label: "excl".to_string(),
-                    children: Vec::new(),
-                };
-                node.children.extend(find_cuts(&dfg, &filtered_dfg, combo_set, &start_activities, &end_activities));
-                node.children.extend(find_cuts(&dfg, &filtered_dfg, complement_set, &start_activities, &end_activities));
-                forest.push(node);
-                return forest; // Return early if you only want the first valid cut
-            }
-
-            let seq_cut = is_sequence_cut_possible(&filtered_dfg, &combo_set, &complement_set);
-            if seq_cut {
-                info!("Seq parallel cut found: {:?} (->) {:?}", combo_set, complement_set);
-                let mut node = TreeNode {
-                    label: "seq".to_string(),
-                    children: Vec::new(),
-                };
-                node.children.extend(find_cuts(&dfg, &filtered_dfg, combo_set, &start_activities, &end_activities));
-                node.children.extend(find_cuts(&dfg, &filtered_dfg, complement_set, &start_activities, &end_activities));
-                forest.push(node);
-                return forest; // Return early if you only want the first valid cut
-            }
-
-            let para_cut = is_parallel_cut_possible(&filtered_dfg, &combo_set, &complement_set, &filtered_start_activites, &filtered_end_activites);
-            if para_cut {
-                info!("Parallel cut found: {:?} (||) {:?}", combo_set, complement_set);
-                let mut node = TreeNode {
-                    label: "para".to_string(),
-                    children: Vec::new(),
-                };
-                node.children.extend(find_cuts(&dfg, &filtered_dfg, combo_set, &start_activities, &end_activities));
-                node.children.extend(find_cuts(&dfg, &filtered_dfg, complement_set, &start_activities, &end_activities));
-                forest.push(node);
-                return forest; // Return early if you only want the first valid cut
-            }
-
-            let redo_cut = is_redo_cut_possible(&filtered_dfg, &combo_set, &complement_set, &filtered_start_activites, &filtered_end_activites);
-            if redo_cut {
-                info!("Redo cut found: {:?} (O->) {:?}", combo_set, complement_set);
-                let mut node = TreeNode {
-                    label: "redo".to_string(),
-                    children: Vec::new(),
-                };
-                node.children.extend(find_cuts(&dfg, &filtered_dfg, combo_set, &start_activities, &end_activities));
-                node.children.extend(find_cuts(&dfg, &filtered_dfg, complement_set, &start_activities, &end_activities));
-                forest.push(node);
-                return forest; // Return early if you only want the first valid cut
-            }
-        }
-    }
-
-    // If no valid cuts are found, return disjoint trees
-    for activity in activities {
-        let node = TreeNode {
-            label: activity,
-            children: Vec::new(),
-        };
-        forest.push(node);
-    }
-
-    forest
+            let complement_set: HashSet<String> = all_activities.difference(&combo_set).cloned().collect();
+            (combo_set, complement_set)
+        })
+    };
+
+    bipartitions()
+        .find(|(set_1, set_2)| is_exclusive_choice_cut_possible(filtered_dfg, set_1, set_2))
+        .map(|(set_1, set_2)| Cut::Exclusive(set_1, set_2))
+        .or_else(|| {
+            bipartitions()
+                .find(|(set_1, set_2)| is_sequence_cut_possible(filtered_dfg, set_1, set_2))
+                .map(|(set_1, set_2)| Cut::Sequence(set_1, set_2))
+        })
+        .or_else(|| {
+            bipartitions()
+                .find(|(set_1, set_2)| {
+                    is_parallel_cut_possible(filtered_dfg, set_1, set_2, start_activities, end_activities)
+                })
+                .map(|(set_1, set_2)| Cut::Parallel(set_1, set_2))
+        })
+        .or_else(|| {
+            bipartitions()
+                .find(|(set_1, set_2)| {
+                    is_redo_cut_possible(filtered_dfg, set_1, set_2, start_activities, end_activities)
+                })
+                .map(|(set_1, set_2)| Cut::Redo(set_1, set_2))
+        })
 }
 
 pub fn is_reachable(
@@ -132,18 +73,6 @@ pub fn is_reachable(
     false
 }
 
-fn filter_keep_dfg(
-    dfg: &HashMap<(String, String), usize>,
-    keep_list: &HashSet<String>,
-) -> HashMap<(String, String), usize> {
-    dfg.iter()
-        .filter(|((from, to), _)| {
-            keep_list.contains(from) && keep_list.contains(to)
-        })
-        .map(|(k, v)| (k.clone(), *v))
-        .collect()
-}
-
 
 pub fn is_sequence_cut_possible(
     dfg: &HashMap<(String, String), usize>,
@@ -187,17 +116,13 @@ pub fn is_exclusive_choice_cut_possible(
     true
 }
 
-fn is_parallel_cut_possible(
+pub fn is_parallel_cut_possible(
     dfg: &HashMap<(String, String), usize>,
     set_1_activities: &HashSet<String>,
     set_2_activities: &HashSet<String>,
     start_activities: &HashSet<String>,
     end_activities: &HashSet<String>,
 ) -> bool {
-    info!("Checking parallel cut...");
-    info!("Start activities: {:?}", start_activities);
-    info!("End activities: {:?}", end_activities);
-
     // 1. There must be some common activities between set_1_activities and start_activities
     if set_1_activities.is_disjoint(start_activities) {
         return false;
@@ -230,7 +155,7 @@ fn is_parallel_cut_possible(
     true
 }
 
-fn is_redo_cut_possible(
+pub fn is_redo_cut_possible(
     dfg: &HashMap<(String, String), usize>,
     set_1_activities: &HashSet<String>,
     set_2_activities: &HashSet<String>,
@@ -324,79 +249,3 @@ fn is_redo_cut_possible(
 
     true
 }
-
-
-fn get_start_and_end_activities(
-    dfg: &HashMap<(String, String), usize>,
-    filtered_activities: &HashSet<String>,
-) -> (HashSet<String>, HashSet<String>) {
-    let mut start_activities = HashSet::new();
-    let mut end_activities = HashSet::new();
-
-    // info!("Getting start and end activities based on filtered set...");
-
-    for ((from, to), _) in dfg {
-        let from_in = filtered_activities.contains(from);
-        let to_in = filtered_activities.contains(to);
-
-        if to_in && !from_in {
-            // 'to' is inside filtered, 'from' is outside → 'to' is a start activity
-            start_activities.insert(to.clone());
-        }
-
-        if from_in && !to_in {
-            // 'from' is inside filtered, 'to' is outside → 'from' is an end activity
-            end_activities.insert(from.clone());
-        }
-    }
-
-    // info!("Start activities: {:?}", start_activities);
-    // info!("End activities: {:?}", end_activities);
-
-    (start_activities, end_activities)
-}
-
-fn get_start_and_end_activities_v2(
-    dfg: &HashMap<(String, String), usize>,
-    filtered_activities: &HashSet<String>,
-    global_start_activities: &HashSet<String>,
-    global_end_activities: &HashSet<String>,
-) -> (HashSet<String>, HashSet<String>) {
-    let mut start_activities = HashSet::new();
-    let mut end_activities = HashSet::new();
-
-    for ((a, b), _) in dfg {
-        let a_in = filtered_activities.contains(a);
-        let b_in = filtered_activities.contains(b);
-
-        if !a_in && b_in {
-            // 'a' is outside and 'b' is inside → 'b' is a start activity
-            start_activities.insert(b.clone());
-        }
-
-        if a_in && !b_in {
-            // 'a' is inside and 'b' is outside → 'a' is an end activity
-            end_activities.insert(a.clone());
-        }
-    }
-
-    // Add common activities from global sets
-    for activity in filtered_activities {
-        if global_start_activities.contains(activity) {
-            start_activities.insert(activity.clone());
-        }
-        if global_end_activities.contains(activity) {
-            end_activities.insert(activity.clone());
-        }
-    }
-
-    (start_activities, end_activities)
-}
-
-
-
-
-
-
-
-
@@ -1,161 +1,56 @@
-use crate::{core::df2_miner::start_cuts::is_reachable, models::ocpt::{ProcessForest, TreeNode}};
-use itertools::Itertools;
-use log::info;
+use crate::core::df2_miner::cuts::Cut;
+use crate::core::df2_miner::start_cuts::is_reachable;
 use std::collections::{HashMap, HashSet, VecDeque};
 use crate::core::df2_miner::start_cuts::{is_exclusive_choice_cut_possible, is_sequence_cut_possible};
 
-pub fn find_cuts_start(
-    dfg: &HashMap<(String, String), usize>,
+/// Finds a cut of the (already filtered) DFG with the heuristics below, trying exclusive choice,
+/// sequence, parallel and redo cuts in this order
+pub fn find_cut_optimised(
+    filtered_dfg: &HashMap<(String, String), usize>,
     all_activities: &HashSet<String>,
     start_activities: &HashSet<String>,
     end_activities: &HashSet<String>,
-) -> ProcessForest {
-    let mut forest = Vec::new();
-
-    let activities: Vec<String> = all_activities.clone().into_iter().collect();
-    let n = activities.len();
-
-    if n == 1 {
-        // Base case: single activity, create a leaf node
-        let node = TreeNode {
-            label: activities[0].clone(),
-            children: Vec::new(),
-        };
-        forest.push(node);
-        return forest;
-    }
-
-    let filtered_dfg = filter_keep_dfg(&dfg, &all_activities);
-    let (start_activities, end_activities) =
-        get_start_and_end_activities(&dfg, &all_activities, &start_activities, &end_activities);
-
-    // ----- perform cuts--------
-
-    let (excl_set1, excl_set2) = find_exclusive_choice_cut(&filtered_dfg, &all_activities);
-    if (!excl_set1.is_empty() && !excl_set2.is_empty() && is_exclusive_choice_cut_possible(&filtered_dfg, &excl_set1, &excl_set2)) {
-        info!("Exclusive cut found: {:?} (X) {:?}", excl_set1, excl_set2);
-        let mut node = TreeNode {
-            label: "excl".to_string(),
-            children: Vec::new(),
-        };
-        node.children.extend(find_cuts_start(
-            &dfg,
-            &excl_set1,
-            &start_activities,
-            &end_activities,
-        ));
-        node.children.extend(find_cuts_start(
-            &dfg,
-            &excl_set2,
-            &start_activities,
-            &end_activities,
-        ));
-        forest.push(node);
-        return forest;
-    }
-
-    let (set1, set2) = find_sequence_cut(&filtered_dfg, &all_activities);
-    if (!set1.is_empty() && !set2.is_empty() && is_sequence_cut_possible(&filtered_dfg, &set1, &set2)) {
-        info!("Sequence cut found: {:?} (->) {:?}", set1, set2);
-        let mut node = TreeNode {
-            label: "seq".to_string(),
-            children: Vec::new(),
-        };
-        node.children.extend(find_cuts_start(
-            &dfg,
-            &set1,
-            &start_activities,
-            &end_activities,
-        ));
-        node.children.extend(find_cuts_start(
-            &dfg,
-            &set2,
-            &start_activities,
-            &end_activities,
-        ));
-        forest.push(node);
-        return forest;
-    }
-
-    let (is_parallel, para_set1, para_set2) = find_parallel_cut(&filtered_dfg, &all_activities);
-    if (is_parallel
+) -> Option<Cut> {
+    let (excl_set1, excl_set2) = find_exclusive_choice_cut(filtered_dfg, all_activities);
+    if !excl_set1.is_empty() && !excl_set2.is_empty() && is_exclusive_choice_cut_possible(filtered_dfg, &excl_set1, &excl_set2) {
+        return Some(Cut::Exclusive(excl_set1, excl_set2));
+    }
+
+    let (set1, set2) = find_sequence_cut(filtered_dfg, all_activities);
+    if !set1.is_empty() && !set2.is_empty() && is_sequence_cut_possible(filtered_dfg, &set1, &set2) {
+        return Some(Cut::Sequence(set1, set2));
+    }
+
+    let (is_parallel, para_set1, para_set2) = find_parallel_cut(filtered_dfg, all_activities);
+    if is_parallel
         && !para_set1.is_empty()
         && !para_set2.is_empty()
-        && parallel_cut_condition_check(&para_set1, &para_set2, &start_activities, &end_activities))
+        && parallel_cut_condition_check(&para_set1, &para_set2, start_activities, end_activities)
     {
-        info!("Parallel cut found: {:?} (||) {:?}", para_set1, para_set2);
-        let mut node = TreeNode {
-            label: "para".to_string(),
-            children: Vec::new(),
-        };
-        node.children.extend(find_cuts_start(
-            &dfg,
-            &para_set1,
-            &start_activities,
-            &end_activities,
-        ));
-        node.children.extend(find_cuts_start(
-            &dfg,
-            &para_set2,
-            &start_activities,
-            &end_activities,
-        ));
-        forest.push(node);
-        return forest;
+        return Some(Cut::Parallel(para_set1, para_set2));
     }
 
     let (is_redo, redo_set1, redo_set2) = find_redo_cut(
-        &filtered_dfg,
-        &all_activities,
-        &start_activities,
-        &end_activities,
+        filtered_dfg,
+        all_activities,
+        start_activities,
+        end_activities,
     );
-    if (is_redo
+    if is_redo
         && !redo_set2.is_empty()
         && !redo_set1.is_empty()
         && redo_cut_condition_check(
-            &filtered_dfg,
+            filtered_dfg,
             &redo_set1,
             &redo_set2,
-            &start_activities,
-            &end_activities,
-        ))
+            start_activities,
+            end_activities,
+        )
     {
-        info!("Redo cut found: {:?} (R) {:?}", redo_set1, redo_set2);
-        let mut node = TreeNode {
-            label: "redo".to_string(),
-            children: Vec::new(),
-        };
-        node.children.extend(find_cuts_start(
-            &dfg,
-            &redo_set1,
-            &start_activities,
-            &end_activities,
-        ));
-        node.children.extend(find_cuts_start(
-            &dfg,
-            &redo_set2,
-            &start_activities,
-            &end_activities,
-        ));
-        forest.push(node);
-        return forest;
+        return Some(Cut::Redo(redo_set1, redo_set2));
     }
 
-    info!(
-        "No further cuts found for the current set of activities: {:?}",
-        all_activities
-    );
-    // If no valid cuts are found, return disjoint trees
-    for activity in activities {
-        let node = TreeNode {
-            label: activity,
-            children: Vec::new(),
-        };
-        forest.push(node);
-    }
-
-    forest
+    None
 }
 
 // Exclusive cut and helpers --------------
@@ -185,7 +80,7 @@ fn find_exclusive_choice_cut(
     let mut visited: HashSet<String> = HashSet::new();
     let mut components: Vec<HashSet<String>> = Vec::new();
 
-    for activity in all_activities {
+    for activity in sorted(all_activities) {
         if !visited.contains(activity) {
             let mut component = HashSet::new();
             let mut queue = VecDeque::new();
@@ -252,7 +147,11 @@ fn find_sequence_cut(
     dfg: &HashMap<(String, String), usize>,
     all_activities: &HashSet<String>,
 ) -> (HashSet<String>, HashSet<String>) {
-    let sccs = strongly_connected_components(&dfg, &all_activities);
+    // Order the SCCs by their sorted activities, so that the partition does not depend on the
+    // iteration order of the sets
+    let mut sccs = strongly_connected_components(&dfg, &all_activities);
+    sccs.iter_mut().for_each(|scc| scc.sort());
+    sccs.sort();
     // println!("SCCs:");
     // for (i, comp) in sccs.iter().enumerate() {
     //     println!("  SCC {}: {:?}", i, comp);
@@ -402,7 +301,8 @@ pub fn partition_scc_sets(
 
     // Find common activities and remove them from both sets
     let intersection: HashSet<_> = set1.intersection(&set2).cloned().collect();
-    let mut common_activities = intersection.clone();
+    let mut common_activities: Vec<usize> = intersection.iter().cloned().collect();
+    common_activities.sort();
 
     for i in &intersection {
         set1.remove(i);
@@ -497,7 +397,7 @@ fn find_parallel_cut(
     let mut set1: HashSet<String> = HashSet::new();
     let mut set2: HashSet<String> = HashSet::new();
 
-    for act in all_activities {
+    for act in sorted(all_activities) {
         if set1.is_empty() {
             set1.insert(act.clone());
             continue;
@@ -656,14 +556,11 @@ fn redo_cut_condition_check(
 
 // --------------------- common helpers ---------------------
 
-fn filter_keep_dfg(
-    dfg: &HashMap<(String, String), usize>,
-    keep_list: &HashSet<String>,
-) -> HashMap<(String, String), usize> {
-    dfg.iter()
-        .filter(|((from, to), _)| keep_list.contains(from) && keep_list.contains(to))
-        .map(|(k, v)| (k.clone(), *v))
-        .collect()
+/// The activities in sorted order, for a deterministic tie-breaking of the greedy cut search
+fn sorted(activities: &HashSet<String>) -> Vec<&String> {
+    let mut sorted: Vec<&String> = activities.iter().collect();
+    sorted.sort();
+    sorted
 }
 
 fn check_bi_direction_sets(
@@ -683,43 +580,6 @@ fn check_bi_direction_sets(
     true
 }
 
-fn get_start_and_end_activities(
-    dfg: &HashMap<(String, String), usize>,
-    filtered_activities: &HashSet<String>,
-    global_start_activities: &HashSet<String>,
-    global_end_activities: &HashSet<String>,
-) -> (HashSet<String>, HashSet<String>) {
-    let mut start_activities = HashSet::new();
-    let mut end_activities = HashSet::new();
-
-    for ((a, b), _) in dfg {
-        let a_in = filtered_activities.contains(a);
-        let b_in = filtered_activities.contains(b);
-
-        if !a_in && b_in {
-            // 'a' is outside and 'b' is inside → 'b' is a start activity
-            start_activities.insert(b.clone());
-        }
-
-        if a_in && !b_in {
-            // 'a' is inside and 'b' is outside → 'a' is an end activity
-            end_activities.insert(a.clone());
-        }
-    }
-
-    // Add common activities from global sets
-    for activity in filtered_activities {
-        if global_start_activities.contains(activity) {
-            start_activities.insert(activity.clone());
-        }
-        if global_end_activities.contains(activity) {
-            end_activities.insert(activity.clone());
-        }
-    }
-
-    (start_activities, end_activities)
-}
-
 pub fn is_reachable_before_end_activity(
     start_activities: &HashSet<String>,
     target: &String,
@@ -0,0 +1,160 @@
+//! Discovery of object-centric directly-follows graphs with object-type selection and
+//! frequency thresholds, and their conversion to the [`Graph`] node/edge shape.
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+use process_mining::dfg::dfg_struct::DirectlyFollowsGraph;
+use process_mining::event_log::event_log_struct::EventLogClassifier;
+use process_mining::ocel::linked_ocel::LinkedOCELAccess;
+
+use crate::core::utils::flatten::flatten_ocel_on;
+use crate::models::dfg::{Edge, Graph, IndexLinkedOCEL, Node, OCDirectlyFollowsGraph};
+
+/// Id of the artificial start node in [`dfg_to_graph`]
+pub const START_NODE_ID: &str = "__start__";
+/// Id of the artificial end node in [`dfg_to_graph`]
+pub const END_NODE_ID: &str = "__end__";
+
+/// Options for [`discover_oc_dfg`]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DfgOptions {
+    /// Comma-separated object types to discover DFGs for (default: all object types)
+    pub object_types: Option<String>,
+    /// Activities occurring less often for an object type are removed with their relations
+    pub min_activity_frequency: u32,
+    /// Directly-follows relations occurring less often are removed
+    pub min_edge_frequency: u32,
+}
+
+impl DfgOptions {
+    /// The selected object types, or an error naming the first one not in the log
+    pub fn selected_object_types(&self, locel: &IndexLinkedOCEL) -> Result<BTreeSet<String>, String> {
+        let known: BTreeSet<String> = locel.get_ob_types().map(|ot| ot.to_string()).collect();
+        let Some(selection) = &self.object_types else {
+            return Ok(known);
+        };
+        selection
+            .split(',')
+            .map(str::trim)
+            .filter(|ot| !ot.is_empty())
+            .map(|ot| {
+                if known.contains(ot) {
+                    Ok(ot.to_string())
+                } else {
+                    Err(format!("Unknown object type: {ot}"))
+                }
+            })
+            .collect()
+    }
+}
+
+/// Discovers the DFG of each selected object type and applies the frequency thresholds
+pub fn discover_oc_dfg(
+    locel: &IndexLinkedOCEL,
+    options: &DfgOptions,
+) -> Result<OCDirectlyFollowsGraph<'static>, String> {
+    let mut result = OCDirectlyFollowsGraph::new();
+    for ob_type in options.selected_object_types(locel)? {
+        let event_log = flatten_ocel_on(locel, &ob_type);
+        let mut dfg = DirectlyFollowsGraph::create_from_log(&event_log, &EventLogClassifier::default());
+        apply_thresholds(&mut dfg, options);
+        result.object_type_to_dfg.insert(ob_type, dfg);
+    }
+    Ok(result)
+}
+
+/// Removes infrequent activities (with their relations) and infrequent relations
+pub fn apply_thresholds(dfg: &mut DirectlyFollowsGraph, options: &DfgOptions) {
+    let infrequent: Vec<String> = dfg
+        .activities
+        .iter()
+        .filter(|(_, frequency)| **frequency < options.min_activity_frequency)
+        .map(|(activity, _)| activity.clone())
+        .collect();
+    for activity in infrequent {
+        dfg.remove_activity(activity);
+    }
+    dfg.directly_follows_relations
+        .retain(|_, frequency| *frequency >= options.min_edge_frequency);
+}
+
+/// Converts a DFG into the node/edge shape rendered by the frontend. Activities are labelled
+/// with their frequency, relations with theirs; start and end activities are connected to
+/// artificial start and end nodes.
+pub fn dfg_to_graph(dfg: &DirectlyFollowsGraph) -> Graph {
+    let activities: BTreeMap<&String, &u32> = dfg.activities.iter().collect();
+    let mut nodes = vec![Node { id: START_NODE_ID.to_string(), label: "start".to_string() }];
+    nodes.extend(activities.iter().map(|(activity, frequency)| Node {
+        id: activity.to_string(),
+        label: format!("{activity} ({frequency})"),
+    }));
+    nodes.push(Node { id: END_NODE_ID.to_string(), label: "end".to_string() });
+
+    let edge = |source: &str, target: &str, label: String| Edge {
+        id: format!("{source}->{target}"),
+        source: source.to_string(),
+        target: target.to_string(),
+        label,
+    };
+    let relations: BTreeMap<(&str, &str), &u32> = dfg
+        .directly_follows_relations
+        .iter()
+        .map(|((from, to), frequency)| ((from.as_ref(), to.as_ref()), frequency))
+        .collect();
+    let mut edges: Vec<Edge> = dfg
+        .start_activities
+        .iter()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|activity| edge(START_NODE_ID, activity, String::new()))
+        .collect();
+    edges.extend(relations.into_iter().map(|((from, to), frequency)| edge(from, to, frequency.to_string())));
+    edges.extend(
+        dfg.end_activities
+            .iter()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|activity| edge(activity, END_NODE_ID, String::new())),
+    );
+
+    Graph { nodes, edges }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thresholds_and_graph() {
+        let mut dfg = DirectlyFollowsGraph::new();
+        dfg.add_activity("place".to_string(), 10);
+        dfg.add_activity("pay".to_string(), 8);
+        dfg.add_activity("cancel".to_string(), 2);
+        dfg.add_df_relation("place".into(), "pay".into(), 7);
+        dfg.add_df_relation("pay".into(), "place".into(), 1);
+        dfg.add_df_relation("place".into(), "cancel".into(), 2);
+        dfg.add_start_activity("place".to_string());
+        dfg.add_end_activity("pay".to_string());
+        dfg.add_end_activity("cancel".to_string());
+
+        let options = DfgOptions { object_types: None, min_activity_frequency: 3, min_edge_frequency: 2 };
+        apply_thresholds(&mut dfg, &options);
+        assert!(!dfg.contains_activity("cancel"));
+        assert!(!dfg.is_end_activity("cancel"));
+        assert_eq!(dfg.directly_follows_relations.len(), 1);
+
+        let graph = dfg_to_graph(&dfg);
+        let labels: Vec<&str> = graph.nodes.iter().map(|n| n.label.as_str()).collect();
+        assert_eq!(labels, vec!["start", "pay (8)", "place (10)", "end"]);
+        let edges: Vec<(&str, &str, &str)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.source.as_str(), e.target.as_str(), e.label.as_str()))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![(START_NODE_ID, "place", ""), ("place", "pay", "7"), ("pay", END_NODE_ID, "")]
+        );
+    }
+}
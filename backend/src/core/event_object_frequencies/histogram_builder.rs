@@ -1,6 +1,6 @@
 use serde::Serialize;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use process_mining::OCEL;
 
 #[derive(Serialize)]
@@ -111,35 +111,193 @@ pub fn build_event_object_histograms(log: &OCEL) -> Value {
     serde_json::to_value(&result).unwrap()
 }
 
+#[derive(Serialize)]
+struct ObjectHistogramEntry {
+    object_type: String,
+    event_type: String,
+    histogram: Vec<HistogramBin>,
+}
+
+#[derive(Serialize)]
+struct ObjectHistogramResult {
+    histograms: Vec<ObjectHistogramEntry>,
+}
+
+/*
+Example JSON output:
+one histogram per (object_type, event_type) pair
+    - histogram x: count (number of events of event_type the object is related to)
+    - histogram y: frequency (number of objects of object_type with that count)
+    - objects of object_type that never take part in event_type are reported with count 0
+
+{
+  "histograms": [
+    {
+      "object_type": "Container",
+      "event_type": "Load Truck",
+      "histogram": [
+        { "count": 0, "frequency": 1 },
+        { "count": 1, "frequency": 6 },
+        { "count": 3, "frequency": 2 }
+      ]
+    }
+  ]
+}
+
+*/
+
+/// Counts for every object how many events of each event type it is related to.
+///
+/// Events relating the same object more than once (e.g. with different qualifiers) are counted once.
+pub(crate) fn count_events_per_object(log: &OCEL) -> HashMap<&str, HashMap<&str, usize>> {
+    let mut counts: HashMap<&str, HashMap<&str, usize>> = HashMap::new();
+
+    for event in &log.events {
+        let mut seen: HashSet<&str> = HashSet::new();
+        for rel in &event.relationships {
+            if seen.insert(rel.object_id.as_str()) {
+                *counts
+                    .entry(rel.object_id.as_str())
+                    .or_default()
+                    .entry(event.event_type.as_str())
+                    .or_insert(0) += 1;
+            }
+        }
+    }
+
+    counts
+}
+
+/// Builds one histogram per (object_type, event_type) pair describing how many events of the
+/// event type each object of the object type participates in.
+///
+/// This is the dual of [`build_event_object_histograms`]: a count above one marks the
+/// object type as divergent for the event type.
+pub fn build_object_event_histograms(log: &OCEL) -> Value {
+    let counts = count_events_per_object(log);
+
+    // Only (object_type, event_type) pairs that occur at least once get a histogram
+    let object_index = build_object_index(log);
+    let mut pairs: HashSet<(&str, &str)> = HashSet::new();
+    for event in &log.events {
+        for rel in &event.relationships {
+            if let Some(&otype) = object_index.get(rel.object_id.as_str()) {
+                pairs.insert((otype, event.event_type.as_str()));
+            }
+        }
+    }
+
+    let mut stats: HashMap<(&str, &str), HashMap<usize, usize>> = HashMap::new();
+    for obj in &log.objects {
+        let per_event_type = counts.get(obj.id.as_str());
+        for &(otype, etype) in pairs.iter().filter(|(otype, _)| *otype == obj.object_type) {
+            let count = per_event_type
+                .and_then(|m| m.get(etype))
+                .copied()
+                .unwrap_or(0);
+            *stats
+                .entry((otype, etype))
+                .or_default()
+                .entry(count)
+                .or_insert(0) += 1;
+        }
+    }
+
+    let mut histograms: Vec<ObjectHistogramEntry> = stats
+        .into_iter()
+        .map(|((otype, etype), hist)| {
+            let mut bins: Vec<HistogramBin> = hist
+                .into_iter()
+                .map(|(count, freq)| HistogramBin { count, frequency: freq })
+                .collect();
+            bins.sort_by_key(|bin| bin.count);
+
+            ObjectHistogramEntry {
+                object_type: otype.to_string(),
+                event_type: etype.to_string(),
+                histogram: bins,
+            }
+        })
+        .collect();
+
+    histograms.sort_by(|a, b| {
+        a.object_type
+            .cmp(&b.object_type)
+            .then_with(|| a.event_type.cmp(&b.event_type))
+    });
+
+    serde_json::to_value(ObjectHistogramResult { histograms }).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tokio;
-    use std::path::PathBuf;
-    use tokio::fs as tokio_fs;
 
-    #[tokio::test]
-    async fn test_build_event_object_histograms() {
-        // 1. Build the path to your OCEL JSON
-        let ocel_path = PathBuf::from("./temp/ocel_v2_123.json");
+    #[test]
+    fn test_build_event_object_histograms() {
+        let ocel = crate::tests::orders_ocel();
 
-        // 2. Read the file
-        let ocel_data = tokio_fs::read_to_string(&ocel_path)
-            .await
-            .expect("failed to read OCEL JSON file");
+        let histogram = build_event_object_histograms(&ocel);
+
+        let histograms = histogram["histograms"].as_array().unwrap();
+        assert_eq!(histograms.len(), 6);
+        let place_order_items = histograms
+            .iter()
+            .find(|h| h["event_type"] == "place order" && h["object_type"] == "item")
+            .unwrap();
+        assert_eq!(
+            place_order_items["histogram"],
+            serde_json::json!([
+                { "count": 1, "frequency": 1 },
+                { "count": 2, "frequency": 1 },
+                { "count": 3, "frequency": 1 }
+            ])
+        );
+        crate::tests::assert_golden("orders_event_object_histograms", &histogram);
+    }
 
-        // 3. Deserialize into OCEL
-        let ocel: OCEL = serde_json::from_str(&ocel_data)
-            .expect("failed to parse OCEL JSON");
+    #[test]
+    fn test_build_object_event_histograms() {
+        use chrono::{DateTime, FixedOffset};
+        use process_mining::ocel::ocel_struct::{OCELEvent, OCELObject, OCELRelationship};
 
-        // 4. Build the histogram
-        let histogram = build_event_object_histograms(&ocel);
+        let t: DateTime<FixedOffset> = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap();
+        let object = |id: &str, ot: &str| OCELObject {
+            id: id.to_string(),
+            object_type: ot.to_string(),
+            attributes: Vec::new(),
+            relationships: Vec::new(),
+        };
+        let rel = |id: &str| OCELRelationship::new(id, "");
+        let ocel = OCEL {
+            event_types: Vec::new(),
+            object_types: Vec::new(),
+            events: vec![
+                OCELEvent::new("e1", "Load", t, Vec::new(), vec![rel("c1"), rel("t1")]),
+                OCELEvent::new("e2", "Load", t, Vec::new(), vec![rel("c2"), rel("t1")]),
+                OCELEvent::new("e3", "Depart", t, Vec::new(), vec![rel("t1")]),
+            ],
+            objects: vec![object("c1", "Container"), object("c2", "Container"), object("c3", "Container"), object("t1", "Truck")],
+        };
 
-        // 5. Serialize to pretty JSON string
-        let json_str = serde_json::to_string_pretty(&histogram)
-            .expect("failed to serialize histogram");
+        let histogram = build_object_event_histograms(&ocel);
 
-        // 6. Print to console
-        println!("{}", json_str);
+        assert_eq!(
+            histogram,
+            serde_json::json!({
+                "histograms": [
+                    { "object_type": "Container", "event_type": "Load", "histogram": [
+                        { "count": 0, "frequency": 1 },
+                        { "count": 1, "frequency": 2 }
+                    ]},
+                    { "object_type": "Truck", "event_type": "Depart", "histogram": [
+                        { "count": 1, "frequency": 1 }
+                    ]},
+                    { "object_type": "Truck", "event_type": "Load", "histogram": [
+                        { "count": 2, "frequency": 1 }
+                    ]}
+                ]
+            })
+        );
     }
 }
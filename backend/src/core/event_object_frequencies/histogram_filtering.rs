@@ -1,23 +1,47 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use process_mining::OCEL;
 
-/// JSON structs for deserializing the user selection
-#[derive(Deserialize)]
-struct Selection {
-    name: Option<String>,
-    filters: Vec<Filter>,
+use crate::core::event_object_frequencies::histogram_builder::count_events_per_object;
+
+/// JSON structs for (de)serializing the user selection
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Selection {
+    pub(crate) name: Option<String>,
+    pub(crate) filters: Vec<Filter>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Filter {
+    pub(crate) event_type: String,
+    pub(crate) object_type: String,
+    pub(crate) ranges: Vec<[usize; 2]>, // list of [min, max] intervals
+    #[serde(default)]
+    pub(crate) direction: HistogramDirection,
 }
 
-#[derive(Deserialize)]
-struct Filter {
-    event_type: String,
-    object_type: String,
-    ranges: Vec<[usize; 2]>, // list of [min, max] intervals
+/// Which histogram a filter refers to
+#[derive(Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum HistogramDirection {
+    /// Number of objects of `object_type` per event of `event_type`
+    #[default]
+    EventToObject,
+    /// Number of events of `event_type` per object of `object_type`
+    ObjectToEvent,
+}
+
+impl Filter {
+    fn matches(&self, count: usize) -> bool {
+        self.ranges
+            .iter()
+            .any(|range| count >= range[0] && count <= range[1])
+    }
 }
 
-#[derive(Deserialize)]
-struct SelectionPayload {
-    selections: Vec<Selection>,
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SelectionPayload {
+    pub(crate) selections: Vec<Selection>,
 }
 
 /* 
@@ -48,30 +72,82 @@ one selection per output OCEL
   ]
 }
 
+
+Filters with "direction": "object_to_event" refer to the object-to-event histograms instead:
+    - objects of object_type whose number of event_type events lies outside all ranges are removed
+    - their E2O and O2O relationships are removed as well
+
+In both directions, events left without any relationship are dropped.
+
+        {
+          "event_type": "Load Truck",
+          "object_type": "Container",
+          "direction": "object_to_event",
+          "ranges": [[1, 1]]           // keep containers loaded exactly once
+        }
+
   */
 
 /// Returns a Vec of filtered OCELs according to the user selection JSON
-pub fn filter_ocel_histograms(log: &OCEL, filters_json: &str) -> Vec<OCEL> {
+///
+/// # Errors
+/// - The selection JSON is malformed (e.g. an unknown `direction`).
+pub fn filter_ocel_histograms(log: &OCEL, filters_json: &str) -> Result<Vec<OCEL>, serde_json::Error> {
     // 1. Deserialize the JSON payload
-    let payload: SelectionPayload = serde_json::from_str(filters_json)
-        .expect("Invalid JSON for filters");
+    let payload: SelectionPayload = serde_json::from_str(filters_json)?;
 
     // 2. Precompute object_id -> object_type map
-    let object_index: std::collections::HashMap<&str, &str> = log.objects
+    let object_index: HashMap<&str, &str> = log.objects
         .iter()
         .map(|obj| (obj.id.as_str(), obj.object_type.as_str()))
         .collect();
 
+    // Object-to-event counts are only needed if any filter refers to them
+    let needs_object_counts = payload
+        .selections
+        .iter()
+        .flat_map(|selection| &selection.filters)
+        .any(|filter| filter.direction == HistogramDirection::ObjectToEvent);
+    let events_per_object = if needs_object_counts {
+        count_events_per_object(log)
+    } else {
+        HashMap::new()
+    };
+
     let mut result: Vec<OCEL> = Vec::new();
 
     // 3. Iterate over selections
     for selection in payload.selections {
+        let (event_filters, object_filters): (Vec<&Filter>, Vec<&Filter>) = selection
+            .filters
+            .iter()
+            .partition(|filter| filter.direction == HistogramDirection::EventToObject);
+
+        // 3a. Determine the objects removed by object-to-event filters
+        let removed_objects: HashSet<&str> = log.objects
+            .iter()
+            .filter(|obj| {
+                object_filters.iter().any(|filter| {
+                    if obj.object_type != filter.object_type {
+                        return false;
+                    }
+                    let count = events_per_object
+                        .get(obj.id.as_str())
+                        .and_then(|m| m.get(filter.event_type.as_str()))
+                        .copied()
+                        .unwrap_or(0);
+                    !filter.matches(count)
+                })
+            })
+            .map(|obj| obj.id.as_str())
+            .collect();
+
         let mut filtered_events: Vec<_> = Vec::new();
 
-        // 3a. Iterate over all events in the log
+        // 3b. Iterate over all events in the log
         'event_loop: for event in &log.events {
             // Check if event matches any filter in this selection
-            for filter in &selection.filters {
+            for filter in &event_filters {
                 if event.event_type != filter.event_type {
                     continue; // skip filters that don’t match this event type
                 }
@@ -87,25 +163,24 @@ pub fn filter_ocel_histograms(log: &OCEL, filters_json: &str) -> Vec<OCEL> {
                 }
 
                 // Check if object_count falls in any of the ranges
-                let mut matched = false;
-                for range in &filter.ranges {
-                    if object_count >= range[0] && object_count <= range[1] {
-                        matched = true;
-                        break;
-                    }
-                }
-
-                if !matched {
+                if !filter.matches(object_count) {
                     continue 'event_loop; // skip this event
                 }
             }
 
-            // Event passed all filters in this selection
-            filtered_events.push(event.clone());
+            // Event passed all filters in this selection: drop relationships to removed
+            // objects, and the event if nothing is left
+            let mut event = event.clone();
+            event
+                .relationships
+                .retain(|rel| !removed_objects.contains(rel.object_id.as_str()));
+            if !event.relationships.is_empty() {
+                filtered_events.push(event);
+            }
         }
 
         // 4. Filter objects: keep only objects that appear in the filtered events
-        let mut used_objects: std::collections::HashSet<&str> = std::collections::HashSet::new();
+        let mut used_objects: HashSet<&str> = HashSet::new();
         for event in &filtered_events {
             for rel in &event.relationships {
                 used_objects.insert(rel.object_id.as_str());
@@ -116,6 +191,11 @@ pub fn filter_ocel_histograms(log: &OCEL, filters_json: &str) -> Vec<OCEL> {
             .iter()
             .filter(|obj| used_objects.contains(obj.id.as_str()))
             .cloned()
+            .map(|mut obj| {
+                obj.relationships
+                    .retain(|rel| !removed_objects.contains(rel.object_id.as_str()));
+                obj
+            })
             .collect();
 
         // 5. Create filtered OCEL
@@ -129,5 +209,5 @@ pub fn filter_ocel_histograms(log: &OCEL, filters_json: &str) -> Vec<OCEL> {
         result.push(filtered_ocel);
     }
 
-    result
+    Ok(result)
 }
@@ -1,2 +1,3 @@
 pub mod histogram_builder;
-pub mod histogram_filtering;
\ No newline at end of file
+pub mod histogram_filtering;
+pub mod sublog_suggestions;
\ No newline at end of file
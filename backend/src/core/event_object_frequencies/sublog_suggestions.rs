@@ -0,0 +1,232 @@
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use process_mining::OCEL;
+
+use crate::core::event_object_frequencies::histogram_filtering::{
+    Filter, HistogramDirection, Selection, SelectionPayload,
+};
+
+/// The `[min, max]` count ranges of the modes per multimodal object type of an event type
+type ModesPerObType<'a> = Vec<(&'a str, Vec<[usize; 2]>)>;
+
+/// Options for [`suggest_sublogs`]
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct SuggestionOptions {
+    /// Minimum share of the events of an event type a suggested sublog has to cover
+    pub min_support: f64,
+    /// Maximum number of returned selections
+    pub max_suggestions: usize,
+    /// A bin whose frequency is at most this share of both neighbouring peaks separates two modes
+    pub valley_ratio: f64,
+}
+
+impl Default for SuggestionOptions {
+    fn default() -> Self {
+        Self {
+            min_support: 0.05,
+            max_suggestions: 10,
+            valley_ratio: 0.5,
+        }
+    }
+}
+
+/*
+Example JSON output (same scheme as the input of `filter_ocel_histograms`):
+one selection per behavioural variant found in the event-to-object histograms
+
+{
+  "selections": [
+    {
+      "name": "Load Truck: Container 1-2",
+      "filters": [
+        {
+          "event_type": "Load Truck",
+          "object_type": "Container",
+          "ranges": [[1, 2]],
+          "direction": "event_to_object"
+        }
+      ]
+    },
+    {
+      "name": "Load Truck: Container 8-10",
+      "filters": [ ... ]
+    }
+  ]
+}
+
+*/
+
+/// Proposes sublogs by clustering events on their object-count signature.
+///
+/// **Steps**:
+/// 1. Count, for every event, its objects per object type (including zero counts for object
+///    types that occur with the event type elsewhere).
+/// 2. Split every (event_type, object_type) histogram into modes at gaps and valleys.
+/// 3. Assign each event the modes its counts fall into for all multimodal histograms of its
+///    event type; events sharing this signature form a cluster.
+/// 4. Turn every cluster reaching `min_support` into a [`Selection`], most frequent first.
+pub(crate) fn suggest_sublogs(log: &OCEL, options: &SuggestionOptions) -> SelectionPayload {
+    let object_index: HashMap<&str, &str> = log
+        .objects
+        .iter()
+        .map(|obj| (obj.id.as_str(), obj.object_type.as_str()))
+        .collect();
+
+    // 1. Object counts per event
+    let mut counts_per_event: Vec<HashMap<&str, usize>> = Vec::with_capacity(log.events.len());
+    let mut ob_types_per_ev_type: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for event in &log.events {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for rel in &event.relationships {
+            if let Some(&otype) = object_index.get(rel.object_id.as_str()) {
+                *counts.entry(otype).or_insert(0) += 1;
+            }
+        }
+        ob_types_per_ev_type
+            .entry(event.event_type.as_str())
+            .or_default()
+            .extend(counts.keys());
+        counts_per_event.push(counts);
+    }
+
+    // 2. Histograms and their modes
+    let mut histograms: BTreeMap<(&str, &str), BTreeMap<usize, usize>> = BTreeMap::new();
+    for (event, counts) in log.events.iter().zip(&counts_per_event) {
+        for &otype in &ob_types_per_ev_type[event.event_type.as_str()] {
+            let count = counts.get(otype).copied().unwrap_or(0);
+            *histograms
+                .entry((event.event_type.as_str(), otype))
+                .or_default()
+                .entry(count)
+                .or_insert(0) += 1;
+        }
+    }
+
+    let mut modes_per_ev_type: BTreeMap<&str, ModesPerObType<'_>> = BTreeMap::new();
+    for (&(etype, otype), histogram) in &histograms {
+        let modes = split_modes(histogram, options.valley_ratio);
+        if modes.len() > 1 {
+            modes_per_ev_type.entry(etype).or_default().push((otype, modes));
+        }
+    }
+
+    // 3. Cluster events by the modes they fall into
+    let mut events_per_ev_type: HashMap<&str, usize> = HashMap::new();
+    let mut clusters: BTreeMap<(&str, Vec<usize>), usize> = BTreeMap::new();
+    for (event, counts) in log.events.iter().zip(&counts_per_event) {
+        let etype = event.event_type.as_str();
+        *events_per_ev_type.entry(etype).or_insert(0) += 1;
+
+        let Some(multimodal) = modes_per_ev_type.get(etype) else {
+            continue;
+        };
+        let signature: Vec<usize> = multimodal
+            .iter()
+            .map(|(otype, modes)| {
+                let count = counts.get(otype).copied().unwrap_or(0);
+                modes
+                    .iter()
+                    .position(|range| count >= range[0] && count <= range[1])
+                    .expect("modes cover every observed count")
+            })
+            .collect();
+        *clusters.entry((etype, signature)).or_insert(0) += 1;
+    }
+
+    // 4. Build the selections
+    let mut ranked: Vec<(f64, Selection)> = clusters
+        .into_iter()
+        .filter_map(|((etype, signature), size)| {
+            let support = size as f64 / events_per_ev_type[etype] as f64;
+            if support < options.min_support {
+                return None;
+            }
+
+            let filters: Vec<Filter> = modes_per_ev_type[etype]
+                .iter()
+                .zip(signature)
+                .map(|((otype, modes), mode)| Filter {
+                    event_type: etype.to_string(),
+                    object_type: otype.to_string(),
+                    ranges: vec![modes[mode]],
+                    direction: HistogramDirection::EventToObject,
+                })
+                .collect();
+
+            let name = format!(
+                "{etype}: {}",
+                filters
+                    .iter()
+                    .map(|f| format!("{} {}-{}", f.object_type, f.ranges[0][0], f.ranges[0][1]))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+
+            Some((support, Selection { name: Some(name), filters }))
+        })
+        .collect();
+
+    // Stable sort keeps the (event type, signature) order among equally supported clusters
+    ranked.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+    ranked.truncate(options.max_suggestions);
+
+    SelectionPayload {
+        selections: ranked.into_iter().map(|(_, selection)| selection).collect(),
+    }
+}
+
+/// Splits a histogram (count -> frequency) into `[min, max]` count ranges, one per mode.
+///
+/// A new mode starts after a gap (a count that never occurs) or after a valley bin whose
+/// frequency is at most `valley_ratio` times the smaller of the peaks on either side.
+fn split_modes(histogram: &BTreeMap<usize, usize>, valley_ratio: f64) -> Vec<[usize; 2]> {
+    let bins: Vec<(usize, usize)> = histogram.iter().map(|(&c, &f)| (c, f)).collect();
+    let mut modes: Vec<[usize; 2]> = Vec::new();
+
+    let mut start = 0;
+    for i in 1..=bins.len() {
+        let is_gap = i == bins.len() || bins[i].0 > bins[i - 1].0 + 1;
+        if !is_gap {
+            continue;
+        }
+
+        // Contiguous run bins[start..i], split further at valleys
+        let run = &bins[start..i];
+        let mut run_start = 0;
+        for j in 1..run.len().saturating_sub(1) {
+            let freq = run[j].1;
+            if freq >= run[j - 1].1 || freq > run[j + 1].1 {
+                continue;
+            }
+            let left_peak = run[run_start..j].iter().map(|b| b.1).max().unwrap_or(0);
+            let right_peak = run[j + 1..].iter().map(|b| b.1).max().unwrap_or(0);
+            if (freq as f64) <= valley_ratio * left_peak.min(right_peak) as f64 {
+                modes.push([run[run_start].0, run[j].0]);
+                run_start = j + 1;
+            }
+        }
+        modes.push([run[run_start].0, run[run.len() - 1].0]);
+
+        start = i;
+    }
+
+    modes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_modes() {
+        let gap: BTreeMap<usize, usize> = [(1, 5), (2, 3), (8, 4), (9, 2)].into();
+        assert_eq!(split_modes(&gap, 0.5), vec![[1, 2], [8, 9]]);
+
+        let valley: BTreeMap<usize, usize> = [(1, 10), (2, 1), (3, 8), (4, 9)].into();
+        assert_eq!(split_modes(&valley, 0.5), vec![[1, 2], [3, 4]]);
+
+        let unimodal: BTreeMap<usize, usize> = [(1, 2), (2, 6), (3, 4)].into();
+        assert_eq!(split_modes(&unimodal, 0.5), vec![[1, 3]]);
+    }
+}
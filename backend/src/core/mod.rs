@@ -1,4 +1,7 @@
 pub mod df2_miner;
+pub mod dfg;
+pub mod ocpt;
+pub mod render;
 pub mod struct_converters;
 pub mod conformance;
 pub mod utils;
@@ -0,0 +1,487 @@
+//! BPMN 2.0 approximation of an OCPT, including a diagram (BPMNDI) so that modellers such as
+//! bpmn.io can render it.
+//!
+//! Activities become tasks, exclusive choices and concurrency become exclusive and parallel
+//! gateway blocks, and loops become an exclusive join before the do part and an exclusive split
+//! after it, from which the redo parts lead back to the join. Silent leaves become plain
+//! sequence flows. BPMN has no notion of object types, so they (with their divergent,
+//! convergent and deficient exhibits) and loop bounds are kept as `documentation`.
+//!
+//! The layout is a simple block layout: blocks flow from left to right, branches are stacked
+//! vertically and redo parts are placed below the do part of their loop.
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+use super::{leaf_exhibits, xml_escape};
+use crate::models::ocpt::{OCPTLeaf, OCPTLeafLabel, OCPTNode, OCPTOperator, OCPTOperatorType, OCPT};
+
+const TASK_WIDTH: i32 = 100;
+const TASK_HEIGHT: i32 = 80;
+const GATEWAY_SIZE: i32 = 50;
+const EVENT_SIZE: i32 = 36;
+const H_GAP: i32 = 50;
+const V_GAP: i32 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ElementKind {
+    StartEvent,
+    EndEvent,
+    Task,
+    ExclusiveGateway,
+    ParallelGateway,
+}
+
+impl ElementKind {
+    fn tag(&self) -> &'static str {
+        match self {
+            Self::StartEvent => "startEvent",
+            Self::EndEvent => "endEvent",
+            Self::Task => "task",
+            Self::ExclusiveGateway => "exclusiveGateway",
+            Self::ParallelGateway => "parallelGateway",
+        }
+    }
+
+    fn id_prefix(&self) -> &'static str {
+        match self {
+            Self::StartEvent | Self::EndEvent => "Event",
+            Self::Task => "Activity",
+            Self::ExclusiveGateway | Self::ParallelGateway => "Gateway",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bounds {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+impl Bounds {
+    fn centered(x: i32, center_y: i32, width: i32, height: i32) -> Self {
+        Self { x, y: center_y - height / 2, width, height }
+    }
+
+    fn right(&self) -> i32 {
+        self.x + self.width
+    }
+
+    fn bottom(&self) -> i32 {
+        self.y + self.height
+    }
+
+    fn center(&self) -> (i32, i32) {
+        (self.x + self.width / 2, self.y + self.height / 2)
+    }
+}
+
+struct Element {
+    id: String,
+    kind: ElementKind,
+    name: String,
+    documentation: Option<String>,
+    bounds: Bounds,
+}
+
+struct Flow {
+    id: String,
+    source: usize,
+    target: usize,
+    /// Row below the elements to route the flow through (skipped choices and redo parts)
+    via_y: Option<i32>,
+}
+
+/// Extent of a laid out block: its width and its height above and below the center line
+#[derive(Debug, Clone, Copy, Default)]
+struct Extent {
+    width: i32,
+    up: i32,
+    down: i32,
+}
+
+impl Extent {
+    fn new(width: i32, height: i32) -> Self {
+        Self { width, up: height / 2, down: height - height / 2 }
+    }
+
+    fn height(&self) -> i32 {
+        self.up + self.down
+    }
+}
+
+#[derive(Default)]
+struct Diagram {
+    elements: Vec<Element>,
+    flows: Vec<Flow>,
+    extents: HashMap<uuid::Uuid, Option<Extent>>,
+}
+
+impl Diagram {
+    fn element(&mut self, kind: ElementKind, name: String, documentation: Option<String>, bounds: Bounds) -> usize {
+        let id = format!("{}_{}", kind.id_prefix(), self.elements.len() + 1);
+        self.elements.push(Element { id, kind, name, documentation, bounds });
+        self.elements.len() - 1
+    }
+
+    fn flow(&mut self, source: usize, target: usize) {
+        self.flow_via(source, target, None);
+    }
+
+    fn flow_via(&mut self, source: usize, target: usize, via_y: Option<i32>) {
+        let id = format!("Flow_{}", self.flows.len() + 1);
+        self.flows.push(Flow { id, source, target, via_y });
+    }
+
+    fn gateway(&mut self, kind: ElementKind, name: String, documentation: Option<String>, x: i32, center_y: i32) -> usize {
+        self.element(kind, name, documentation, Bounds::centered(x, center_y, GATEWAY_SIZE, GATEWAY_SIZE))
+    }
+
+    /// Extent of the block of `node`, or `None` if the node is silent (a plain flow)
+    fn extent(&mut self, node: &OCPTNode) -> Option<Extent> {
+        if let Some(extent) = self.extents.get(node.get_uuid()) {
+            return *extent;
+        }
+        let extent = match node {
+            OCPTNode::Leaf(leaf) => match leaf.activity_label {
+                OCPTLeafLabel::Activity(_) => Some(Extent::new(TASK_WIDTH, TASK_HEIGHT)),
+                OCPTLeafLabel::Tau => None,
+            },
+            OCPTNode::Operator(op) => {
+                let children: Vec<Option<Extent>> = op.children.iter().map(|c| self.extent(c)).collect();
+                match op.operator_type {
+                    OCPTOperatorType::Sequence => {
+                        let visible: Vec<Extent> = children.into_iter().flatten().collect();
+                        (!visible.is_empty()).then(|| Extent {
+                            width: visible.iter().map(|e| e.width).sum::<i32>()
+                                + H_GAP * (visible.len() as i32 - 1),
+                            up: visible.iter().map(|e| e.up).max().unwrap_or(0),
+                            down: visible.iter().map(|e| e.down).max().unwrap_or(0),
+                        })
+                    }
+                    OCPTOperatorType::ExclusiveChoice | OCPTOperatorType::Concurrency => {
+                        let branches = branch_extents(&children, is_choice(op));
+                        (!branches.is_empty()).then(|| {
+                            let inner = branches.iter().map(|e| e.width).max().unwrap_or(0);
+                            Extent::new(2 * GATEWAY_SIZE + 2 * H_GAP + inner, stacked_height(&branches))
+                        })
+                    }
+                    OCPTOperatorType::Loop(_) => {
+                        let do_part = children.first().copied().flatten().unwrap_or_default();
+                        let redos = branch_extents(children.get(1..).unwrap_or_default(), true);
+                        let inner = redos.iter().map(|e| e.width).chain([do_part.width]).max().unwrap_or(0);
+                        Some(Extent {
+                            width: 2 * GATEWAY_SIZE + 2 * H_GAP + inner,
+                            up: do_part.up.max(GATEWAY_SIZE / 2),
+                            down: do_part.down.max(GATEWAY_SIZE / 2) + V_GAP + stacked_height(&redos),
+                        })
+                    }
+                }
+            }
+        };
+        self.extents.insert(*node.get_uuid(), extent);
+        extent
+    }
+
+    /// Lays out the block of `node` starting at `x`, centered on `center_y`.
+    /// Returns its entry and exit element, or `None` if the node is silent.
+    fn layout(&mut self, node: &OCPTNode, x: i32, center_y: i32) -> Option<(usize, usize)> {
+        let extent = self.extent(node)?;
+        match node {
+            OCPTNode::Leaf(leaf) => {
+                let OCPTLeafLabel::Activity(label) = &leaf.activity_label else {
+                    return None;
+                };
+                let bounds = Bounds::centered(x, center_y, TASK_WIDTH, TASK_HEIGHT);
+                let task = self.element(ElementKind::Task, label.clone(), leaf_documentation(leaf), bounds);
+                Some((task, task))
+            }
+            OCPTNode::Operator(op) => match op.operator_type {
+                OCPTOperatorType::Sequence => {
+                    let mut cursor = x;
+                    let mut ends: Option<(usize, usize)> = None;
+                    for child in &op.children {
+                        let Some(child_extent) = self.extent(child) else { continue };
+                        let (entry, exit) = self.layout(child, cursor, center_y)?;
+                        cursor += child_extent.width + H_GAP;
+                        ends = match ends {
+                            Some((first, last)) => {
+                                self.flow(last, entry);
+                                Some((first, exit))
+                            }
+                            None => Some((entry, exit)),
+                        };
+                    }
+                    ends
+                }
+                OCPTOperatorType::ExclusiveChoice | OCPTOperatorType::Concurrency => {
+                    let kind = if is_choice(op) {
+                        ElementKind::ExclusiveGateway
+                    } else {
+                        ElementKind::ParallelGateway
+                    };
+                    let split = self.gateway(kind, String::new(), None, x, center_y);
+                    let join = self.gateway(kind, String::new(), None, x + extent.width - GATEWAY_SIZE, center_y);
+                    let top = center_y - extent.up;
+                    self.layout_branches(&op.children, is_choice(op), x + GATEWAY_SIZE + H_GAP, top, split, join);
+                    Some((split, join))
+                }
+                OCPTOperatorType::Loop(bound) => {
+                    let (name, documentation) = match bound {
+                        Some(n) => (format!("max {n}"), Some(format!("Loop with at most {n} repetitions"))),
+                        None => (String::new(), None),
+                    };
+                    let join = self.gateway(ElementKind::ExclusiveGateway, String::new(), None, x, center_y);
+                    let split = self.gateway(
+                        ElementKind::ExclusiveGateway,
+                        name,
+                        documentation,
+                        x + extent.width - GATEWAY_SIZE,
+                        center_y,
+                    );
+                    let inner_x = x + GATEWAY_SIZE + H_GAP;
+                    match op.children.first().and_then(|do_part| self.layout(do_part, inner_x, center_y)) {
+                        Some((entry, exit)) => {
+                            self.flow(join, entry);
+                            self.flow(exit, split);
+                        }
+                        None => self.flow(join, split),
+                    }
+                    let do_down = op
+                        .children
+                        .first()
+                        .and_then(|c| self.extent(c))
+                        .map_or(0, |e| e.down)
+                        .max(GATEWAY_SIZE / 2);
+                    let redo_top = center_y + do_down + V_GAP;
+                    let redos = op.children.get(1..).unwrap_or_default();
+                    self.layout_branches(redos, true, inner_x, redo_top, split, join);
+                    Some((join, split))
+                }
+            },
+        }
+    }
+
+    /// Stacks the branches from `top` downwards and connects them from `split` to `join`.
+    /// If `allow_skip` is set (exclusive choices), silent branches become a single direct flow
+    /// routed through a row below the other branches.
+    fn layout_branches(&mut self, children: &[OCPTNode], allow_skip: bool, x: i32, top: i32, split: usize, join: usize) {
+        let mut top = top;
+        let mut skipped = false;
+        for child in children {
+            match self.extent(child) {
+                Some(extent) => {
+                    let (entry, exit) = self
+                        .layout(child, x, top + extent.up)
+                        .expect("blocks with an extent are laid out");
+                    self.flow(split, entry);
+                    self.flow(exit, join);
+                    top += extent.height() + V_GAP;
+                }
+                None => skipped |= allow_skip,
+            }
+        }
+        if skipped {
+            self.flow_via(split, join, Some(top + GATEWAY_SIZE / 2));
+        }
+    }
+}
+
+fn is_choice(op: &OCPTOperator) -> bool {
+    matches!(op.operator_type, OCPTOperatorType::ExclusiveChoice)
+}
+
+/// Extents of the stacked branches, at least as high as a gateway. With `allow_skip`, silent
+/// branches share one extra row for their direct flow.
+fn branch_extents(children: &[Option<Extent>], allow_skip: bool) -> Vec<Extent> {
+    let row = |e: &Extent| Extent { up: e.up.max(GATEWAY_SIZE / 2), down: e.down.max(GATEWAY_SIZE / 2), ..*e };
+    let mut branches: Vec<Extent> = children.iter().flatten().map(row).collect();
+    if allow_skip && children.iter().any(Option::is_none) {
+        branches.push(row(&Extent::default()));
+    }
+    branches
+}
+
+fn stacked_height(branches: &[Extent]) -> i32 {
+    branches.iter().map(Extent::height).sum::<i32>() + V_GAP * (branches.len() as i32 - 1).max(0)
+}
+
+/// Lists the related object types of a leaf with their exhibits
+fn leaf_documentation(leaf: &OCPTLeaf) -> Option<String> {
+    let ob_types: BTreeSet<&String> = leaf.related_ob_types.iter().collect();
+    if ob_types.is_empty() {
+        return None;
+    }
+    let described: Vec<String> = ob_types
+        .into_iter()
+        .map(|ot| {
+            let exhibits = leaf_exhibits(leaf, ot, ["divergent", "convergent", "deficient"]);
+            if exhibits.is_empty() {
+                ot.clone()
+            } else {
+                format!("{ot} ({})", exhibits.join(", "))
+            }
+        })
+        .collect();
+    Some(format!("Object types: {}", described.join("; ")))
+}
+
+/// Translates the OCPT into a BPMN process with diagram and serializes it
+pub fn export_bpmn(ocpt: &OCPT, name: &str) -> String {
+    let mut diagram = Diagram::default();
+    let extent = diagram.extent(&ocpt.root).unwrap_or_default();
+    let center_y = extent.up.max(EVENT_SIZE / 2) + V_GAP;
+
+    let start = diagram.element(
+        ElementKind::StartEvent,
+        String::new(),
+        None,
+        Bounds::centered(H_GAP, center_y, EVENT_SIZE, EVENT_SIZE),
+    );
+    let body_x = H_GAP + EVENT_SIZE + H_GAP;
+    let body = diagram.layout(&ocpt.root, body_x, center_y);
+    let end_x = body.map_or(body_x, |_| body_x + extent.width + H_GAP);
+    let end = diagram.element(
+        ElementKind::EndEvent,
+        String::new(),
+        None,
+        Bounds::centered(end_x, center_y, EVENT_SIZE, EVENT_SIZE),
+    );
+    match body {
+        Some((entry, exit)) => {
+            diagram.flow(start, entry);
+            diagram.flow(exit, end);
+        }
+        None => diagram.flow(start, end),
+    }
+
+    write_bpmn(&diagram, name)
+}
+
+/// Orthogonal waypoints of a flow; backward flows (of loops) are routed below both elements
+fn waypoints(source: &Bounds, target: &Bounds, via_y: Option<i32>, lane: i32) -> Vec<(i32, i32)> {
+    let (sx, sy) = source.center();
+    let (tx, ty) = target.center();
+    if let Some(via_y) = via_y {
+        vec![(sx, source.bottom()), (sx, via_y), (tx, via_y), (tx, target.bottom())]
+    } else if target.x >= source.right() {
+        let mid = (source.right() + target.x) / 2;
+        if sy == ty {
+            vec![(source.right(), sy), (target.x, ty)]
+        } else {
+            vec![(source.right(), sy), (mid, sy), (mid, ty), (target.x, ty)]
+        }
+    } else {
+        let below = source.bottom().max(target.bottom()) + lane;
+        vec![(sx, source.bottom()), (sx, below), (tx, below), (tx, target.bottom())]
+    }
+}
+
+fn write_bpmn(diagram: &Diagram, name: &str) -> String {
+    let mut incoming: HashMap<usize, Vec<&str>> = HashMap::new();
+    let mut outgoing: HashMap<usize, Vec<&str>> = HashMap::new();
+    for flow in &diagram.flows {
+        outgoing.entry(flow.source).or_default().push(&flow.id);
+        incoming.entry(flow.target).or_default().push(&flow.id);
+    }
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(concat!(
+        "<bpmn:definitions xmlns:bpmn=\"http://www.omg.org/spec/BPMN/20100524/MODEL\" ",
+        "xmlns:bpmndi=\"http://www.omg.org/spec/BPMN/20100524/DI\" ",
+        "xmlns:dc=\"http://www.omg.org/spec/DD/20100524/DC\" ",
+        "xmlns:di=\"http://www.omg.org/spec/DD/20100524/DI\" ",
+        "id=\"Definitions_1\" targetNamespace=\"http://bpmn.io/schema/bpmn\">\n"
+    ));
+    let _ = writeln!(out, "<bpmn:process id=\"Process_1\" name=\"{}\" isExecutable=\"false\">", xml_escape(name));
+
+    for (i, element) in diagram.elements.iter().enumerate() {
+        let tag = element.kind.tag();
+        let _ = write!(out, "<bpmn:{tag} id=\"{}\"", element.id);
+        if !element.name.is_empty() {
+            let _ = write!(out, " name=\"{}\"", xml_escape(&element.name));
+        }
+        out.push_str(">\n");
+        if let Some(documentation) = &element.documentation {
+            let _ = writeln!(out, "<bpmn:documentation>{}</bpmn:documentation>", xml_escape(documentation));
+        }
+        for flow in incoming.get(&i).into_iter().flatten() {
+            let _ = writeln!(out, "<bpmn:incoming>{flow}</bpmn:incoming>");
+        }
+        for flow in outgoing.get(&i).into_iter().flatten() {
+            let _ = writeln!(out, "<bpmn:outgoing>{flow}</bpmn:outgoing>");
+        }
+        let _ = writeln!(out, "</bpmn:{tag}>");
+    }
+    for flow in &diagram.flows {
+        let _ = writeln!(
+            out,
+            "<bpmn:sequenceFlow id=\"{}\" sourceRef=\"{}\" targetRef=\"{}\"/>",
+            flow.id, diagram.elements[flow.source].id, diagram.elements[flow.target].id
+        );
+    }
+    out.push_str("</bpmn:process>\n");
+
+    out.push_str("<bpmndi:BPMNDiagram id=\"BPMNDiagram_1\">\n");
+    out.push_str("<bpmndi:BPMNPlane id=\"BPMNPlane_1\" bpmnElement=\"Process_1\">\n");
+    for element in &diagram.elements {
+        let b = element.bounds;
+        let _ = writeln!(
+            out,
+            "<bpmndi:BPMNShape id=\"{0}_di\" bpmnElement=\"{0}\"><dc:Bounds x=\"{1}\" y=\"{2}\" width=\"{3}\" height=\"{4}\"/></bpmndi:BPMNShape>",
+            element.id, b.x, b.y, b.width, b.height
+        );
+    }
+    let mut lane = 0;
+    for flow in &diagram.flows {
+        let source = &diagram.elements[flow.source].bounds;
+        let target = &diagram.elements[flow.target].bounds;
+        if flow.via_y.is_none() && target.x < source.right() {
+            // Spread backward flows so they do not overlap
+            lane += 10;
+        }
+        let _ = writeln!(out, "<bpmndi:BPMNEdge id=\"{0}_di\" bpmnElement=\"{0}\">", flow.id);
+        for (x, y) in waypoints(source, target, flow.via_y, V_GAP / 2 + lane) {
+            let _ = writeln!(out, "<di:waypoint x=\"{x}\" y=\"{y}\"/>");
+        }
+        out.push_str("</bpmndi:BPMNEdge>\n");
+    }
+    out.push_str("</bpmndi:BPMNPlane>\n</bpmndi:BPMNDiagram>\n</bpmn:definitions>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_bpmn() {
+        // seq(a{o, div i}, loop[3](xor(b, τ), c))
+        let mut a = OCPTNode::new_leaf(Some("a".to_string()));
+        a.add_related_ob_type(&"o".to_string());
+        a.add_related_ob_type(&"i".to_string());
+        a.add_divergent_ob_type(&"i".to_string());
+        let mut choice = OCPTNode::new_operator(OCPTOperatorType::ExclusiveChoice);
+        choice.add_child(OCPTNode::new_leaf(Some("b".to_string())));
+        choice.add_child(OCPTNode::new_leaf(None));
+        let mut loop_node = OCPTNode::new_operator(OCPTOperatorType::Loop(Some(3)));
+        loop_node.add_child(choice);
+        loop_node.add_child(OCPTNode::new_leaf(Some("c".to_string())));
+        let mut root = OCPTNode::new_operator(OCPTOperatorType::Sequence);
+        root.add_child(a);
+        root.add_child(loop_node);
+        let bpmn = export_bpmn(&OCPT::new(root), "model");
+
+        assert_eq!(bpmn.matches("<bpmn:task ").count(), 3);
+        // xor split/join and loop join/split
+        assert_eq!(bpmn.matches("<bpmn:exclusiveGateway ").count(), 4);
+        assert!(bpmn.contains("<bpmn:documentation>Object types: i (divergent); o</bpmn:documentation>"));
+        assert!(bpmn.contains("name=\"max 3\""));
+        // start→a, a→loop, loop join→xor, xor→b, b→xor, xor skip, xor→loop split, redo c (2), →end
+        assert_eq!(bpmn.matches("<bpmn:sequenceFlow ").count(), 10);
+        assert_eq!(bpmn.matches("<bpmndi:BPMNShape ").count(), 9);
+        assert_eq!(bpmn.matches("<bpmndi:BPMNEdge ").count(), 10);
+    }
+}
@@ -0,0 +1,239 @@
+//! Edit operations on OCPTs addressed by node uuid.
+//!
+//! New subtrees and object-type annotations are given in the frontend shape
+//! ([`HierarchyNode`], [`ObjectTypeFE`]); their nodes get fresh uuids. Operators are named as in
+//! the frontend (`sequence`, `xor`, `parallel`, `loop`, ...). An edit may leave the tree invalid
+//! (e.g. deleting the last child of an operator); callers check the result before storing it.
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::core::struct_converters::ocpt_frontend_backend::{
+    frontend_activity_to_leaf, frontend_node_to_backend, parse_operator,
+};
+use crate::models::ocpt::{
+    ActivityValue, HierarchyNode, ObjectTypeFE, OCPTLeafLabel, OCPTNode, OCPTOperator, OCPTOperatorType, OCPT,
+};
+
+/// An edit of an OCPT, tagged by `op`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum OcptEdit {
+    /// Inserts `node` as child of the operator `parent` at `index` (default: as last child)
+    InsertChild {
+        parent: Uuid,
+        #[serde(default)]
+        index: Option<usize>,
+        node: HierarchyNode,
+    },
+    /// Removes `node` and its descendants
+    DeleteSubtree { node: Uuid },
+    /// Changes the type of the operator `node`, keeping its children
+    ReplaceOperator {
+        node: Uuid,
+        operator: String,
+        /// Maximum number of repetitions if `operator` is a loop
+        #[serde(default)]
+        loop_bound: Option<u32>,
+    },
+    /// Moves `node` with its descendants to the operator `parent` at `index` (default: as last
+    /// child). The index refers to the children of `parent` after `node` was removed.
+    MoveSubtree {
+        node: Uuid,
+        parent: Uuid,
+        #[serde(default)]
+        index: Option<usize>,
+    },
+    /// Replaces `node` by a new operator with `node` as only child; a new loop gets a silent
+    /// redo part
+    WrapInOperator {
+        node: Uuid,
+        operator: String,
+        #[serde(default)]
+        loop_bound: Option<u32>,
+    },
+    /// Replaces the object types (and exhibits) of the leaf `node`
+    SetObjectTypes { node: Uuid, ots: Vec<ObjectTypeFE> },
+}
+
+/// Applies `edit` to `ocpt`. Returns the uuid of the node created by the edit, if any, or a
+/// message if the edit cannot be applied.
+pub fn apply_edit(ocpt: &mut OCPT, edit: &OcptEdit) -> Result<Option<Uuid>, String> {
+    match edit {
+        OcptEdit::InsertChild { parent, index, node } => {
+            let node = frontend_node_to_backend(node).map_err(|e| e.to_string())?;
+            let uuid = *node.get_uuid();
+            insert_child(find_operator_mut(&mut ocpt.root, parent)?, *index, node)?;
+            Ok(Some(uuid))
+        }
+        OcptEdit::DeleteSubtree { node } => {
+            detach(&mut ocpt.root, node)?;
+            Ok(None)
+        }
+        OcptEdit::ReplaceOperator { node, operator, loop_bound } => {
+            find_operator_mut(&mut ocpt.root, node)?.operator_type = operator_type(operator, *loop_bound)?;
+            Ok(None)
+        }
+        OcptEdit::MoveSubtree { node, parent, index } => {
+            let subtree = find(&ocpt.root, node).ok_or_else(|| unknown(node))?;
+            if find(subtree, parent).is_some() {
+                return Err(format!("Cannot move node {node} into its own subtree"));
+            }
+            find_operator_mut(&mut ocpt.root, parent)?;
+            let subtree = detach(&mut ocpt.root, node)?;
+            insert_child(find_operator_mut(&mut ocpt.root, parent)?, *index, subtree)?;
+            Ok(None)
+        }
+        OcptEdit::WrapInOperator { node, operator, loop_bound } => {
+            let operator_type = operator_type(operator, *loop_bound)?;
+            let target = find_mut(&mut ocpt.root, node).ok_or_else(|| unknown(node))?;
+            let is_loop = matches!(operator_type, OCPTOperatorType::Loop(_));
+            let mut wrapper = OCPTNode::new_operator(operator_type);
+            let uuid = *wrapper.get_uuid();
+            let wrapped = std::mem::replace(target, OCPTNode::new_leaf(None));
+            wrapper.add_child(wrapped);
+            if is_loop {
+                wrapper.add_child(OCPTNode::new_leaf(None));
+            }
+            *target = wrapper;
+            Ok(Some(uuid))
+        }
+        OcptEdit::SetObjectTypes { node, ots } => {
+            let OCPTNode::Leaf(leaf) = find_mut(&mut ocpt.root, node).ok_or_else(|| unknown(node))? else {
+                return Err(format!("Node {node} is not a leaf"));
+            };
+            let OCPTLeafLabel::Activity(activity) = &leaf.activity_label else {
+                return Err(format!("Silent leaf {node} has no object types"));
+            };
+            let value = ActivityValue { isSilent: None, activity: activity.clone(), ots: ots.clone() };
+            let annotated = frontend_activity_to_leaf(&value);
+            leaf.related_ob_types = annotated.related_ob_types;
+            leaf.divergent_ob_types = annotated.divergent_ob_types;
+            leaf.convergent_ob_types = annotated.convergent_ob_types;
+            leaf.deficient_ob_types = annotated.deficient_ob_types;
+            Ok(None)
+        }
+    }
+}
+
+fn unknown(uuid: &Uuid) -> String {
+    format!("No node with uuid {uuid}")
+}
+
+fn operator_type(name: &str, loop_bound: Option<u32>) -> Result<OCPTOperatorType, String> {
+    match parse_operator(name).map_err(|e| e.to_string())? {
+        OCPTOperatorType::Loop(_) => Ok(OCPTOperatorType::Loop(loop_bound)),
+        operator_type => Ok(operator_type),
+    }
+}
+
+fn find<'a>(node: &'a OCPTNode, uuid: &Uuid) -> Option<&'a OCPTNode> {
+    if node.get_uuid() == uuid {
+        return Some(node);
+    }
+    match node {
+        OCPTNode::Operator(op) => op.children.iter().find_map(|child| find(child, uuid)),
+        OCPTNode::Leaf(_) => None,
+    }
+}
+
+fn find_mut<'a>(node: &'a mut OCPTNode, uuid: &Uuid) -> Option<&'a mut OCPTNode> {
+    if node.get_uuid() == uuid {
+        return Some(node);
+    }
+    match node {
+        OCPTNode::Operator(op) => op.children.iter_mut().find_map(|child| find_mut(child, uuid)),
+        OCPTNode::Leaf(_) => None,
+    }
+}
+
+fn find_operator_mut<'a>(root: &'a mut OCPTNode, uuid: &Uuid) -> Result<&'a mut OCPTOperator, String> {
+    match find_mut(root, uuid) {
+        Some(OCPTNode::Operator(op)) => Ok(op),
+        Some(OCPTNode::Leaf(_)) => Err(format!("Node {uuid} is not an operator")),
+        None => Err(unknown(uuid)),
+    }
+}
+
+fn insert_child(parent: &mut OCPTOperator, index: Option<usize>, child: OCPTNode) -> Result<(), String> {
+    let index = index.unwrap_or(parent.children.len());
+    if index > parent.children.len() {
+        return Err(format!(
+            "Index {index} out of range, operator {} has {} children",
+            parent.uuid,
+            parent.children.len()
+        ));
+    }
+    parent.children.insert(index, child);
+    Ok(())
+}
+
+/// Removes the subtree `uuid` from the tree and returns it
+fn detach(root: &mut OCPTNode, uuid: &Uuid) -> Result<OCPTNode, String> {
+    if root.get_uuid() == uuid {
+        return Err("Cannot remove the root".to_string());
+    }
+    detach_from(root, uuid).ok_or_else(|| unknown(uuid))
+}
+
+fn detach_from(node: &mut OCPTNode, uuid: &Uuid) -> Option<OCPTNode> {
+    let OCPTNode::Operator(op) = node else {
+        return None;
+    };
+    if let Some(i) = op.children.iter().position(|child| child.get_uuid() == uuid) {
+        return Some(op.children.remove(i));
+    }
+    op.children.iter_mut().find_map(|child| detach_from(child, uuid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ocpt::reduce::canonical_string;
+
+    fn edit(json: serde_json::Value) -> OcptEdit {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_apply_edits() {
+        let mut root = OCPTNode::new_operator(OCPTOperatorType::Sequence);
+        let place = OCPTNode::new_leaf(Some("place".to_string()));
+        let place_uuid = *place.get_uuid();
+        root.add_child(place);
+        let root_uuid = *root.get_uuid();
+        let mut ocpt = OCPT::new(root);
+
+        let pay = apply_edit(
+            &mut ocpt,
+            &edit(serde_json::json!({"op": "insert_child", "parent": root_uuid, "node": {
+                "value": {"activity": "pay", "ots": [{"ot": "order"}]}
+            }})),
+        )
+        .unwrap()
+        .unwrap();
+        let set = serde_json::json!({"op": "set_object_types", "node": place_uuid,
+            "ots": [{"ot": "order"}, {"ot": "item", "exhibits": ["div"]}]});
+        apply_edit(&mut ocpt, &edit(set)).unwrap();
+        let wrap = serde_json::json!({"op": "wrap_in_operator", "node": pay, "operator": "loop", "loop_bound": 3});
+        let loop_uuid = apply_edit(&mut ocpt, &edit(wrap)).unwrap().unwrap();
+        let replace = serde_json::json!({"op": "replace_operator", "node": root_uuid, "operator": "parallel"});
+        apply_edit(&mut ocpt, &edit(replace)).unwrap();
+        let moved = serde_json::json!({"op": "move_subtree", "node": place_uuid, "parent": loop_uuid, "index": 1});
+        apply_edit(&mut ocpt, &edit(moved)).unwrap();
+        assert_eq!(
+            canonical_string(&ocpt.root),
+            r#"+(*3("pay"{"order"},"place"{"item":div,"order"},tau))"#
+        );
+        assert!(ocpt.is_valid());
+
+        let into_own = serde_json::json!({"op": "move_subtree", "node": loop_uuid, "parent": loop_uuid});
+        assert!(apply_edit(&mut ocpt, &edit(into_own)).is_err());
+        let root_delete = serde_json::json!({"op": "delete_subtree", "node": root_uuid});
+        assert!(apply_edit(&mut ocpt, &edit(root_delete)).is_err());
+        let on_leaf = serde_json::json!({"op": "insert_child", "parent": pay, "node": {"value": {"activity": "x", "ots": []}}});
+        assert!(apply_edit(&mut ocpt, &edit(on_leaf)).is_err());
+
+        apply_edit(&mut ocpt, &edit(serde_json::json!({"op": "delete_subtree", "node": loop_uuid}))).unwrap();
+        assert!(!ocpt.is_valid());
+    }
+}
@@ -0,0 +1,245 @@
+//! Projection of an [`OCPT`] onto a single object type as a (traditional) process tree.
+//!
+//! The flattened tree describes the traces of one object of the type, following the leaf
+//! semantics of [`OCPTLeaf::get_directly_follows_relations`]:
+//! - leaves unrelated to the object type and silent leaves become silent (τ),
+//! - divergent leaves may repeat and be skipped (`loop(τ, a)`),
+//! - deficient leaves may be skipped (`xor(a, τ)`).
+//!
+//! Loops with several redo children get a single exclusive choice as redo part, and loop
+//! bounds are dropped since traditional process trees cannot express them.
+//!
+//! Flattened trees are displayed in the PM4Py notation, e.g. `->( 'a', *( tau, 'c' ) )`.
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fmt;
+use uuid::Uuid;
+
+use crate::models::ocpt::{OCPTLeaf, OCPTLeafLabel, OCPTNode, OCPTOperatorType, OCPT};
+
+/// Operators of a traditional process tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlatOperator {
+    Sequence,
+    Xor,
+    Parallel,
+    /// Exactly two children: the do and the redo part
+    Loop,
+}
+
+/// Node of a flattened process tree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum FlatNode {
+    Operator {
+        operator: FlatOperator,
+        children: Vec<FlatNode>,
+    },
+    Leaf {
+        /// The OCPT leaf this leaf originates from, `None` for inserted silent leaves
+        #[serde(skip_serializing_if = "Option::is_none")]
+        uuid: Option<Uuid>,
+        /// The activity, `None` for silent leaves
+        label: Option<String>,
+    },
+}
+
+impl FlatNode {
+    fn tau() -> Self {
+        FlatNode::Leaf { uuid: None, label: None }
+    }
+
+    /// Returns `true` for silent leaves
+    pub fn is_tau(&self) -> bool {
+        matches!(self, FlatNode::Leaf { label: None, .. })
+    }
+
+    fn operator(operator: FlatOperator, children: Vec<FlatNode>) -> Self {
+        FlatNode::Operator { operator, children }
+    }
+}
+
+impl fmt::Display for FlatNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlatNode::Leaf { label: None, .. } => write!(f, "tau"),
+            FlatNode::Leaf { label: Some(label), .. } => {
+                write!(f, "'{}'", label.replace('\\', "\\\\").replace('\'', "\\'"))
+            }
+            FlatNode::Operator { operator, children } => {
+                let symbol = match operator {
+                    FlatOperator::Sequence => "->",
+                    FlatOperator::Xor => "X",
+                    FlatOperator::Parallel => "+",
+                    FlatOperator::Loop => "*",
+                };
+                write!(f, "{symbol}( ")?;
+                for (i, child) in children.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{child}")?;
+                }
+                write!(f, " )")
+            }
+        }
+    }
+}
+
+/// All object types related to some leaf of the OCPT, sorted
+pub fn ocpt_object_types(ocpt: &OCPT) -> BTreeSet<String> {
+    ocpt.find_all_leaves()
+        .into_iter()
+        .flat_map(|leaf| leaf.related_ob_types.iter().cloned())
+        .collect()
+}
+
+/// Flattens the OCPT onto `ob_type` and simplifies the result
+pub fn flatten_ocpt(ocpt: &OCPT, ob_type: &str) -> FlatNode {
+    flatten_node(&ocpt.root, ob_type)
+}
+
+fn flatten_node(node: &OCPTNode, ob_type: &str) -> FlatNode {
+    match node {
+        OCPTNode::Leaf(leaf) => flatten_leaf(leaf, ob_type),
+        OCPTNode::Operator(op) => {
+            let mut children = op.children.iter().map(|c| flatten_node(c, ob_type));
+            match op.operator_type {
+                OCPTOperatorType::Sequence => simplify(FlatOperator::Sequence, children.collect()),
+                OCPTOperatorType::ExclusiveChoice => simplify(FlatOperator::Xor, children.collect()),
+                OCPTOperatorType::Concurrency => simplify(FlatOperator::Parallel, children.collect()),
+                OCPTOperatorType::Loop(_) => {
+                    let do_part = children.next().unwrap_or_else(FlatNode::tau);
+                    let redo = simplify(FlatOperator::Xor, children.collect());
+                    simplify(FlatOperator::Loop, vec![do_part, redo])
+                }
+            }
+        }
+    }
+}
+
+fn flatten_leaf(leaf: &OCPTLeaf, ob_type: &str) -> FlatNode {
+    let label = match &leaf.activity_label {
+        OCPTLeafLabel::Activity(label) if leaf.related_ob_types.contains(ob_type) => label,
+        _ => return FlatNode::tau(),
+    };
+    let activity = FlatNode::Leaf { uuid: Some(leaf.uuid), label: Some(label.clone()) };
+    if leaf.divergent_ob_types.contains(ob_type) {
+        FlatNode::operator(FlatOperator::Loop, vec![FlatNode::tau(), activity])
+    } else if leaf.deficient_ob_types.contains(ob_type) {
+        FlatNode::operator(FlatOperator::Xor, vec![activity, FlatNode::tau()])
+    } else {
+        activity
+    }
+}
+
+/// Builds an operator node, dropping silent leaves without effect, merging nested operators of
+/// the same (associative) type and collapsing operators with a single child
+fn simplify(operator: FlatOperator, children: Vec<FlatNode>) -> FlatNode {
+    if operator == FlatOperator::Loop {
+        if children.iter().all(FlatNode::is_tau) {
+            return FlatNode::tau();
+        }
+        // Repeating `loop(a, τ)` (a+) or `loop(τ, a)` (a*) without redo part does not change it
+        if let [inner @ FlatNode::Operator { operator: FlatOperator::Loop, children: inner_children }, redo] =
+            &children[..]
+            && redo.is_tau()
+            && inner_children.iter().any(FlatNode::is_tau)
+        {
+            return inner.clone();
+        }
+        return FlatNode::operator(operator, children);
+    }
+
+    let mut merged = Vec::with_capacity(children.len());
+    let mut has_tau = false;
+    let flattened = children.into_iter().flat_map(|child| match child {
+        FlatNode::Operator { operator: op, children } if op == operator => children,
+        other => vec![other],
+    });
+    for child in flattened {
+        if child.is_tau() {
+            has_tau = true;
+        } else {
+            merged.push(child);
+        }
+    }
+    // A silent branch of a choice keeps the choice skippable, unless all branches are silent
+    if operator == FlatOperator::Xor && has_tau && !merged.is_empty() {
+        merged.push(FlatNode::tau());
+    }
+
+    match merged.len() {
+        0 => FlatNode::tau(),
+        1 => merged.pop().unwrap(),
+        _ => FlatNode::operator(operator, merged),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(label: &str, related: &[&str]) -> OCPTNode {
+        let mut node = OCPTNode::new_leaf(Some(label.to_string()));
+        related.iter().for_each(|ot| node.add_related_ob_type(&ot.to_string()));
+        node
+    }
+
+    fn label(node: &FlatNode) -> Option<&str> {
+        match node {
+            FlatNode::Leaf { label, .. } => label.as_deref(),
+            FlatNode::Operator { .. } => None,
+        }
+    }
+
+    #[test]
+    fn test_flatten_per_object_type() {
+        // seq(a{o,i}, and(b{o}, c{i, div i}), d{o})
+        let mut c = leaf("c", &["i"]);
+        c.add_divergent_ob_type(&"i".to_string());
+        let mut par = OCPTNode::new_operator(OCPTOperatorType::Concurrency);
+        par.add_child(leaf("b", &["o"]));
+        par.add_child(c);
+        let mut root = OCPTNode::new_operator(OCPTOperatorType::Sequence);
+        root.add_child(leaf("a", &["o", "i"]));
+        root.add_child(par);
+        root.add_child(leaf("d", &["o"]));
+        let ocpt = OCPT::new(root);
+
+        assert_eq!(ocpt_object_types(&ocpt).into_iter().collect::<Vec<_>>(), vec!["i", "o"]);
+
+        // Orders: c is silent, so the concurrency collapses to b
+        let FlatNode::Operator { operator, children } = flatten_ocpt(&ocpt, "o") else {
+            panic!("expected an operator");
+        };
+        assert_eq!(operator, FlatOperator::Sequence);
+        assert_eq!(children.iter().map(label).collect::<Vec<_>>(), vec![Some("a"), Some("b"), Some("d")]);
+
+        // Items: seq(a, loop(τ, c))
+        let FlatNode::Operator { operator, children } = flatten_ocpt(&ocpt, "i") else {
+            panic!("expected an operator");
+        };
+        assert_eq!(operator, FlatOperator::Sequence);
+        assert_eq!(children.len(), 2);
+        assert_eq!(label(&children[0]), Some("a"));
+        let FlatNode::Operator { operator: FlatOperator::Loop, children: loop_children } = &children[1] else {
+            panic!("expected a loop");
+        };
+        assert!(loop_children[0].is_tau());
+        assert_eq!(label(&loop_children[1]), Some("c"));
+        assert_eq!(flatten_ocpt(&ocpt, "i").to_string(), "->( 'a', *( tau, 'c' ) )");
+
+        // A divergent leaf in a loop without redo part stays a single self-loop
+        let mut c = leaf("c", &["i"]);
+        c.add_divergent_ob_type(&"i".to_string());
+        let mut repeat = OCPTNode::new_operator(OCPTOperatorType::Loop(Some(3)));
+        repeat.add_child(c);
+        repeat.add_child(OCPTNode::new_leaf(None));
+        assert_eq!(flatten_ocpt(&OCPT::new(repeat), "i").to_string(), "*( tau, 'c' )");
+
+        // Unknown object types flatten to a single silent leaf
+        assert!(flatten_ocpt(&ocpt, "unknown").is_tau());
+    }
+}
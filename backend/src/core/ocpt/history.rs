@@ -0,0 +1,211 @@
+//! Version history of edited OCPTs (`./temp/ocpt_{id}.history.json`).
+//!
+//! The first edit of a stored OCPT records it as version 0; every applied edit appends a new
+//! version with the edit and the resulting tree. Undoing drops the latest version and restores
+//! its predecessor. Uploading or deleting an OCPT discards its history.
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use super::edit::OcptEdit;
+use super::reduce::canonical_string;
+use crate::core::struct_converters::ocel_storage::STORAGE_DIR;
+use crate::models::ocpt::{OCPTNode, OCPTOperatorType, OCPT};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OcptVersion {
+    pub version: u32,
+    /// RFC 3339 timestamp
+    pub created_at: String,
+    /// The edit that produced this version, `None` for the initial version
+    pub edit: Option<OcptEdit>,
+    pub ocpt: OCPT,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct OcptHistory {
+    pub versions: Vec<OcptVersion>,
+}
+
+impl OcptHistory {
+    /// A history with `ocpt` as version 0
+    pub fn new(ocpt: OCPT) -> Self {
+        let mut history = Self::default();
+        history.push(None, ocpt);
+        history
+    }
+
+    /// Appends a version and returns its number
+    pub fn push(&mut self, edit: Option<OcptEdit>, ocpt: OCPT) -> u32 {
+        let version = self.versions.last().map_or(0, |v| v.version + 1);
+        let created_at = chrono::Utc::now().to_rfc3339();
+        self.versions.push(OcptVersion { version, created_at, edit, ocpt });
+        version
+    }
+
+    /// Drops the latest version and returns the restored one, or `None` if there is nothing to
+    /// undo
+    pub fn undo(&mut self) -> Option<&OcptVersion> {
+        if self.versions.len() < 2 {
+            return None;
+        }
+        self.versions.pop();
+        self.versions.last()
+    }
+
+    pub fn latest(&self) -> Option<&OcptVersion> {
+        self.versions.last()
+    }
+
+    pub fn get(&self, version: u32) -> Option<&OcptVersion> {
+        self.versions.iter().find(|v| v.version == version)
+    }
+}
+
+/// Path of the history of the OCPT stored under `id`
+pub fn history_path(id: &str) -> PathBuf {
+    Path::new(STORAGE_DIR).join(format!("ocpt_{id}.history.json"))
+}
+
+/// Loads the history of the OCPT stored under `id`, or `None` if it was never edited
+pub fn load_history(id: &str) -> Result<Option<OcptHistory>> {
+    let path = history_path(id);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("read {}", path.to_string_lossy()))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("parse OCPT history at {}", path.to_string_lossy()))
+        .map(Some)
+}
+
+pub fn save_history(id: &str, history: &OcptHistory) -> Result<()> {
+    let path = history_path(id);
+    std::fs::write(&path, serde_json::to_vec(history)?)
+        .with_context(|| format!("write {}", path.to_string_lossy()))
+}
+
+/// Removes the history of `id`, if any
+pub fn remove_history(id: &str) -> Result<()> {
+    let path = history_path(id);
+    if path.exists() {
+        std::fs::remove_file(&path).with_context(|| format!("remove {}", path.to_string_lossy()))?;
+    }
+    Ok(())
+}
+
+/// A node as it appears in a version
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NodeSummary {
+    /// The operator (with loop bound) or the leaf in its canonical form
+    pub label: String,
+    pub parent: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NodeChange {
+    pub uuid: Uuid,
+    pub before: NodeSummary,
+    pub after: NodeSummary,
+}
+
+/// Differences between two versions, by node uuid
+#[derive(Debug, Default, Serialize)]
+pub struct OcptDiff {
+    pub added: BTreeMap<Uuid, NodeSummary>,
+    pub removed: BTreeMap<Uuid, NodeSummary>,
+    /// Nodes whose operator type or annotations changed, or that were moved to another parent
+    pub changed: Vec<NodeChange>,
+}
+
+/// Compares the nodes of `from` and `to`
+pub fn diff_ocpts(from: &OCPT, to: &OCPT) -> OcptDiff {
+    let (before, after) = (summarize(from), summarize(to));
+    let mut diff = OcptDiff::default();
+    for (uuid, summary) in &before {
+        match after.get(uuid) {
+            None => {
+                diff.removed.insert(*uuid, summary.clone());
+            }
+            Some(other) if other != summary => diff.changed.push(NodeChange {
+                uuid: *uuid,
+                before: summary.clone(),
+                after: other.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    for (uuid, summary) in after {
+        if !before.contains_key(&uuid) {
+            diff.added.insert(uuid, summary);
+        }
+    }
+    diff.changed.sort_by_key(|change| change.uuid);
+    diff
+}
+
+fn summarize(ocpt: &OCPT) -> HashMap<Uuid, NodeSummary> {
+    fn visit(node: &OCPTNode, parent: Option<Uuid>, result: &mut HashMap<Uuid, NodeSummary>) {
+        let label = match node {
+            OCPTNode::Leaf(_) => canonical_string(node),
+            OCPTNode::Operator(op) => match op.operator_type {
+                OCPTOperatorType::Sequence => "->".to_string(),
+                OCPTOperatorType::ExclusiveChoice => "X".to_string(),
+                OCPTOperatorType::Concurrency => "+".to_string(),
+                OCPTOperatorType::Loop(None) => "*".to_string(),
+                OCPTOperatorType::Loop(Some(n)) => format!("*{n}"),
+            },
+        };
+        result.insert(*node.get_uuid(), NodeSummary { label, parent });
+        if let OCPTNode::Operator(op) = node {
+            op.children.iter().for_each(|child| visit(child, Some(op.uuid), result));
+        }
+    }
+    let mut result = HashMap::new();
+    visit(&ocpt.root, None, &mut result);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ocpt::edit::apply_edit;
+
+    #[test]
+    fn test_history_undo_and_diff() {
+        let mut root = OCPTNode::new_operator(OCPTOperatorType::Sequence);
+        root.add_child(OCPTNode::new_leaf(Some("place".to_string())));
+        let root_uuid = *root.get_uuid();
+        let initial = OCPT::new(root);
+        let initial_json = serde_json::to_string(&initial).unwrap();
+        let mut history = OcptHistory::new(initial);
+        assert!(history.undo().is_none());
+
+        let mut ocpt: OCPT = serde_json::from_str(&initial_json).unwrap();
+        let edit: OcptEdit = serde_json::from_value(serde_json::json!({
+            "op": "replace_operator", "node": root_uuid, "operator": "loop", "loop_bound": 2
+        }))
+        .unwrap();
+        apply_edit(&mut ocpt, &edit).unwrap();
+        let insert: OcptEdit = serde_json::from_value(serde_json::json!({
+            "op": "insert_child", "parent": root_uuid, "node": {"value": {"activity": "", "isSilent": true, "ots": []}}
+        }))
+        .unwrap();
+        let tau = apply_edit(&mut ocpt, &insert).unwrap().unwrap();
+        assert_eq!(history.push(Some(edit), ocpt), 1);
+
+        let diff = diff_ocpts(&history.get(0).unwrap().ocpt, &history.get(1).unwrap().ocpt);
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.added[&tau], NodeSummary { label: "tau".to_string(), parent: Some(root_uuid) });
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!((diff.changed[0].before.label.as_str(), diff.changed[0].after.label.as_str()), ("->", "*2"));
+
+        let restored = history.undo().unwrap();
+        assert_eq!(restored.version, 0);
+        assert_eq!(serde_json::to_string(&restored.ocpt).unwrap(), initial_json);
+        assert_eq!(history.versions.len(), 1);
+    }
+}
@@ -0,0 +1,324 @@
+//! OCPT imports: PTML process trees (ProM / PM4Py) combined with an object-type annotation
+//! file, and the OCPT JSON representation of PM4Py.
+//!
+//! The annotation file maps every visible activity of the PTML tree to its object types in the
+//! frontend shape, e.g. `{"pick item": [{"ot": "item"}, {"ot": "order", "exhibits": ["con"]}]}`.
+//!
+//! PM4Py OCPTs are nested nodes `{"operator": "->", "children": [...]}` for operators (`->`,
+//! `X`, `+`, `*` or their names) and `{"label": "a", "related": [...], "divergent": [...],
+//! "convergent": [...], "deficient": [...]}` for leaves, where a missing label is a silent
+//! leaf. The tree may be wrapped as `{"process_tree": node}`. As in PM4Py, the first child of a
+//! loop is its do part and the remaining children are redo parts.
+use anyhow::{anyhow, bail, Context, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+use crate::core::struct_converters::ocpt_frontend_backend::{frontend_activity_to_leaf, parse_operator};
+use crate::models::ocpt::{ActivityValue, ObjectTypeFE, OCPTLeaf, OCPTLeafLabel, OCPTNode, OCPTOperatorType, OCPT};
+
+/// Object types (with exhibits) of each visible activity of a PTML tree
+pub type ActivityAnnotations = HashMap<String, Vec<ObjectTypeFE>>;
+
+/// Source formats accepted when uploading an OCPT
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OcptImportFormat {
+    /// Backend or frontend OCPT JSON, falling back to PM4Py's representation
+    Json,
+    /// PTML process tree with an annotation file
+    Ptml,
+    /// PM4Py OCPT JSON
+    Pm4py,
+}
+
+impl OcptImportFormat {
+    /// Parses a format name as given in the `format` upload field
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s.trim().trim_start_matches('.').to_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "ptml" => Some(Self::Ptml),
+            "pm4py" => Some(Self::Pm4py),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format from the uploaded content (XML is PTML, everything else JSON)
+    pub fn detect(content: &str) -> Self {
+        if content.trim_start().starts_with('<') { Self::Ptml } else { Self::Json }
+    }
+}
+
+/// Parses an annotation file
+pub fn parse_annotations(content: &str) -> Result<ActivityAnnotations> {
+    serde_json::from_str(content).context("Invalid annotation file")
+}
+
+/// Imports a PTML process tree, annotating its visible activities with `annotations`
+pub fn import_ptml(ptml: &str, annotations: &ActivityAnnotations) -> Result<OCPT> {
+    let document = read_ptml(ptml)?;
+    let root = document.root.as_deref().ok_or_else(|| anyhow!("PTML has no process tree root"))?;
+    let mut visited = HashSet::new();
+    Ok(OCPT::new(document.build(root, annotations, &mut visited)?))
+}
+
+/// Imports PM4Py's OCPT JSON representation
+pub fn import_pm4py(value: Value) -> Result<OCPT> {
+    let value = match value {
+        Value::Object(mut map) if map.contains_key("process_tree") => map.remove("process_tree").unwrap_or_default(),
+        value => value,
+    };
+    let node: Pm4pyNode = serde_json::from_value(value).context("Not a PM4Py OCPT")?;
+    Ok(OCPT::new(node.into_ocpt_node()?))
+}
+
+/* ========================= PTML ========================= */
+
+#[derive(Default)]
+struct PtmlDocument {
+    root: Option<String>,
+    /// Tag and name of each node
+    nodes: HashMap<String, (String, String)>,
+    /// Children of each node in document order
+    children: HashMap<String, Vec<String>>,
+}
+
+fn read_ptml(ptml: &str) -> Result<PtmlDocument> {
+    let mut reader = Reader::from_str(ptml);
+    reader.trim_text(true);
+    let mut document = PtmlDocument::default();
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) => {
+                let tag = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                let mut attrs = attributes(&e)?;
+                match tag.as_str() {
+                    "ptml" => {}
+                    "processTree" => {
+                        if document.root.is_some() {
+                            bail!("PTML files with several process trees are not supported");
+                        }
+                        document.root = attrs.remove("root");
+                    }
+                    "parentsNode" => {
+                        let (Some(source), Some(target)) = (attrs.remove("sourceId"), attrs.remove("targetId")) else {
+                            bail!("parentsNode without sourceId or targetId");
+                        };
+                        document.children.entry(source).or_default().push(target);
+                    }
+                    _ => {
+                        let id = attrs.remove("id").ok_or_else(|| anyhow!("<{tag}> without id"))?;
+                        let name = attrs.remove("name").unwrap_or_default();
+                        document.nodes.insert(id, (tag, name));
+                    }
+                }
+            }
+            Event::Eof => return Ok(document),
+            _ => {}
+        }
+    }
+}
+
+fn attributes(e: &BytesStart) -> Result<HashMap<String, String>> {
+    let mut attrs = HashMap::new();
+    for attr in e.attributes() {
+        let attr = attr?;
+        let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).into_owned();
+        attrs.insert(key, attr.unescape_value()?.into_owned());
+    }
+    Ok(attrs)
+}
+
+impl PtmlDocument {
+    fn build(&self, id: &str, annotations: &ActivityAnnotations, visited: &mut HashSet<String>) -> Result<OCPTNode> {
+        if !visited.insert(id.to_string()) {
+            bail!("PTML node {id} has several parents");
+        }
+        let (tag, name) = self.nodes.get(id).ok_or_else(|| anyhow!("PTML references unknown node {id}"))?;
+        let children = self.children.get(id).map(Vec::as_slice).unwrap_or_default();
+        let operator_type = match tag.as_str() {
+            "manualTask" => {
+                let ots = annotations
+                    .get(name)
+                    .ok_or_else(|| anyhow!("No object-type annotation for activity: {name}"))?;
+                let value = ActivityValue { isSilent: None, activity: name.clone(), ots: ots.clone() };
+                return Ok(OCPTNode::Leaf(frontend_activity_to_leaf(&value)));
+            }
+            "automaticTask" => return Ok(OCPTNode::Leaf(OCPTLeaf::new(None))),
+            "sequence" => OCPTOperatorType::Sequence,
+            "xor" => OCPTOperatorType::ExclusiveChoice,
+            "and" => OCPTOperatorType::Concurrency,
+            "xorLoop" => return self.build_loop(id, children, annotations, visited),
+            other => bail!("Unsupported PTML node: <{other}>"),
+        };
+        let mut node = OCPTNode::new_operator(operator_type);
+        for child in children {
+            node.add_child(self.build(child, annotations, visited)?);
+        }
+        Ok(node)
+    }
+
+    /// ProM loops execute `do (redo do)* exit`; a silent exit is dropped, a visible one is
+    /// sequenced after the loop
+    fn build_loop(
+        &self,
+        id: &str,
+        children: &[String],
+        annotations: &ActivityAnnotations,
+        visited: &mut HashSet<String>,
+    ) -> Result<OCPTNode> {
+        let [do_part, redo, exit] = children else {
+            bail!("PTML loop {id} needs a do, a redo and an exit child, found {} children", children.len());
+        };
+        let mut loop_node = OCPTNode::new_operator(OCPTOperatorType::Loop(None));
+        loop_node.add_child(self.build(do_part, annotations, visited)?);
+        loop_node.add_child(self.build(redo, annotations, visited)?);
+        match self.build(exit, annotations, visited)? {
+            OCPTNode::Leaf(leaf) if leaf.activity_label == OCPTLeafLabel::Tau => Ok(loop_node),
+            exit => {
+                let mut sequence = OCPTNode::new_operator(OCPTOperatorType::Sequence);
+                sequence.add_child(loop_node);
+                sequence.add_child(exit);
+                Ok(sequence)
+            }
+        }
+    }
+}
+
+/* ========================= PM4Py ========================= */
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Pm4pyNode {
+    #[serde(default)]
+    operator: Option<String>,
+    #[serde(default)]
+    label: Option<String>,
+    #[serde(default)]
+    children: Vec<Pm4pyNode>,
+    #[serde(default)]
+    related: Vec<String>,
+    #[serde(default)]
+    divergent: Vec<String>,
+    #[serde(default)]
+    convergent: Vec<String>,
+    #[serde(default)]
+    deficient: Vec<String>,
+}
+
+impl Pm4pyNode {
+    fn into_ocpt_node(self) -> Result<OCPTNode> {
+        let Some(operator) = self.operator else {
+            if !self.children.is_empty() {
+                bail!("PM4Py leaf {:?} has children", self.label);
+            }
+            let mut leaf = OCPTLeaf::new(self.label);
+            for (ob_types, marked) in [
+                (self.divergent, &mut leaf.divergent_ob_types),
+                (self.convergent, &mut leaf.convergent_ob_types),
+                (self.deficient, &mut leaf.deficient_ob_types),
+            ] {
+                leaf.related_ob_types.extend(ob_types.iter().cloned());
+                marked.extend(ob_types);
+            }
+            leaf.related_ob_types.extend(self.related);
+            return Ok(OCPTNode::Leaf(leaf));
+        };
+        let operator_type = match operator.trim() {
+            "->" => OCPTOperatorType::Sequence,
+            "X" => OCPTOperatorType::ExclusiveChoice,
+            "+" => OCPTOperatorType::Concurrency,
+            "*" => OCPTOperatorType::Loop(None),
+            "O" => bail!("PM4Py OR operators are not supported"),
+            name => parse_operator(name)?,
+        };
+        let mut node = OCPTNode::new_operator(operator_type);
+        for child in self.children {
+            node.add_child(child.into_ocpt_node()?);
+        }
+        Ok(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(node: &OCPTNode) -> &OCPTLeaf {
+        match node {
+            OCPTNode::Leaf(leaf) => leaf,
+            OCPTNode::Operator(_) => panic!("expected leaf"),
+        }
+    }
+
+    fn children(node: &OCPTNode) -> &[OCPTNode] {
+        match node {
+            OCPTNode::Operator(op) => &op.children,
+            OCPTNode::Leaf(_) => panic!("expected operator"),
+        }
+    }
+
+    #[test]
+    fn test_import_ptml_with_annotations() {
+        let ptml = r#"<?xml version="1.0" encoding="utf-8"?>
+<ptml><processTree id="t" name="order" root="1">
+<sequence id="1" name=""/>
+<manualTask id="2" name="place order"/>
+<xorLoop id="3" name=""/>
+<manualTask id="4" name="pick item"/>
+<automaticTask id="5" name=""/>
+<manualTask id="6" name="ship"/>
+<parentsNode id="p1" sourceId="1" targetId="2"/>
+<parentsNode id="p2" sourceId="1" targetId="3"/>
+<parentsNode id="p3" sourceId="3" targetId="4"/>
+<parentsNode id="p4" sourceId="3" targetId="5"/>
+<parentsNode id="p5" sourceId="3" targetId="6"/>
+</processTree></ptml>"#;
+        let annotations = parse_annotations(
+            r#"{"place order": [{"ot": "order"}, {"ot": "item", "exhibits": ["div"]}],
+                "pick item": [{"ot": "item"}],
+                "ship": [{"ot": "order"}, {"ot": "item", "exhibits": ["con"]}]}"#,
+        )
+        .unwrap();
+        let ocpt = import_ptml(ptml, &annotations).unwrap();
+        assert!(ocpt.is_valid());
+
+        // seq(place order, seq(loop(pick item, tau), ship))
+        let root = children(&ocpt.root);
+        assert!(leaf(&root[0]).divergent_ob_types.contains("item"));
+        let exit_sequence = children(&root[1]);
+        assert_eq!(children(&exit_sequence[0]).len(), 2);
+        assert_eq!(leaf(&children(&exit_sequence[0])[1]).activity_label, OCPTLeafLabel::Tau);
+        assert!(leaf(&exit_sequence[1]).convergent_ob_types.contains("item"));
+
+        let mut missing = annotations;
+        missing.remove("ship");
+        let err = import_ptml(ptml, &missing).unwrap_err();
+        assert!(err.to_string().contains("ship"));
+    }
+
+    #[test]
+    fn test_import_pm4py() {
+        let value = serde_json::json!({"process_tree": {
+            "operator": "*",
+            "children": [
+                {"operator": "->", "children": [
+                    {"label": "place order", "related": ["order"], "divergent": ["item"]},
+                    {"label": "pay", "related": ["order"], "deficient": ["order"]}
+                ]},
+                {"label": null}
+            ]
+        }});
+        let ocpt = import_pm4py(value).unwrap();
+        assert!(ocpt.is_valid());
+        let sequence = children(&children(&ocpt.root)[0]);
+        let place = leaf(&sequence[0]);
+        assert_eq!(place.related_ob_types.len(), 2);
+        assert!(place.divergent_ob_types.contains("item"));
+        assert!(leaf(&sequence[1]).deficient_ob_types.contains("order"));
+
+        assert!(import_pm4py(serde_json::json!({"operator": "O", "children": []})).is_err());
+        assert!(import_pm4py(serde_json::json!({"root": {}})).is_err());
+    }
+}
@@ -0,0 +1,88 @@
+//! Object-centric process tree (OCPT) transformations: flattening per object type,
+//! exports to PTML, PNML and BPMN, imports from PTML and PM4Py,
+//! validation, reduction to a canonical form, uuid-addressed editing with version history,
+//! and playout into synthetic OCEL logs.
+pub mod bpmn;
+pub mod edit;
+pub mod flatten;
+pub mod history;
+pub mod import;
+pub mod playout;
+pub mod pnml;
+pub mod ptml;
+pub mod reduce;
+pub mod validation;
+
+use crate::models::ocpt::OCPTLeaf;
+
+/// Serialization formats of an OCPT
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OcptFormat {
+    /// The frontend JSON shape
+    Json,
+    /// Flattened process trees (ProM / PM4Py)
+    Ptml,
+    /// Object-centric Petri net with typed places
+    Pnml,
+    /// BPMN 2.0 approximation with diagram layout
+    Bpmn,
+}
+
+impl OcptFormat {
+    /// Parses a format name as given in the `format` query parameter
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s.trim().trim_start_matches('.').to_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "ptml" => Some(Self::Ptml),
+            "pnml" => Some(Self::Pnml),
+            "bpmn" => Some(Self::Bpmn),
+            _ => None,
+        }
+    }
+
+    /// The MIME type used when serving this format
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Ptml | Self::Pnml | Self::Bpmn => "application/xml",
+        }
+    }
+
+    /// The file extension used when serving this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Ptml => "ptml",
+            Self::Pnml => "pnml",
+            Self::Bpmn => "bpmn",
+        }
+    }
+}
+
+/// The names (in order divergent, convergent, deficient) of the exhibits of `ob_type` at `leaf`
+pub fn leaf_exhibits<'a>(leaf: &OCPTLeaf, ob_type: &str, names: [&'a str; 3]) -> Vec<&'a str> {
+    [&leaf.divergent_ob_types, &leaf.convergent_ob_types, &leaf.deficient_ob_types]
+        .into_iter()
+        .zip(names)
+        .filter(|(set, _)| set.contains(ob_type))
+        .map(|(_, name)| name)
+        .collect()
+}
+
+/// Escapes text for XML attributes and content
+fn xml_escape(s: &str) -> std::borrow::Cow<'_, str> {
+    quick_xml::escape::escape(s)
+}
+
+/// Deterministic UUID-formatted ids for generated XML elements
+#[derive(Default)]
+struct IdGenerator {
+    next: u128,
+}
+
+impl IdGenerator {
+    fn next_id(&mut self) -> String {
+        self.next += 1;
+        uuid::Uuid::from_u128(self.next).to_string()
+    }
+}
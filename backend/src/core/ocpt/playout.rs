@@ -0,0 +1,495 @@
+//! Playout of an [`OCPT`] into a synthetic OCEL 2.0 log.
+//!
+//! Every case creates objects of each object type of the tree and executes the tree once:
+//! sequences run their children in order, exclusive choices pick a child uniformly, children of
+//! concurrencies start at the same time, and loops repeat (do, redo) with a configurable
+//! probability, at most as often as their bound allows.
+//!
+//! An executed leaf produces events for the case objects of its related types:
+//! - objects of types that are neither convergent nor divergent get exactly one event each,
+//! - convergent types are split into groups (of [`PlayoutOptions::convergent_objects`]
+//!   objects) that share an event,
+//! - divergent types may take part in several events; without other types, the leaf executes
+//!   [`PlayoutOptions::divergent_events`] times,
+//! - deficient types are left out of an event with
+//!   [`PlayoutOptions::deficient_skip_probability`].
+//!
+//! If several non-convergent, non-divergent types have different numbers of objects, the
+//! events beyond the smaller counts lack the smaller types. Objects that end up without events
+//! are not part of the log. Timestamps advance by durations drawn per activity; with a `seed`,
+//! the playout is reproducible.
+use chrono::{DateTime, FixedOffset, TimeDelta};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_distr::{Distribution, Exp, Normal};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use super::flatten::ocpt_object_types;
+use crate::models::ocel::{OCELEvent, OCELObject, OCELRelationship, OCELType, OCEL};
+use crate::models::ocpt::{OCPTLeaf, OCPTLeafLabel, OCPTNode, OCPTOperatorType, OCPT};
+
+/// Upper bound on the number of generated events
+pub const MAX_EVENTS: usize = 1_000_000;
+/// Upper bound on the number of generated objects
+pub const MAX_OBJECTS: usize = 1_000_000;
+/// Upper bound on [`PlayoutOptions::cases`]
+pub const MAX_CASES: u32 = 100_000;
+/// Upper bound on the objects (and group sizes and executions) in the count ranges of
+/// [`PlayoutOptions`]
+pub const MAX_COUNT: u32 = 10_000;
+/// Upper bound on durations (about ten years); longer samples are cut off
+pub const MAX_DURATION_SECONDS: f64 = 315_360_000.0;
+
+/// Distribution of durations (in seconds, negative samples count as 0)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "distribution", rename_all = "snake_case")]
+pub enum DurationDistribution {
+    Fixed { seconds: f64 },
+    Uniform { min_seconds: f64, max_seconds: f64 },
+    Exponential { mean_seconds: f64 },
+    Normal { mean_seconds: f64, std_dev_seconds: f64 },
+}
+
+impl DurationDistribution {
+    fn check(&self) -> Result<(), String> {
+        let in_range = |seconds: f64| seconds.is_finite() && seconds.abs() <= MAX_DURATION_SECONDS;
+        let valid = match *self {
+            Self::Fixed { seconds } => in_range(seconds) && seconds >= 0.0,
+            Self::Uniform { min_seconds, max_seconds } => {
+                in_range(min_seconds) && in_range(max_seconds) && 0.0 <= min_seconds && min_seconds <= max_seconds
+            }
+            Self::Exponential { mean_seconds } => in_range(mean_seconds) && mean_seconds > 0.0,
+            Self::Normal { mean_seconds, std_dev_seconds } => {
+                in_range(mean_seconds) && in_range(std_dev_seconds) && std_dev_seconds >= 0.0
+            }
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(format!("Invalid duration distribution (at most {MAX_DURATION_SECONDS} seconds): {self:?}"))
+        }
+    }
+
+    fn sample(&self, rng: &mut StdRng) -> TimeDelta {
+        let seconds = match *self {
+            Self::Fixed { seconds } => seconds,
+            Self::Uniform { min_seconds, max_seconds } => rng.gen_range(min_seconds..=max_seconds),
+            Self::Exponential { mean_seconds } => Exp::new(1.0 / mean_seconds).map_or(0.0, |d| d.sample(rng)),
+            Self::Normal { mean_seconds, std_dev_seconds } => {
+                Normal::new(mean_seconds, std_dev_seconds).map_or(mean_seconds, |d| d.sample(rng))
+            }
+        };
+        TimeDelta::milliseconds((seconds.clamp(0.0, MAX_DURATION_SECONDS) * 1000.0).round() as i64)
+    }
+}
+
+/// Inclusive range of counts
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CountRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl CountRange {
+    fn check(&self, name: &str) -> Result<(), String> {
+        if self.min > self.max {
+            return Err(format!("{name}: min must not exceed max"));
+        }
+        if self.max > MAX_COUNT {
+            return Err(format!("{name}: max must not exceed {MAX_COUNT}"));
+        }
+        Ok(())
+    }
+
+    fn sample(&self, rng: &mut StdRng) -> usize {
+        rng.gen_range(self.min..=self.max) as usize
+    }
+}
+
+/// Options of [`playout_ocpt`]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PlayoutOptions {
+    /// Number of cases (executions of the tree)
+    pub cases: u32,
+    /// Seed of the random generator (default: random)
+    pub seed: Option<u64>,
+    /// Start of the first case (default: 2024-01-01T00:00:00Z)
+    pub start_time: Option<DateTime<FixedOffset>>,
+    /// Objects created per case for specific object types
+    pub objects_per_type: HashMap<String, CountRange>,
+    /// Objects created per case for all other object types
+    pub default_objects_per_type: CountRange,
+    /// Size of the object groups sharing an event of a convergent leaf
+    pub convergent_objects: CountRange,
+    /// Executions of a leaf whose related types are all divergent
+    pub divergent_events: CountRange,
+    /// Probability that a deficient type is left out of an event
+    pub deficient_skip_probability: f64,
+    /// Probability of another iteration of a loop
+    pub loop_repeat_probability: f64,
+    /// Bound on the iterations of loops without bound
+    pub max_loop_repetitions: u32,
+    /// Time between the start of consecutive cases
+    pub case_arrival: DurationDistribution,
+    /// Duration before an event of an activity, per activity
+    pub activity_durations: HashMap<String, DurationDistribution>,
+    /// Duration before an event of any other activity
+    pub default_duration: DurationDistribution,
+}
+
+impl Default for PlayoutOptions {
+    fn default() -> Self {
+        Self {
+            cases: 10,
+            seed: None,
+            start_time: None,
+            objects_per_type: HashMap::new(),
+            default_objects_per_type: CountRange { min: 1, max: 3 },
+            convergent_objects: CountRange { min: 1, max: 5 },
+            divergent_events: CountRange { min: 1, max: 3 },
+            deficient_skip_probability: 0.3,
+            loop_repeat_probability: 0.3,
+            max_loop_repetitions: 5,
+            case_arrival: DurationDistribution::Exponential { mean_seconds: 3600.0 },
+            activity_durations: HashMap::new(),
+            default_duration: DurationDistribution::Exponential { mean_seconds: 1800.0 },
+        }
+    }
+}
+
+impl PlayoutOptions {
+    /// Checks limits, ranges, probabilities and distributions
+    pub fn check(&self) -> Result<(), String> {
+        if self.cases > MAX_CASES {
+            return Err(format!("cases must not exceed {MAX_CASES}"));
+        }
+        self.default_objects_per_type.check("default_objects_per_type")?;
+        for (ot, range) in &self.objects_per_type {
+            range.check(&format!("objects_per_type.{ot}"))?;
+        }
+        self.convergent_objects.check("convergent_objects")?;
+        if self.convergent_objects.min == 0 {
+            return Err("convergent_objects: min must be at least 1".to_string());
+        }
+        self.divergent_events.check("divergent_events")?;
+        for (name, p) in [
+            ("deficient_skip_probability", self.deficient_skip_probability),
+            ("loop_repeat_probability", self.loop_repeat_probability),
+        ] {
+            if !(0.0..=1.0).contains(&p) {
+                return Err(format!("{name} must be between 0 and 1"));
+            }
+        }
+        self.case_arrival.check()?;
+        self.default_duration.check()?;
+        self.activity_durations.values().try_for_each(DurationDistribution::check)
+    }
+}
+
+/// Plays out `ocpt` into an OCEL with `options.cases` cases
+pub fn playout_ocpt(ocpt: &OCPT, options: &PlayoutOptions) -> Result<OCEL, String> {
+    options.check()?;
+    if !ocpt.is_valid() {
+        return Err("Cannot play out an invalid OCPT".to_string());
+    }
+    let mut player = Player {
+        options,
+        rng: options.seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64),
+        events: Vec::new(),
+        objects: Vec::new(),
+        used_objects: HashSet::new(),
+    };
+    let ob_types = ocpt_object_types(ocpt);
+    let mut case_start = options
+        .start_time
+        .unwrap_or_else(|| DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap());
+    for _ in 0..options.cases {
+        let case_objects = player.create_objects(&ob_types)?;
+        player.execute(&ocpt.root, &case_objects, case_start)?;
+        case_start = advance(case_start, options.case_arrival.sample(&mut player.rng))?;
+    }
+    Ok(player.into_ocel(&ob_types))
+}
+
+/// `time + duration`, or an error if it leaves the supported date range
+fn advance(time: DateTime<FixedOffset>, duration: TimeDelta) -> Result<DateTime<FixedOffset>, String> {
+    time.checked_add_signed(duration)
+        .ok_or_else(|| "Playout timestamps exceed the supported date range".to_string())
+}
+
+/// Ids of the objects of a case, per object type
+type CaseObjects = BTreeMap<String, Vec<String>>;
+
+struct Player<'a> {
+    options: &'a PlayoutOptions,
+    rng: StdRng,
+    /// Generated events with their case-local order
+    events: Vec<OCELEvent>,
+    objects: Vec<OCELObject>,
+    used_objects: HashSet<String>,
+}
+
+impl Player<'_> {
+    fn create_objects(&mut self, ob_types: &BTreeSet<String>) -> Result<CaseObjects, String> {
+        let mut case_objects = CaseObjects::new();
+        for ot in ob_types {
+            let range = self.options.objects_per_type.get(ot).unwrap_or(&self.options.default_objects_per_type);
+            let count = range.sample(&mut self.rng);
+            if self.objects.len() + count > MAX_OBJECTS {
+                return Err(format!("Playout exceeds {MAX_OBJECTS} objects"));
+            }
+            let ids = case_objects.entry(ot.clone()).or_default();
+            for _ in 0..count {
+                let id = format!("{ot}_{}", self.objects.len() + 1);
+                self.objects.push(OCELObject {
+                    id: id.clone(),
+                    object_type: ot.clone(),
+                    attributes: Vec::new(),
+                    relationships: Vec::new(),
+                });
+                ids.push(id);
+            }
+        }
+        Ok(case_objects)
+    }
+
+    /// Executes `node` from `start`, returning the time its execution ends
+    fn execute(
+        &mut self,
+        node: &OCPTNode,
+        objects: &CaseObjects,
+        start: DateTime<FixedOffset>,
+    ) -> Result<DateTime<FixedOffset>, String> {
+        let op = match node {
+            OCPTNode::Leaf(leaf) => return self.execute_leaf(leaf, objects, start),
+            OCPTNode::Operator(op) => op,
+        };
+        match op.operator_type {
+            OCPTOperatorType::Sequence => {
+                let mut time = start;
+                for child in &op.children {
+                    time = self.execute(child, objects, time)?;
+                }
+                Ok(time)
+            }
+            OCPTOperatorType::ExclusiveChoice => {
+                let child = op.children.choose(&mut self.rng).ok_or("Empty exclusive choice")?;
+                self.execute(child, objects, start)
+            }
+            OCPTOperatorType::Concurrency => {
+                let mut end = start;
+                for child in &op.children {
+                    end = end.max(self.execute(child, objects, start)?);
+                }
+                Ok(end)
+            }
+            OCPTOperatorType::Loop(bound) => {
+                let (do_part, redo_parts) = op.children.split_first().ok_or("Empty loop")?;
+                let max_repetitions = bound.unwrap_or(self.options.max_loop_repetitions);
+                let mut time = self.execute(do_part, objects, start)?;
+                let mut repetitions = 0;
+                while repetitions < max_repetitions && self.rng.gen_bool(self.options.loop_repeat_probability) {
+                    let redo = redo_parts.choose(&mut self.rng).ok_or("Loop without redo part")?;
+                    time = self.execute(redo, objects, time)?;
+                    time = self.execute(do_part, objects, time)?;
+                    repetitions += 1;
+                }
+                Ok(time)
+            }
+        }
+    }
+
+    fn execute_leaf(
+        &mut self,
+        leaf: &OCPTLeaf,
+        objects: &CaseObjects,
+        start: DateTime<FixedOffset>,
+    ) -> Result<DateTime<FixedOffset>, String> {
+        let OCPTLeafLabel::Activity(activity) = &leaf.activity_label else {
+            return Ok(start);
+        };
+        let related: BTreeSet<&String> = leaf.related_ob_types.iter().collect();
+        let objects_of = |ot: &String| objects.get(ot).map(Vec::as_slice).unwrap_or_default();
+        let is_conv = |ot: &String| leaf.convergent_ob_types.contains(ot);
+        let is_div = |ot: &String| leaf.divergent_ob_types.contains(ot);
+
+        // Objects of the first convergent, non-divergent type in groups sharing an event
+        let first_conv = related.iter().find(|ot| is_conv(ot) && !is_div(ot));
+        let mut conv_groups = Vec::new();
+        if let Some(ot) = first_conv {
+            let mut remaining = objects_of(ot);
+            while !remaining.is_empty() {
+                let size = self.options.convergent_objects.sample(&mut self.rng).clamp(1, remaining.len());
+                let (group, rest) = remaining.split_at(size);
+                conv_groups.push(group);
+                remaining = rest;
+            }
+        }
+        let one_to_one: Vec<&&String> = related.iter().filter(|ot| !is_conv(ot) && !is_div(ot)).collect();
+        let n = if !one_to_one.is_empty() {
+            one_to_one.iter().map(|ot| objects_of(ot).len()).max().unwrap_or(0)
+        } else if first_conv.is_some() {
+            conv_groups.len()
+        } else if related.iter().any(|ot| is_div(ot)) {
+            self.options.divergent_events.sample(&mut self.rng)
+        } else {
+            1
+        };
+        if self.events.len() + n > MAX_EVENTS {
+            return Err(format!("Playout exceeds {MAX_EVENTS} events"));
+        }
+
+        let duration = self.options.activity_durations.get(activity).unwrap_or(&self.options.default_duration);
+        let mut time = start;
+        for i in 0..n {
+            let mut bound: Vec<&String> = Vec::new();
+            for ot in &related {
+                if leaf.deficient_ob_types.contains(*ot) && self.rng.gen_bool(self.options.deficient_skip_probability) {
+                    continue;
+                }
+                let ids = objects_of(ot);
+                if ids.is_empty() {
+                    continue;
+                }
+                match (is_conv(ot), is_div(ot)) {
+                    (false, false) => bound.extend(ids.get(i)),
+                    (true, false) if Some(ot) == first_conv => bound.extend(conv_groups.get(i).into_iter().flat_map(|g| g.iter())),
+                    (true, false) => bound.extend(ids.iter().skip(i).step_by(n)),
+                    (false, true) => bound.push(&ids[i % ids.len()]),
+                    (true, true) => {
+                        let size = self.options.convergent_objects.sample(&mut self.rng).clamp(1, ids.len());
+                        bound.extend(ids.choose_multiple(&mut self.rng, size));
+                    }
+                }
+            }
+            if bound.is_empty() && !related.is_empty() {
+                continue;
+            }
+            time = advance(time, duration.sample(&mut self.rng))?;
+            self.used_objects.extend(bound.iter().map(|id| id.to_string()));
+            self.events.push(OCELEvent {
+                id: format!("e{}", self.events.len() + 1),
+                event_type: activity.clone(),
+                time,
+                attributes: Vec::new(),
+                relationships: bound
+                    .into_iter()
+                    .map(|id| OCELRelationship { object_id: id.clone(), qualifier: String::new() })
+                    .collect(),
+            });
+        }
+        Ok(time)
+    }
+
+    fn into_ocel(self, ob_types: &BTreeSet<String>) -> OCEL {
+        let mut events = self.events;
+        // Stable: events of the same time keep their execution order
+        events.sort_by_key(|e| e.time);
+        let event_types: BTreeSet<&String> = events.iter().map(|e| &e.event_type).collect();
+        let used_objects = self.used_objects;
+        OCEL {
+            event_types: event_types
+                .into_iter()
+                .map(|name| OCELType { name: name.clone(), attributes: Vec::new() })
+                .collect(),
+            object_types: ob_types
+                .iter()
+                .map(|name| OCELType { name: name.clone(), attributes: Vec::new() })
+                .collect(),
+            objects: self.objects.into_iter().filter(|o| used_objects.contains(&o.id)).collect(),
+            events,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(label: &str, related: &[&str]) -> OCPTNode {
+        let mut node = OCPTNode::new_leaf(Some(label.to_string()));
+        related.iter().for_each(|ot| node.add_related_ob_type(&ot.to_string()));
+        node
+    }
+
+    fn object_types_of<'a>(ocel: &'a OCEL, event: &OCELEvent) -> Vec<&'a str> {
+        event
+            .relationships
+            .iter()
+            .map(|r| ocel.objects.iter().find(|o| o.id == r.object_id).unwrap().object_type.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_playout_ocpt() {
+        // place order (order, items convergent) -> loop(pick item (item, order divergent), tau) max 1
+        let mut place = leaf("place order", &["order", "item"]);
+        place.add_convergent_ob_type(&"item".to_string());
+        let mut pick = leaf("pick item", &["order", "item"]);
+        pick.add_divergent_ob_type(&"order".to_string());
+        let mut repeat = OCPTNode::new_operator(OCPTOperatorType::Loop(Some(1)));
+        repeat.add_child(pick);
+        repeat.add_child(OCPTNode::new_leaf(None));
+        let mut root = OCPTNode::new_operator(OCPTOperatorType::Sequence);
+        root.add_child(place);
+        root.add_child(repeat);
+        let ocpt = OCPT::new(root);
+
+        let options = PlayoutOptions {
+            cases: 20,
+            seed: Some(7),
+            objects_per_type: HashMap::from([("order".to_string(), CountRange { min: 1, max: 1 })]),
+            default_objects_per_type: CountRange { min: 2, max: 4 },
+            convergent_objects: CountRange { min: 10, max: 10 },
+            loop_repeat_probability: 1.0,
+            ..Default::default()
+        };
+        let ocel = playout_ocpt(&ocpt, &options).unwrap();
+        let count = |activity: &str| ocel.events.iter().filter(|e| e.event_type == activity).count();
+        let items = ocel.objects.iter().filter(|o| o.object_type == "item").count();
+
+        // One order and all items of a case in one event
+        assert_eq!(count("place order"), 20);
+        for event in ocel.events.iter().filter(|e| e.event_type == "place order") {
+            let types = object_types_of(&ocel, event);
+            assert_eq!(types.iter().filter(|t| **t == "order").count(), 1);
+            assert!(types.iter().filter(|t| **t == "item").count() >= 2);
+        }
+        // Each item picked once per iteration; the bound allows exactly one repetition
+        assert_eq!(count("pick item"), 2 * items);
+        assert!(ocel.events.windows(2).all(|w| w[0].time <= w[1].time));
+
+        // Reproducible with the same seed
+        let again = playout_ocpt(&ocpt, &options).unwrap();
+        assert_eq!(serde_json::to_string(&again).unwrap(), serde_json::to_string(&ocel).unwrap());
+
+        let invalid = PlayoutOptions { loop_repeat_probability: 2.0, ..Default::default() };
+        assert!(playout_ocpt(&ocpt, &invalid).is_err());
+    }
+
+    #[test]
+    fn test_playout_limits() {
+        let ocpt = OCPT::new(leaf("pay", &["order"]));
+        let huge = CountRange { min: u32::MAX, max: u32::MAX };
+        for options in [
+            PlayoutOptions { cases: u32::MAX, ..Default::default() },
+            PlayoutOptions { default_objects_per_type: huge, ..Default::default() },
+            PlayoutOptions { objects_per_type: HashMap::from([("order".to_string(), huge)]), ..Default::default() },
+            PlayoutOptions { default_duration: DurationDistribution::Fixed { seconds: 1e15 }, ..Default::default() },
+        ] {
+            assert!(options.check().is_err(), "{options:?}");
+        }
+
+        // Timestamps beyond the date range of chrono are an error, not a panic
+        let options = PlayoutOptions {
+            seed: Some(1),
+            start_time: Some(DateTime::<chrono::Utc>::MAX_UTC.fixed_offset() - TimeDelta::days(1)),
+            default_duration: DurationDistribution::Fixed { seconds: MAX_DURATION_SECONDS },
+            ..Default::default()
+        };
+        assert!(playout_ocpt(&ocpt, &options).is_err());
+    }
+}
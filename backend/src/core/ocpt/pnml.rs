@@ -0,0 +1,264 @@
+//! PNML export of an OCPT as object-centric Petri net.
+//!
+//! Every object type gets a workflow net translated from its flattened process tree
+//! ([`super::flatten`]), whose places are typed with the object type. Visible transitions
+//! originating from the same OCPT leaf are fused across object types, so a transition consumes
+//! and produces tokens of all object types related to its activity. Arcs of object types that
+//! are convergent for the activity (several objects per event) are marked as variable arcs.
+//!
+//! Types and variable arcs are stored as `toolspecific` elements (tool `ocpt`); silent
+//! transitions use the ProM convention understood by PM4Py.
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+use uuid::Uuid;
+
+use super::flatten::{flatten_ocpt, ocpt_object_types, FlatNode, FlatOperator};
+use super::xml_escape;
+use crate::models::ocpt::{OCPTLeafLabel, OCPT};
+
+const TOOL: &str = "ocpt";
+
+struct Place {
+    id: String,
+    ob_type: String,
+    is_initial: bool,
+    is_final: bool,
+}
+
+struct Transition {
+    id: String,
+    /// `None` for silent transitions
+    label: Option<String>,
+}
+
+struct Arc {
+    source: String,
+    target: String,
+    variable: bool,
+}
+
+#[derive(Default)]
+struct NetBuilder {
+    places: Vec<Place>,
+    transitions: Vec<Transition>,
+    /// Visible transitions by the OCPT leaf they originate from
+    fused: HashMap<Uuid, usize>,
+    arcs: Vec<Arc>,
+}
+
+/// The net being built for one object type
+struct TypedNet<'a> {
+    ob_type: &'a str,
+    /// Leaves for which `ob_type` is convergent
+    convergent: &'a HashSet<Uuid>,
+}
+
+impl NetBuilder {
+    fn place(&mut self, ob_type: &str) -> String {
+        let id = format!("p{}", self.places.len());
+        self.places.push(Place { id: id.clone(), ob_type: ob_type.to_string(), is_initial: false, is_final: false });
+        id
+    }
+
+    fn silent(&mut self) -> String {
+        let id = format!("tau{}", self.transitions.len());
+        self.transitions.push(Transition { id: id.clone(), label: None });
+        id
+    }
+
+    fn visible(&mut self, uuid: Option<Uuid>, label: &str) -> String {
+        if let Some(&index) = uuid.and_then(|u| self.fused.get(&u)) {
+            return self.transitions[index].id.clone();
+        }
+        let id = format!("t{}", self.transitions.len());
+        if let Some(uuid) = uuid {
+            self.fused.insert(uuid, self.transitions.len());
+        }
+        self.transitions.push(Transition { id: id.clone(), label: Some(label.to_string()) });
+        id
+    }
+
+    fn arc(&mut self, source: &str, target: &str, variable: bool) {
+        self.arcs.push(Arc { source: source.to_string(), target: target.to_string(), variable });
+    }
+
+    /// Connects `source` and `target` (places of `net.ob_type`) by the subnet of `node`
+    fn translate(&mut self, net: &TypedNet, node: &FlatNode, source: &str, target: &str) {
+        match node {
+            FlatNode::Leaf { uuid, label } => {
+                let (transition, variable) = match label {
+                    Some(label) => (
+                        self.visible(*uuid, label),
+                        uuid.is_some_and(|u| net.convergent.contains(&u)),
+                    ),
+                    None => (self.silent(), false),
+                };
+                self.arc(source, &transition, variable);
+                self.arc(&transition, target, variable);
+            }
+            FlatNode::Operator { operator: FlatOperator::Sequence, children } => {
+                let mut from = source.to_string();
+                for (i, child) in children.iter().enumerate() {
+                    let to = if i + 1 == children.len() { target.to_string() } else { self.place(net.ob_type) };
+                    self.translate(net, child, &from, &to);
+                    from = to;
+                }
+            }
+            FlatNode::Operator { operator: FlatOperator::Xor, children } => {
+                for child in children {
+                    self.translate(net, child, source, target);
+                }
+            }
+            FlatNode::Operator { operator: FlatOperator::Parallel, children } => {
+                let split = self.silent();
+                let join = self.silent();
+                self.arc(source, &split, false);
+                self.arc(&join, target, false);
+                for child in children {
+                    let from = self.place(net.ob_type);
+                    let to = self.place(net.ob_type);
+                    self.arc(&split, &from, false);
+                    self.arc(&to, &join, false);
+                    self.translate(net, child, &from, &to);
+                }
+            }
+            FlatNode::Operator { operator: FlatOperator::Loop, children } => {
+                // Silent entry and exit keep the loop places apart from choices around the loop
+                let (enter, exit) = (self.silent(), self.silent());
+                let (start, end) = (self.place(net.ob_type), self.place(net.ob_type));
+                self.arc(source, &enter, false);
+                self.arc(&enter, &start, false);
+                self.arc(&end, &exit, false);
+                self.arc(&exit, target, false);
+                if let Some(do_part) = children.first() {
+                    self.translate(net, do_part, &start, &end);
+                }
+                for redo in children.iter().skip(1) {
+                    self.translate(net, redo, &end, &start);
+                }
+            }
+        }
+    }
+}
+
+/// Translates the OCPT into an object-centric Petri net and serializes it as PNML
+pub fn export_pnml(ocpt: &OCPT, name: &str) -> String {
+    let mut builder = NetBuilder::default();
+
+    // Convergent object types per visible leaf
+    let mut convergent: BTreeMap<&str, HashSet<Uuid>> = BTreeMap::new();
+    for leaf in ocpt.find_all_leaves() {
+        if let OCPTLeafLabel::Activity(_) = leaf.activity_label {
+            for ob_type in &leaf.convergent_ob_types {
+                convergent.entry(ob_type).or_default().insert(leaf.uuid);
+            }
+        }
+    }
+
+    let no_convergent = HashSet::new();
+    for ob_type in ocpt_object_types(ocpt) {
+        let tree = flatten_ocpt(ocpt, &ob_type);
+        let net = TypedNet {
+            ob_type: &ob_type,
+            convergent: convergent.get(ob_type.as_str()).unwrap_or(&no_convergent),
+        };
+        let source = builder.places.len();
+        let source_id = builder.place(&ob_type);
+        let sink_id = builder.place(&ob_type);
+        builder.places[source].is_initial = true;
+        builder.places[source + 1].is_final = true;
+        builder.translate(&net, &tree, &source_id, &sink_id);
+    }
+
+    write_pnml(&builder, name)
+}
+
+fn write_pnml(builder: &NetBuilder, name: &str) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<pnml>\n");
+    out.push_str("<net id=\"net\" type=\"http://www.pnml.org/version-2009/grammar/pnmlcoremodel\">\n");
+    let _ = writeln!(out, "<name><text>{}</text></name>", xml_escape(name));
+    out.push_str("<page id=\"page\">\n");
+
+    for place in &builder.places {
+        let _ = writeln!(out, "<place id=\"{}\">", place.id);
+        let _ = writeln!(out, "<name><text>{}</text></name>", xml_escape(&place.ob_type));
+        let _ = writeln!(
+            out,
+            "<toolspecific tool=\"{TOOL}\" version=\"1.0\"><objectType>{}</objectType></toolspecific>",
+            xml_escape(&place.ob_type)
+        );
+        if place.is_initial {
+            out.push_str("<initialMarking><text>1</text></initialMarking>\n");
+        }
+        out.push_str("</place>\n");
+    }
+
+    for transition in &builder.transitions {
+        let _ = writeln!(out, "<transition id=\"{}\">", transition.id);
+        match &transition.label {
+            Some(label) => {
+                let _ = writeln!(out, "<name><text>{}</text></name>", xml_escape(label));
+            }
+            None => {
+                out.push_str("<name><text>tau</text></name>\n");
+                let _ = writeln!(
+                    out,
+                    "<toolspecific tool=\"ProM\" version=\"6.4\" activity=\"$invisible$\" localNodeID=\"{}\"/>",
+                    transition.id
+                );
+            }
+        }
+        out.push_str("</transition>\n");
+    }
+
+    for (i, arc) in builder.arcs.iter().enumerate() {
+        let _ = write!(out, "<arc id=\"a{i}\" source=\"{}\" target=\"{}\"", arc.source, arc.target);
+        if arc.variable {
+            let _ = writeln!(
+                out,
+                ">\n<toolspecific tool=\"{TOOL}\" version=\"1.0\"><variable>true</variable></toolspecific>\n</arc>"
+            );
+        } else {
+            out.push_str("/>\n");
+        }
+    }
+
+    out.push_str("</page>\n<finalmarkings>\n<marking>\n");
+    for place in builder.places.iter().filter(|p| p.is_final) {
+        let _ = writeln!(out, "<place idref=\"{}\"><text>1</text></place>", place.id);
+    }
+    out.push_str("</marking>\n</finalmarkings>\n</net>\n</pnml>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ocpt::{OCPTNode, OCPTOperatorType};
+
+    #[test]
+    fn test_export_pnml_fuses_transitions() {
+        // seq(a{o, i, con i}, b{o})
+        let mut a = OCPTNode::new_leaf(Some("a".to_string()));
+        a.add_related_ob_type(&"o".to_string());
+        a.add_related_ob_type(&"i".to_string());
+        a.add_convergent_ob_type(&"i".to_string());
+        let mut b = OCPTNode::new_leaf(Some("b".to_string()));
+        b.add_related_ob_type(&"o".to_string());
+        let mut root = OCPTNode::new_operator(OCPTOperatorType::Sequence);
+        root.add_child(a);
+        root.add_child(b);
+        let pnml = export_pnml(&OCPT::new(root), "net");
+
+        // `a` is shared by both object types, `b` only belongs to orders
+        assert_eq!(pnml.matches("<name><text>a</text></name>").count(), 1);
+        assert_eq!(pnml.matches("<name><text>b</text></name>").count(), 1);
+        // source/sink for both types plus the place between a and b for orders
+        assert_eq!(pnml.matches("<place id=").count(), 5);
+        assert_eq!(pnml.matches("<objectType>i</objectType>").count(), 2);
+        assert_eq!(pnml.matches("<initialMarking>").count(), 2);
+        // both item arcs of `a` are variable
+        assert_eq!(pnml.matches("<variable>true</variable>").count(), 2);
+    }
+}
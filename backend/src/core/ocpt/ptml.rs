@@ -0,0 +1,94 @@
+//! PTML export (ProM / PM4Py process tree format) of flattened OCPTs.
+use std::fmt::Write;
+
+use super::flatten::{FlatNode, FlatOperator};
+use super::{xml_escape, IdGenerator};
+
+/// Serializes a flattened process tree as PTML document named `name`
+pub fn export_ptml(tree: &FlatNode, name: &str) -> String {
+    let mut ids = IdGenerator::default();
+    let mut nodes = String::new();
+    let mut parents = String::new();
+    let root = write_node(tree, &mut ids, &mut nodes, &mut parents);
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<ptml>\n");
+    let _ = writeln!(
+        out,
+        "<processTree id=\"{}\" name=\"{}\" root=\"{root}\">",
+        ids.next_id(),
+        xml_escape(name)
+    );
+    out.push_str(&nodes);
+    out.push_str(&parents);
+    out.push_str("</processTree>\n</ptml>\n");
+    out
+}
+
+/// Writes the node and its descendants, returning the id of the node
+fn write_node(node: &FlatNode, ids: &mut IdGenerator, nodes: &mut String, parents: &mut String) -> String {
+    let id = ids.next_id();
+    match node {
+        FlatNode::Leaf { label: Some(label), .. } => {
+            let _ = writeln!(nodes, "<manualTask id=\"{id}\" name=\"{}\"/>", xml_escape(label));
+        }
+        FlatNode::Leaf { label: None, .. } => {
+            let _ = writeln!(nodes, "<automaticTask id=\"{id}\" name=\"\"/>");
+        }
+        FlatNode::Operator { operator, children } => {
+            let tag = match operator {
+                FlatOperator::Sequence => "sequence",
+                FlatOperator::Xor => "xor",
+                FlatOperator::Parallel => "and",
+                FlatOperator::Loop => "xorLoop",
+            };
+            let _ = writeln!(nodes, "<{tag} id=\"{id}\" name=\"\"/>");
+
+            // ProM loops have a do, a redo and an exit child
+            let exit = FlatNode::Leaf { uuid: None, label: None };
+            let children = children
+                .iter()
+                .chain((*operator == FlatOperator::Loop).then_some(&exit));
+            for child in children {
+                let child_id = write_node(child, ids, nodes, parents);
+                let _ = writeln!(
+                    parents,
+                    "<parentsNode id=\"{}\" sourceId=\"{id}\" targetId=\"{child_id}\"/>",
+                    ids.next_id()
+                );
+            }
+        }
+    }
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_ptml() {
+        let leaf = |l: &str| FlatNode::Leaf { uuid: None, label: Some(l.to_string()) };
+        let tree = FlatNode::Operator {
+            operator: FlatOperator::Sequence,
+            children: vec![
+                leaf("a & b"),
+                FlatNode::Operator {
+                    operator: FlatOperator::Loop,
+                    children: vec![leaf("c"), FlatNode::Leaf { uuid: None, label: None }],
+                },
+            ],
+        };
+        let ptml = export_ptml(&tree, "order");
+
+        assert!(ptml.contains("name=\"order\" root=\"00000000-0000-0000-0000-000000000001\""));
+        assert!(ptml.contains("<manualTask id=\"00000000-0000-0000-0000-000000000002\" name=\"a &amp; b\"/>"));
+        assert_eq!(ptml.matches("<xorLoop ").count(), 1);
+        // redo and exit of the loop
+        assert_eq!(ptml.matches("<automaticTask ").count(), 2);
+        // every node but the root has a parent
+        assert_eq!(ptml.matches("<parentsNode ").count(), 5);
+        // the export is deterministic
+        assert_eq!(ptml, export_ptml(&tree, "order"));
+    }
+}
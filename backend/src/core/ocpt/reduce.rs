@@ -0,0 +1,260 @@
+//! Reduction and canonicalisation of OCPTs.
+//!
+//! [`OCPT::reduce`] applies the standard process tree reduction rules, keeping the uuids and
+//! object-type annotations of the remaining nodes:
+//! - children of the same operator type are merged into their parent (sequence, exclusive
+//!   choice and concurrency are associative),
+//! - silent leaves are removed from sequences and concurrencies,
+//! - exclusive choices keep at most one silent child,
+//! - loops whose children are all silent become silent,
+//! - operators with a single child (except loops) are replaced by the child.
+//!
+//! Operators without children are left as they are, see [`validate_ocpt`].
+//!
+//! [`OCPT::canonicalize`] additionally sorts the children of commutative operators (exclusive
+//! choices, concurrencies and the redo parts of loops) by their [`canonical_string`], so that
+//! equivalent trees have the same structure and the same canonical string.
+//!
+//! [`validate_ocpt`]: super::validation::validate_ocpt
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use sha1::{Digest, Sha1};
+
+use crate::core::ocpt::leaf_exhibits;
+use crate::models::ocpt::{OCPTLeafLabel, OCPTNode, OCPTOperator, OCPTOperatorType, OCPT};
+
+impl OCPT {
+    /// Applies the reduction rules bottom-up, after which none of them applies anymore
+    pub fn reduce(&mut self) {
+        let root = std::mem::replace(&mut self.root, OCPTNode::new_leaf(None));
+        self.root = reduce_node(root);
+    }
+
+    /// Reduces the tree and orders the children of commutative operators canonically
+    pub fn canonicalize(&mut self) {
+        self.reduce();
+        sort_children(&mut self.root);
+    }
+
+    /// SHA-1 (hex) of the canonical string of the canonicalized tree. It does not depend on the
+    /// uuids, so equivalent trees have the same hash.
+    pub fn content_hash(&self) -> String {
+        // OCPTs are not `Clone`, copy the tree through its (lossless) JSON form
+        let mut canonical: OCPT = serde_json::to_value(self)
+            .and_then(serde_json::from_value)
+            .expect("OCPT JSON round trip");
+        canonical.canonicalize();
+        Sha1::digest(canonical_string(&canonical.root).as_bytes())
+            .iter()
+            .fold(String::new(), |mut hex, byte| {
+                let _ = write!(hex, "{byte:02x}");
+                hex
+            })
+    }
+}
+
+/// A uuid-free string representation of the subtree. Leaves are written with their activity
+/// and their sorted object types and exhibits, e.g. `"pick"{item:div,order}`.
+pub fn canonical_string(node: &OCPTNode) -> String {
+    let mut out = String::new();
+    write_canonical(node, &mut out);
+    out
+}
+
+fn write_canonical(node: &OCPTNode, out: &mut String) {
+    match node {
+        OCPTNode::Leaf(leaf) => match &leaf.activity_label {
+            OCPTLeafLabel::Tau => out.push_str("tau"),
+            OCPTLeafLabel::Activity(activity) => {
+                out.push_str(&serde_json::Value::from(activity.as_str()).to_string());
+                let ob_types: BTreeSet<&String> = leaf.related_ob_types.iter().collect();
+                let ob_types: Vec<String> = ob_types
+                    .into_iter()
+                    .map(|ot| {
+                        let exhibits = leaf_exhibits(leaf, ot, ["div", "con", "def"]);
+                        let ot = serde_json::Value::from(ot.as_str()).to_string();
+                        if exhibits.is_empty() { ot } else { format!("{ot}:{}", exhibits.join(",")) }
+                    })
+                    .collect();
+                let _ = write!(out, "{{{}}}", ob_types.join(","));
+            }
+        },
+        OCPTNode::Operator(op) => {
+            match op.operator_type {
+                OCPTOperatorType::Sequence => out.push_str("->"),
+                OCPTOperatorType::ExclusiveChoice => out.push('X'),
+                OCPTOperatorType::Concurrency => out.push('+'),
+                OCPTOperatorType::Loop(None) => out.push('*'),
+                OCPTOperatorType::Loop(Some(n)) => {
+                    let _ = write!(out, "*{n}");
+                }
+            }
+            out.push('(');
+            for (i, child) in op.children.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(child, out);
+            }
+            out.push(')');
+        }
+    }
+}
+
+fn is_tau(node: &OCPTNode) -> bool {
+    matches!(node, OCPTNode::Leaf(leaf) if leaf.activity_label == OCPTLeafLabel::Tau)
+}
+
+fn same_operator(a: &OCPTOperatorType, b: &OCPTOperatorType) -> bool {
+    matches!(
+        (a, b),
+        (OCPTOperatorType::Sequence, OCPTOperatorType::Sequence)
+            | (OCPTOperatorType::ExclusiveChoice, OCPTOperatorType::ExclusiveChoice)
+            | (OCPTOperatorType::Concurrency, OCPTOperatorType::Concurrency)
+    )
+}
+
+fn reduce_node(node: OCPTNode) -> OCPTNode {
+    let OCPTNode::Operator(op) = node else {
+        return node;
+    };
+    let OCPTOperator { uuid, operator_type, children, extra } = op;
+    let children: Vec<OCPTNode> = children.into_iter().map(reduce_node).collect();
+
+    if let OCPTOperatorType::Loop(_) = operator_type {
+        if !children.is_empty() && children.iter().all(is_tau) {
+            return children.into_iter().next().unwrap();
+        }
+        return OCPTNode::Operator(OCPTOperator { uuid, operator_type, children, extra });
+    }
+    if children.is_empty() {
+        return OCPTNode::Operator(OCPTOperator { uuid, operator_type, children, extra });
+    }
+
+    let flattened = children.into_iter().flat_map(|child| match child {
+        OCPTNode::Operator(child_op)
+            if same_operator(&child_op.operator_type, &operator_type) && !child_op.children.is_empty() =>
+        {
+            child_op.children
+        }
+        child => vec![child],
+    });
+    let mut merged = Vec::new();
+    let mut tau = None;
+    for child in flattened {
+        if is_tau(&child) {
+            tau.get_or_insert(child);
+        } else {
+            merged.push(child);
+        }
+    }
+    // A silent branch of a choice keeps the choice skippable, unless all branches are silent
+    if let Some(tau) = tau
+        && (merged.is_empty() || matches!(operator_type, OCPTOperatorType::ExclusiveChoice))
+    {
+        merged.push(tau);
+    }
+
+    if merged.len() == 1 {
+        merged.pop().unwrap()
+    } else {
+        OCPTNode::Operator(OCPTOperator { uuid, operator_type, children: merged, extra })
+    }
+}
+
+fn sort_children(node: &mut OCPTNode) {
+    let OCPTNode::Operator(op) = node else {
+        return;
+    };
+    op.children.iter_mut().for_each(sort_children);
+    let commutative = match op.operator_type {
+        OCPTOperatorType::ExclusiveChoice | OCPTOperatorType::Concurrency => &mut op.children[..],
+        OCPTOperatorType::Loop(_) if !op.children.is_empty() => &mut op.children[1..],
+        _ => return,
+    };
+    commutative.sort_by_cached_key(canonical_string);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(label: &str, related: &[&str]) -> OCPTNode {
+        let mut node = OCPTNode::new_leaf(Some(label.to_string()));
+        related.iter().for_each(|ot| node.add_related_ob_type(&ot.to_string()));
+        node
+    }
+
+    fn operator(operator_type: OCPTOperatorType, children: Vec<OCPTNode>) -> OCPTNode {
+        let mut node = OCPTNode::new_operator(operator_type);
+        children.into_iter().for_each(|c| node.add_child(c));
+        node
+    }
+
+    #[test]
+    fn test_content_hash() {
+        use OCPTOperatorType::*;
+        let tree = |children: Vec<OCPTNode>| {
+            OCPT::new(operator(Sequence, vec![leaf("place", &["order"]), operator(Concurrency, children)]))
+        };
+        let a = tree(vec![leaf("pick", &["item"]), leaf("pay", &["order"])]);
+        let b = tree(vec![leaf("pay", &["order"]), leaf("pick", &["item"])]);
+        let c = tree(vec![leaf("pay", &["order"]), leaf("pick", &["item", "order"])]);
+        assert_eq!(a.content_hash().len(), 40);
+        assert_eq!(a.content_hash(), b.content_hash());
+        assert_ne!(a.content_hash(), c.content_hash());
+    }
+
+    #[test]
+    fn test_reduce_and_canonicalize() {
+        use OCPTOperatorType::*;
+        let mut pick = leaf("pick", &["item", "order"]);
+        pick.add_divergent_ob_type(&"item".to_string());
+        let tree = |pick: OCPTNode, swap: bool| {
+            let choice = if swap {
+                vec![
+                    OCPTNode::new_leaf(None),
+                    leaf("pay", &["order"]),
+                    operator(ExclusiveChoice, vec![OCPTNode::new_leaf(None)]),
+                ]
+            } else {
+                let taus = vec![OCPTNode::new_leaf(None), OCPTNode::new_leaf(None)];
+                vec![leaf("pay", &["order"]), operator(ExclusiveChoice, taus)]
+            };
+            operator(
+                Sequence,
+                vec![
+                    operator(Sequence, vec![leaf("place", &["order"]), OCPTNode::new_leaf(None)]),
+                    operator(Concurrency, vec![pick]),
+                    operator(ExclusiveChoice, choice),
+                    operator(Loop(None), vec![OCPTNode::new_leaf(None), OCPTNode::new_leaf(None)]),
+                ],
+            )
+        };
+
+        let mut ocpt = OCPT::new(tree(pick, false));
+        let root_uuid = *ocpt.root.get_uuid();
+        ocpt.reduce();
+        assert_eq!(
+            canonical_string(&ocpt.root),
+            r#"->("place"{"order"},"pick"{"item":div,"order"},X("pay"{"order"},tau))"#
+        );
+        assert_eq!(*ocpt.root.get_uuid(), root_uuid);
+        assert!(ocpt.is_valid());
+
+        let mut other = OCPT::new(tree(leaf("pick", &["order", "item"]), true));
+        if let OCPTNode::Operator(op) = &mut other.root
+            && let OCPTNode::Operator(concurrency) = &mut op.children[1]
+        {
+            concurrency.children[0].add_divergent_ob_type(&"item".to_string());
+        }
+        other.canonicalize();
+        ocpt.canonicalize();
+        assert_eq!(canonical_string(&other.root), canonical_string(&ocpt.root));
+        assert_eq!(
+            canonical_string(&ocpt.root),
+            r#"->("place"{"order"},"pick"{"item":div,"order"},X("pay"{"order"},tau))"#
+        );
+    }
+}
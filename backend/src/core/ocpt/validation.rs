@@ -0,0 +1,210 @@
+//! Structural validation of OCPTs that, unlike [`OCPT::is_valid`], reports every problem.
+//!
+//! Each [`OcptIssue`] names the offending node by uuid and by its path from the root, given as
+//! child indices (the root has the empty path). As the frontend hierarchy has the same child
+//! order as the backend tree, paths also address nodes of an [`OcptFE`].
+//!
+//! [`OcptFE`]: crate::models::ocpt::OcptFE
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::models::ocpt::{OCPTLeaf, OCPTLeafLabel, OCPTNode, OCPTOperatorType, OCPT};
+
+/// Kind of an OCPT problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OcptIssueKind {
+    /// A loop without a do and a redo part
+    LoopTooFewChildren,
+    /// A sequence, exclusive choice or concurrency without children
+    EmptyOperator,
+    /// A node whose uuid is also used by a different node
+    DuplicateUuid,
+    /// A subtree that appears (with the same uuids) under several parents
+    SharedSubtree,
+    /// A divergent, convergent or deficient object type of a leaf that is not related to it
+    UnrelatedExhibit,
+    /// An object type of a leaf that is not declared in `ots` of the frontend OCPT
+    UndeclaredObjectType,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OcptIssue {
+    pub kind: OcptIssueKind,
+    /// The uuid of the offending node
+    pub uuid: Uuid,
+    /// Child indices from the root to the offending node
+    pub path: Vec<usize>,
+    pub message: String,
+}
+
+/// Validates `ocpt`, returning all problems in depth-first order. If `declared_ob_types` is
+/// given (the `ots` of a frontend OCPT), object types of leaves must be declared there.
+pub fn validate_ocpt(ocpt: &OCPT, declared_ob_types: Option<&HashSet<String>>) -> Vec<OcptIssue> {
+    let mut validator = Validator { declared_ob_types, seen: HashMap::new(), issues: Vec::new() };
+    validator.visit(&ocpt.root, &mut Vec::new());
+    validator.issues
+}
+
+struct Validator<'a> {
+    declared_ob_types: Option<&'a HashSet<String>>,
+    /// First occurrence of every uuid
+    seen: HashMap<Uuid, &'a OCPTNode>,
+    issues: Vec<OcptIssue>,
+}
+
+impl<'a> Validator<'a> {
+    fn visit(&mut self, node: &'a OCPTNode, path: &mut Vec<usize>) {
+        let uuid = *node.get_uuid();
+        if let Some(first) = self.seen.get(&uuid) {
+            if preorder_uuids(first) == preorder_uuids(node) {
+                // The issues of the subtree were reported at its first occurrence
+                let message = format!("Subtree {} appears under several parents", describe(node));
+                self.push(OcptIssueKind::SharedSubtree, uuid, path, message);
+                return;
+            }
+            let message = format!("{} reuses the uuid of another node", describe(node));
+            self.push(OcptIssueKind::DuplicateUuid, uuid, path, message);
+        } else {
+            self.seen.insert(uuid, node);
+        }
+
+        match node {
+            OCPTNode::Operator(op) => {
+                match op.operator_type {
+                    OCPTOperatorType::Loop(_) if op.children.len() < 2 => self.push(
+                        OcptIssueKind::LoopTooFewChildren,
+                        uuid,
+                        path,
+                        format!("Loop has {} children, needs a do and at least one redo part", op.children.len()),
+                    ),
+                    OCPTOperatorType::Loop(_) => {}
+                    _ if op.children.is_empty() => {
+                        self.push(OcptIssueKind::EmptyOperator, uuid, path, format!("{} has no children", describe(node)))
+                    }
+                    _ => {}
+                }
+                for (i, child) in op.children.iter().enumerate() {
+                    path.push(i);
+                    self.visit(child, path);
+                    path.pop();
+                }
+            }
+            OCPTNode::Leaf(leaf) => self.check_leaf(leaf, path),
+        }
+    }
+
+    fn check_leaf(&mut self, leaf: &OCPTLeaf, path: &[usize]) {
+        let name = describe_leaf(leaf);
+        for (exhibit, ob_types) in [
+            ("divergent", &leaf.divergent_ob_types),
+            ("convergent", &leaf.convergent_ob_types),
+            ("deficient", &leaf.deficient_ob_types),
+        ] {
+            let unrelated: BTreeSet<&String> = ob_types.difference(&leaf.related_ob_types).collect();
+            for ot in unrelated {
+                self.push(
+                    OcptIssueKind::UnrelatedExhibit,
+                    leaf.uuid,
+                    path,
+                    format!("{name} marks {ot} as {exhibit}, but {ot} is not related"),
+                );
+            }
+        }
+        if let Some(declared) = self.declared_ob_types {
+            let ob_types: BTreeSet<&String> = [
+                &leaf.related_ob_types,
+                &leaf.divergent_ob_types,
+                &leaf.convergent_ob_types,
+                &leaf.deficient_ob_types,
+            ]
+            .into_iter()
+            .flatten()
+            .filter(|ot| !declared.contains(*ot))
+            .collect();
+            for ot in ob_types {
+                self.push(
+                    OcptIssueKind::UndeclaredObjectType,
+                    leaf.uuid,
+                    path,
+                    format!("{name} uses object type {ot}, which is not declared in ots"),
+                );
+            }
+        }
+    }
+
+    fn push(&mut self, kind: OcptIssueKind, uuid: Uuid, path: &[usize], message: String) {
+        self.issues.push(OcptIssue { kind, uuid, path: path.to_vec(), message });
+    }
+}
+
+fn preorder_uuids(node: &OCPTNode) -> Vec<Uuid> {
+    let mut result = vec![*node.get_uuid()];
+    if let OCPTNode::Operator(op) = node {
+        result.extend(op.children.iter().flat_map(preorder_uuids));
+    }
+    result
+}
+
+fn describe(node: &OCPTNode) -> String {
+    match node {
+        OCPTNode::Operator(op) => match op.operator_type {
+            OCPTOperatorType::Sequence => "Sequence".to_string(),
+            OCPTOperatorType::ExclusiveChoice => "Exclusive choice".to_string(),
+            OCPTOperatorType::Concurrency => "Concurrency".to_string(),
+            OCPTOperatorType::Loop(_) => "Loop".to_string(),
+        },
+        OCPTNode::Leaf(leaf) => describe_leaf(leaf),
+    }
+}
+
+fn describe_leaf(leaf: &OCPTLeaf) -> String {
+    match &leaf.activity_label {
+        OCPTLeafLabel::Activity(activity) => format!("Leaf {activity:?}"),
+        OCPTLeafLabel::Tau => "Silent leaf".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_ocpt() {
+        let mut place = OCPTNode::new_leaf(Some("place order".to_string()));
+        place.add_related_ob_type(&"order".to_string());
+        let OCPTNode::Leaf(leaf) = &mut place else { unreachable!() };
+        leaf.divergent_ob_types.insert("item".to_string());
+        let place_json = serde_json::to_string(&place).unwrap();
+
+        let mut lonely_loop = OCPTNode::new_operator(OCPTOperatorType::Loop(None));
+        lonely_loop.add_child(serde_json::from_str(&place_json).unwrap());
+        let mut root = OCPTNode::new_operator(OCPTOperatorType::Sequence);
+        root.add_child(place);
+        root.add_child(lonely_loop);
+        root.add_child(OCPTNode::new_operator(OCPTOperatorType::Concurrency));
+        let ocpt = OCPT::new(root);
+        assert!(!ocpt.is_valid());
+
+        let declared: HashSet<String> = HashSet::from(["order".to_string()]);
+        let issues = validate_ocpt(&ocpt, Some(&declared));
+        let found: Vec<(OcptIssueKind, Vec<usize>)> = issues.iter().map(|i| (i.kind, i.path.clone())).collect();
+        assert_eq!(
+            found,
+            vec![
+                (OcptIssueKind::UnrelatedExhibit, vec![0]),
+                (OcptIssueKind::UndeclaredObjectType, vec![0]),
+                (OcptIssueKind::LoopTooFewChildren, vec![1]),
+                (OcptIssueKind::SharedSubtree, vec![1, 0]),
+                (OcptIssueKind::EmptyOperator, vec![2]),
+            ]
+        );
+        assert_eq!(issues[3].uuid, issues[0].uuid);
+        assert!(issues[1].message.contains("item"));
+
+        let mut valid = OCPTNode::new_operator(OCPTOperatorType::ExclusiveChoice);
+        valid.add_child(OCPTNode::new_leaf(None));
+        assert!(validate_ocpt(&OCPT::new(valid), Some(&declared)).is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+//! DOT rendering of an [`OCDirectlyFollowsGraph`] as multi-colored DFG.
+//!
+//! Activities are shared by all object types. Every object type gets a color, a start and an
+//! end node, and its directly-follows edges labelled with their frequency. The pen width of an
+//! edge grows logarithmically with its frequency relative to the most frequent edge.
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use super::quote;
+use crate::models::dfg::OCDirectlyFollowsGraph;
+
+/// Colors assigned to the object types in alphabetical order (repeating if there are more)
+const PALETTE: [&str; 10] = [
+    "#1f77b4", "#d62728", "#2ca02c", "#ff7f0e", "#9467bd", "#8c564b", "#e377c2", "#17becf",
+    "#bcbd22", "#7f7f7f",
+];
+
+const MAX_PEN_WIDTH: f64 = 6.0;
+
+/// The color of each object type of the DFG
+pub fn object_type_colors(dfg: &OCDirectlyFollowsGraph) -> BTreeMap<String, &'static str> {
+    dfg.object_type_to_dfg
+        .keys()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .enumerate()
+        .map(|(i, ot)| (ot.clone(), PALETTE[i % PALETTE.len()]))
+        .collect()
+}
+
+/// Renders the object-centric DFG as DOT digraph
+pub fn oc_dfg_to_dot(dfg: &OCDirectlyFollowsGraph) -> String {
+    let colors = object_type_colors(dfg);
+    let activities: BTreeSet<&String> = dfg
+        .object_type_to_dfg
+        .values()
+        .flat_map(|d| d.activities.keys())
+        .collect();
+    let activity_ids: BTreeMap<&String, String> = activities
+        .iter()
+        .enumerate()
+        .map(|(i, a)| (*a, format!("a{i}")))
+        .collect();
+    let max_frequency = dfg
+        .object_type_to_dfg
+        .values()
+        .flat_map(|d| d.directly_follows_relations.values())
+        .copied()
+        .max()
+        .unwrap_or(1)
+        .max(1);
+
+    let mut out = String::from("digraph ocdfg {\n");
+    out.push_str("  rankdir=TB;\n");
+    out.push_str("  node [fontsize=12];\n");
+    for (activity, id) in &activity_ids {
+        let _ = writeln!(out, "  {id} [shape=box, label={}];", quote(activity));
+    }
+
+    for (i, (ot, color)) in colors.iter().enumerate() {
+        let ot_dfg = &dfg.object_type_to_dfg[ot];
+        let (start, end) = (format!("s{i}"), format!("e{i}"));
+        let _ = writeln!(
+            out,
+            "  {start} [shape=circle, style=filled, fillcolor={0}, color={0}, label={1}];",
+            quote(color),
+            quote(ot)
+        );
+        let _ = writeln!(out, "  {end} [shape=doublecircle, color={}, label=\"\"];", quote(color));
+
+        for activity in ot_dfg.start_activities.iter().collect::<BTreeSet<_>>() {
+            let _ = writeln!(out, "  {start} -> {} [color={}];", activity_ids[activity], quote(color));
+        }
+        let edges: BTreeMap<(&str, &str), u32> = ot_dfg
+            .directly_follows_relations
+            .iter()
+            .map(|((from, to), frequency)| ((from.as_ref(), to.as_ref()), *frequency))
+            .collect();
+        for ((from, to), frequency) in edges {
+            let (Some(from_id), Some(to_id)) = (
+                activity_ids.get(&from.to_string()),
+                activity_ids.get(&to.to_string()),
+            ) else {
+                continue;
+            };
+            let _ = writeln!(
+                out,
+                "  {from_id} -> {to_id} [color={}, penwidth={}, label=\"{frequency}\"];",
+                quote(color),
+                pen_width(frequency, max_frequency)
+            );
+        }
+        for activity in ot_dfg.end_activities.iter().collect::<BTreeSet<_>>() {
+            let _ = writeln!(out, "  {} -> {end} [color={}];", activity_ids[activity], quote(color));
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Integer pen width between 1 and [`MAX_PEN_WIDTH`], logarithmic in the frequency
+fn pen_width(frequency: u32, max_frequency: u32) -> u32 {
+    let scale = (1.0 + frequency as f64).ln() / (1.0 + max_frequency as f64).ln();
+    (1.0 + scale * (MAX_PEN_WIDTH - 1.0)).round() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use process_mining::dfg::dfg_struct::DirectlyFollowsGraph;
+
+    #[test]
+    fn test_oc_dfg_to_dot() {
+        let mut order = DirectlyFollowsGraph::new();
+        order.add_activity("place".to_string(), 10);
+        order.add_activity("pay".to_string(), 10);
+        order.add_df_relation("place".into(), "pay".into(), 10);
+        order.add_start_activity("place".to_string());
+        order.add_end_activity("pay".to_string());
+        let mut item = DirectlyFollowsGraph::new();
+        item.add_activity("place".to_string(), 30);
+        item.add_activity("pick".to_string(), 30);
+        item.add_df_relation("place".into(), "pick".into(), 1);
+        item.add_start_activity("place".to_string());
+        item.add_end_activity("pick".to_string());
+
+        let mut dfg = OCDirectlyFollowsGraph::new();
+        dfg.object_type_to_dfg.insert("order".to_string(), order);
+        dfg.object_type_to_dfg.insert("item".to_string(), item);
+        let dot = oc_dfg_to_dot(&dfg);
+
+        // Activities are shared, colors follow the alphabetical order of the object types
+        assert_eq!(dot.matches("[shape=box").count(), 3);
+        assert!(dot.contains("s0 [shape=circle, style=filled, fillcolor=\"#1f77b4\", color=\"#1f77b4\", label=\"item\"];"));
+        assert!(dot.contains("label=\"order\""));
+        // a0 = pay, a1 = pick, a2 = place
+        assert!(dot.contains("a2 -> a0 [color=\"#d62728\", penwidth=6, label=\"10\"];"));
+        assert!(dot.contains("a2 -> a1 [color=\"#1f77b4\", penwidth=2, label=\"1\"];"));
+        assert_eq!(dot, oc_dfg_to_dot(&dfg));
+        assert!(super::super::dot_to_svg(&dot).unwrap().contains("<svg"));
+    }
+}
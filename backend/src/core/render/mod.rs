@@ -0,0 +1,86 @@
+//! Server-side rendering of OCPTs and object-centric DFGs as Graphviz DOT or SVG.
+//!
+//! The DOT documents are plain Graphviz and can also be rendered with `dot`. SVG is laid out
+//! in-process with `layout-rs`, so no Graphviz installation is needed.
+use anyhow::{anyhow, Result};
+use layout::backends::svg::SVGWriter;
+use layout::gv::{DotParser, GraphBuilder};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+pub mod dfg_dot;
+pub mod ocpt_dot;
+
+/// Output formats of a rendering
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderFormat {
+    Dot,
+    #[default]
+    Svg,
+}
+
+impl RenderFormat {
+    /// Parses a format name as given in the `format` query parameter or as file extension
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s.trim().trim_start_matches('.').to_lowercase().as_str() {
+            "dot" | "gv" => Some(Self::Dot),
+            "svg" => Some(Self::Svg),
+            _ => None,
+        }
+    }
+
+    /// The MIME type used when serving this format
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Dot => "text/vnd.graphviz",
+            Self::Svg => "image/svg+xml",
+        }
+    }
+
+    /// The file extension used when serving this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Dot => "dot",
+            Self::Svg => "svg",
+        }
+    }
+
+    /// Renders a DOT document in this format
+    pub fn render(&self, dot: String) -> Result<String> {
+        match self {
+            Self::Dot => Ok(dot),
+            Self::Svg => dot_to_svg(&dot),
+        }
+    }
+}
+
+/// Lays out a DOT document and renders it as SVG
+pub fn dot_to_svg(dot: &str) -> Result<String> {
+    let graph = DotParser::new(dot).process().map_err(|e| anyhow!("Invalid DOT: {e}"))?;
+    // The layout engine panics on some degenerate graphs instead of returning an error
+    catch_unwind(AssertUnwindSafe(|| {
+        let mut builder = GraphBuilder::new();
+        builder.visit_graph(&graph);
+        let mut visual = builder.get();
+        let mut svg = SVGWriter::new();
+        visual.do_it(false, false, false, &mut svg);
+        svg.finalize()
+    }))
+    .map_err(|_| anyhow!("Failed to lay out the graph"))
+}
+
+/// Quotes a string as DOT identifier; line breaks become `\n`
+fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => {}
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,98 @@
+//! DOT rendering of an [`OCPT`] as top-down tree.
+//!
+//! Operators use the usual process tree symbols (`->` sequence, `X` exclusive choice, `+`
+//! concurrency, `*` loop with its bound). Leaves list their related object types, marked with
+//! the exhibits `div`, `con` and `def` as in the frontend.
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use super::quote;
+use crate::core::ocpt::leaf_exhibits;
+use crate::models::ocpt::{OCPTLeaf, OCPTLeafLabel, OCPTNode, OCPTOperatorType, OCPT};
+
+/// Renders the OCPT as DOT digraph
+pub fn ocpt_to_dot(ocpt: &OCPT) -> String {
+    let mut out = String::from("digraph ocpt {\n");
+    out.push_str("  rankdir=TB;\n");
+    out.push_str("  node [fontsize=12];\n");
+    let mut next_id = 0;
+    write_node(&ocpt.root, &mut next_id, &mut out);
+    out.push_str("}\n");
+    out
+}
+
+/// Writes the node, its descendants and the edges to them. Returns the DOT id of the node.
+fn write_node(node: &OCPTNode, next_id: &mut usize, out: &mut String) -> String {
+    let id = format!("n{next_id}");
+    *next_id += 1;
+    match node {
+        OCPTNode::Leaf(leaf) => match &leaf.activity_label {
+            OCPTLeafLabel::Activity(_) => {
+                let _ = writeln!(out, "  {id} [shape=box, label={}];", quote(&leaf_label(leaf)));
+            }
+            OCPTLeafLabel::Tau => {
+                let _ = writeln!(out, "  {id} [shape=box, style=filled, fillcolor=lightgray, label=\"tau\"];");
+            }
+        },
+        OCPTNode::Operator(op) => {
+            let label = match op.operator_type {
+                OCPTOperatorType::Sequence => "->".to_string(),
+                OCPTOperatorType::ExclusiveChoice => "X".to_string(),
+                OCPTOperatorType::Concurrency => "+".to_string(),
+                OCPTOperatorType::Loop(None) => "*".to_string(),
+                OCPTOperatorType::Loop(Some(n)) => format!("*\nmax {n}"),
+            };
+            let _ = writeln!(out, "  {id} [shape=circle, label={}];", quote(&label));
+            for child in &op.children {
+                let child_id = write_node(child, next_id, out);
+                let _ = writeln!(out, "  {id} -> {child_id};");
+            }
+        }
+    }
+    id
+}
+
+/// Activity followed by one line per related object type, e.g. `item (div, con)`
+fn leaf_label(leaf: &OCPTLeaf) -> String {
+    let mut label = match &leaf.activity_label {
+        OCPTLeafLabel::Activity(activity) => activity.clone(),
+        OCPTLeafLabel::Tau => String::new(),
+    };
+    let ob_types: BTreeSet<&String> = leaf.related_ob_types.iter().collect();
+    for ot in ob_types {
+        let exhibits = leaf_exhibits(leaf, ot, ["div", "con", "def"]);
+        label.push('\n');
+        label.push_str(ot);
+        if !exhibits.is_empty() {
+            let _ = write!(label, " ({})", exhibits.join(", "));
+        }
+    }
+    label
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::render::dot_to_svg;
+
+    #[test]
+    fn test_ocpt_to_dot_and_svg() {
+        let mut a = OCPTNode::new_leaf(Some("place \"order\"".to_string()));
+        a.add_related_ob_type(&"order".to_string());
+        a.add_related_ob_type(&"item".to_string());
+        a.add_convergent_ob_type(&"item".to_string());
+        a.add_deficient_ob_type(&"item".to_string());
+        let mut root = OCPTNode::new_operator(OCPTOperatorType::Loop(Some(2)));
+        root.add_child(a);
+        root.add_child(OCPTNode::new_leaf(None));
+        let dot = ocpt_to_dot(&OCPT::new(root));
+
+        assert!(dot.contains("n0 [shape=circle, label=\"*\\nmax 2\"];"));
+        assert!(dot.contains("label=\"place \\\"order\\\"\\nitem (con, def)\\norder\""));
+        assert!(dot.contains("n0 -> n1;") && dot.contains("n0 -> n2;"));
+
+        let svg = dot_to_svg(&dot).unwrap();
+        assert!(svg.contains("<svg"));
+        assert!(svg.contains("item (con, def)"));
+    }
+}
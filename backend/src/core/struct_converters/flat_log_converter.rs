@@ -0,0 +1,476 @@
+//! Convert **flat event logs** (XES or CSV) to **OCEL 2.0**.
+//!
+//! A flat log has one row per event. A [`FlatLogMapping`] names the columns holding the
+//! activity and timestamp, the columns referencing objects (and their object types), and the
+//! columns carrying event or object attributes.
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, FixedOffset};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::BufRead;
+
+use crate::core::struct_converters::utils::{
+    json_to_attr_value, merge_tys, parse_time_any, vty_to_attr_type, VTy,
+};
+use process_mining::event_log::event_log_struct::{AttributeValue, Attributes};
+use process_mining::ocel::ocel_struct::{
+    OCELEvent, OCELEventAttribute, OCELObject, OCELObjectAttribute, OCELRelationship, OCELType,
+    OCELTypeAttribute,
+};
+use process_mining::event_log::import_xes::import_xes;
+use process_mining::{XESImportOptions, OCEL};
+
+/// One event of a flat log: column name -> value
+pub type FlatRow = BTreeMap<String, Value>;
+
+/// Serialization formats of a flat event log (compressed uploads are decompressed beforehand)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlatLogFormat {
+    Csv,
+    Xes,
+}
+
+impl FlatLogFormat {
+    /// Parses a format name as given in the `file_type` form field
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s.trim().trim_start_matches('.').to_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "xes" => Some(Self::Xes),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format from the file extension of an uploaded file name
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        std::path::Path::new(name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_name)
+    }
+}
+
+/// Describes how the columns of a flat log map onto OCEL 2.0 concepts
+#[derive(Debug, Clone, Deserialize)]
+pub struct FlatLogMapping {
+    /// Column holding the activity (event type)
+    #[serde(default = "default_activity_column")]
+    pub activity_column: String,
+    /// Column holding the event timestamp
+    #[serde(default = "default_timestamp_column")]
+    pub timestamp_column: String,
+    /// Column holding a unique event id; events are numbered by row if absent
+    #[serde(default)]
+    pub event_id_column: Option<String>,
+    /// Object identifier columns and the object type of the referenced objects.
+    /// The column name becomes the qualifier of the E2O relationship.
+    pub object_columns: BTreeMap<String, String>,
+    /// Columns copied as event attributes
+    #[serde(default)]
+    pub event_attributes: Vec<String>,
+    /// Columns copied as (time-varying) object attributes, mapped to the object column
+    /// whose objects they describe
+    #[serde(default)]
+    pub object_attributes: BTreeMap<String, String>,
+    /// Separator for cells referencing several objects (e.g. `"i1;i2"` with `";"`)
+    #[serde(default)]
+    pub id_separator: Option<String>,
+    /// CSV field delimiter
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+}
+
+fn default_activity_column() -> String {
+    "concept:name".to_string()
+}
+
+fn default_timestamp_column() -> String {
+    "time:timestamp".to_string()
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+/*
+Example mapping (sent as the `mapping` form field):
+
+{
+  "activity_column": "activity",
+  "timestamp_column": "timestamp",
+  "object_columns": { "order_id": "order", "item_id": "item" },
+  "event_attributes": ["resource"],
+  "object_attributes": { "price": "item_id" },
+  "id_separator": ";"
+}
+
+For XES logs the defaults `concept:name` / `time:timestamp` apply and trace attributes are
+available as `case:<key>`, e.g. `"object_columns": { "case:concept:name": "case" }`.
+*/
+
+/// Imports a flat log in the given format and converts it into an [`OCEL`] (v2).
+///
+/// # Errors
+/// - The content cannot be parsed in the given format, or the conversion fails.
+pub fn import_flat_log<R: BufRead>(
+    reader: R,
+    format: FlatLogFormat,
+    mapping: &FlatLogMapping,
+) -> Result<OCEL> {
+    let rows = match format {
+        FlatLogFormat::Csv => read_csv_rows(reader, mapping)?,
+        FlatLogFormat::Xes => read_xes_rows(reader)?,
+    };
+    convert_flat_log(&rows, mapping)
+}
+
+/// Reads CSV rows with a header line. Cells of the attribute columns of `mapping` are typed as
+/// integer, float or boolean where possible; all other cells (ids, activity, timestamp) are
+/// kept as trimmed strings, so that e.g. `007` stays distinct from `7`. Empty cells become `null`.
+fn read_csv_rows<R: BufRead>(reader: R, mapping: &FlatLogMapping) -> Result<Vec<FlatRow>> {
+    let delimiter = u8::try_from(mapping.delimiter).context("CSV delimiter must be an ASCII character")?;
+    let typed: HashSet<&str> = mapping
+        .event_attributes
+        .iter()
+        .chain(mapping.object_attributes.keys())
+        .map(String::as_str)
+        .collect();
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(reader);
+    let headers = reader.headers().context("read CSV header")?.clone();
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.context("read CSV record")?;
+        rows.push(
+            headers
+                .iter()
+                .zip(record.iter())
+                .map(|(h, cell)| {
+                    let h = h.trim();
+                    (h.to_string(), csv_cell_value(cell, typed.contains(h)))
+                })
+                .collect(),
+        );
+    }
+    Ok(rows)
+}
+
+fn csv_cell_value(cell: &str, typed: bool) -> Value {
+    let cell = cell.trim();
+    if cell.is_empty() {
+        Value::Null
+    } else if !typed {
+        Value::String(cell.to_string())
+    } else if let Ok(i) = cell.parse::<i64>() {
+        Value::from(i)
+    } else if let Some(n) = cell.parse::<f64>().ok().and_then(serde_json::Number::from_f64) {
+        Value::Number(n)
+    } else if cell.eq_ignore_ascii_case("true") || cell.eq_ignore_ascii_case("false") {
+        Value::Bool(cell.eq_ignore_ascii_case("true"))
+    } else {
+        Value::String(cell.to_string())
+    }
+}
+
+/// Reads the events of a XES log as rows, prefixing trace attributes with `case:`
+fn read_xes_rows<R: BufRead>(reader: R) -> Result<Vec<FlatRow>> {
+    let log = import_xes(reader, XESImportOptions::default())
+        .map_err(|e| anyhow!("Invalid XES: {e}"))?;
+
+    let mut rows = Vec::new();
+    for trace in &log.traces {
+        let case_attrs: FlatRow = xes_attributes(&trace.attributes)
+            .map(|(k, v)| (format!("case:{k}"), v))
+            .collect();
+        for event in &trace.events {
+            let mut row = case_attrs.clone();
+            row.extend(xes_attributes(&event.attributes));
+            rows.push(row);
+        }
+    }
+    Ok(rows)
+}
+
+/// Maps the scalar XES attributes to JSON values (lists and containers are skipped)
+fn xes_attributes(attrs: &Attributes) -> impl Iterator<Item = (String, Value)> + '_ {
+    attrs.iter().filter_map(|a| {
+        let v = match &a.value {
+            AttributeValue::String(s) => Value::String(s.clone()),
+            AttributeValue::Date(d) => Value::String(d.to_rfc3339()),
+            AttributeValue::Int(i) => Value::from(*i),
+            AttributeValue::Float(f) => serde_json::Number::from_f64(*f).map(Value::Number)?,
+            AttributeValue::Boolean(b) => Value::Bool(*b),
+            AttributeValue::ID(id) => Value::String(id.to_string()),
+            AttributeValue::List(_) | AttributeValue::Container(_) | AttributeValue::None() => {
+                return None;
+            }
+        };
+        Some((a.key.clone(), v))
+    })
+}
+
+/// Core converter: **flat rows → OCEL 2.0**.
+///
+/// **Steps**:
+/// 1. Validate the mapping and parse activity/timestamp of every row.
+/// 2. Collect the referenced objects per object column. Raw ids used by more than one object
+///    type are disambiguated as `<object type>:<id>`, suffixed with `#<n>` if that is taken.
+/// 3. Build events (attributes, E2O relationships qualified by column name) in time order and
+///    record object attribute values whenever they change.
+/// 4. Infer event/object *type schemas* with [`VTy`]/[`merge_tys`].
+///
+/// # Errors
+/// - Invalid mapping, missing activity, unparseable timestamp or duplicate event id.
+pub fn convert_flat_log(rows: &[FlatRow], mapping: &FlatLogMapping) -> Result<OCEL> {
+    if rows.is_empty() {
+        return Err(anyhow!("No events found in flat log"));
+    }
+    if mapping.object_columns.is_empty() {
+        return Err(anyhow!("Mapping declares no object columns"));
+    }
+    for (attr, obj_col) in &mapping.object_attributes {
+        if !mapping.object_columns.contains_key(obj_col) {
+            return Err(anyhow!("Object attribute '{attr}' refers to unknown object column '{obj_col}'"));
+        }
+    }
+
+    // 1. Activity and time per row, processed in time order
+    let mut parsed: Vec<(usize, String, DateTime<FixedOffset>)> = Vec::with_capacity(rows.len());
+    for (i, row) in rows.iter().enumerate() {
+        let activity = cell_string(row.get(&mapping.activity_column))
+            .ok_or_else(|| anyhow!("Row {}: missing activity '{}'", i + 1, mapping.activity_column))?;
+        let raw_time = cell_string(row.get(&mapping.timestamp_column))
+            .ok_or_else(|| anyhow!("Row {}: missing timestamp '{}'", i + 1, mapping.timestamp_column))?;
+        let time = parse_time_any(&raw_time)
+            .ok_or_else(|| anyhow!("Row {}: unparseable timestamp {raw_time}", i + 1))?;
+        parsed.push((i, activity, time));
+    }
+    parsed.sort_by_key(|(i, _, time)| (*time, *i));
+
+    // 2. Object ids per type
+    let mut types_per_raw_id: BTreeMap<String, BTreeSet<&str>> = BTreeMap::new();
+    for row in rows {
+        for (col, otype) in &mapping.object_columns {
+            for raw in object_ids(row.get(col), mapping.id_separator.as_deref()) {
+                types_per_raw_id.entry(raw).or_default().insert(otype);
+            }
+        }
+    }
+    let mut taken: HashSet<String> = types_per_raw_id
+        .iter()
+        .filter(|(_, types)| types.len() == 1)
+        .map(|(raw, _)| raw.clone())
+        .collect();
+    let mut disambiguated: HashMap<(String, String), String> = HashMap::new();
+    for (raw, types) in types_per_raw_id.iter().filter(|(_, types)| types.len() > 1) {
+        for otype in types {
+            let base = format!("{otype}:{raw}");
+            let mut id = base.clone();
+            let mut n = 1;
+            while !taken.insert(id.clone()) {
+                n += 1;
+                id = format!("{base}#{n}");
+            }
+            disambiguated.insert((otype.to_string(), raw.clone()), id);
+        }
+    }
+    let object_id = |otype: &str, raw: &str| -> String {
+        disambiguated
+            .get(&(otype.to_string(), raw.to_string()))
+            .cloned()
+            .unwrap_or_else(|| raw.to_string())
+    };
+
+    // 3. Events and objects
+    let mut event_ids: HashSet<String> = HashSet::new();
+    let mut events: Vec<OCELEvent> = Vec::with_capacity(rows.len());
+    let mut objects: BTreeMap<String, OCELObject> = BTreeMap::new();
+    let mut ev_type_attrs: BTreeMap<String, BTreeMap<String, VTy>> = BTreeMap::new();
+    let mut ob_type_attrs: BTreeMap<String, BTreeMap<String, VTy>> = mapping
+        .object_columns
+        .values()
+        .map(|otype| (otype.clone(), BTreeMap::new()))
+        .collect();
+
+    for (i, activity, time) in parsed {
+        let row = &rows[i];
+        let eid = match &mapping.event_id_column {
+            Some(col) => cell_string(row.get(col))
+                .ok_or_else(|| anyhow!("Row {}: missing event id '{col}'", i + 1))?,
+            None => format!("e{}", i + 1),
+        };
+        if !event_ids.insert(eid.clone()) {
+            return Err(anyhow!("Duplicate event id {eid}"));
+        }
+
+        let type_attrs = ev_type_attrs.entry(activity.clone()).or_default();
+        let mut attrs: Vec<OCELEventAttribute> = Vec::new();
+        for col in &mapping.event_attributes {
+            let Some(v) = row.get(col).filter(|v| !v.is_null()) else { continue };
+            if let Some(t) = VTy::of(v) {
+                type_attrs.entry(col.clone()).and_modify(|tt| *tt = merge_tys(*tt, t)).or_insert(t);
+            }
+            attrs.push(OCELEventAttribute { name: col.clone(), value: json_to_attr_value(v) });
+        }
+
+        let mut rels: Vec<OCELRelationship> = Vec::new();
+        let mut ids_per_column: HashMap<&str, Vec<String>> = HashMap::new();
+        for (col, otype) in &mapping.object_columns {
+            for raw in object_ids(row.get(col), mapping.id_separator.as_deref()) {
+                let oid = object_id(otype, &raw);
+                objects.entry(oid.clone()).or_insert_with(|| OCELObject {
+                    id: oid.clone(),
+                    object_type: otype.clone(),
+                    attributes: Vec::new(),
+                    relationships: Vec::new(),
+                });
+                if !rels.iter().any(|r| r.object_id == oid && r.qualifier == *col) {
+                    rels.push(OCELRelationship::new(&oid, col));
+                }
+                ids_per_column.entry(col.as_str()).or_default().push(oid);
+            }
+        }
+
+        for (attr, obj_col) in &mapping.object_attributes {
+            let Some(v) = row.get(attr).filter(|v| !v.is_null()) else { continue };
+            let value = json_to_attr_value(v);
+            for oid in ids_per_column.get(obj_col.as_str()).into_iter().flatten() {
+                let obj = objects.get_mut(oid).expect("inserted above");
+                let unchanged = obj
+                    .attributes
+                    .iter()
+                    .rev()
+                    .find(|a| a.name == *attr)
+                    .is_some_and(|a| a.value == value);
+                if unchanged {
+                    continue;
+                }
+                if let Some(t) = VTy::of(v) {
+                    ob_type_attrs
+                        .entry(obj.object_type.clone())
+                        .or_default()
+                        .entry(attr.clone())
+                        .and_modify(|tt| *tt = merge_tys(*tt, t))
+                        .or_insert(t);
+                }
+                obj.attributes.push(OCELObjectAttribute::new(attr, value.clone(), time));
+            }
+        }
+
+        events.push(OCELEvent::new(eid, &activity, time, attrs, rels));
+    }
+
+    // 4. Type schemas
+    Ok(OCEL {
+        event_types: type_schemas(ev_type_attrs),
+        object_types: type_schemas(ob_type_attrs),
+        events,
+        objects: objects.into_values().collect(),
+    })
+}
+
+/// The cell as a non-empty string (numbers and booleans are formatted)
+fn cell_string(v: Option<&Value>) -> Option<String> {
+    match v? {
+        Value::Null => None,
+        Value::String(s) if s.trim().is_empty() => None,
+        Value::String(s) => Some(s.trim().to_string()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// The object ids referenced by a cell, split at `separator` if given
+fn object_ids(v: Option<&Value>, separator: Option<&str>) -> Vec<String> {
+    let Some(cell) = cell_string(v) else { return Vec::new() };
+    match separator {
+        Some(sep) if !sep.is_empty() => cell
+            .split(sep)
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect(),
+        _ => vec![cell],
+    }
+}
+
+fn type_schemas(acc: BTreeMap<String, BTreeMap<String, VTy>>) -> Vec<OCELType> {
+    acc.into_iter()
+        .map(|(name, amap)| OCELType {
+            name,
+            attributes: amap
+                .into_iter()
+                .map(|(aname, vt)| OCELTypeAttribute::new(aname, &vty_to_attr_type(vt)))
+                .collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use process_mining::ocel::ocel_struct::OCELAttributeValue;
+
+    #[test]
+    fn test_convert_flat_csv() {
+        let csv = "activity,timestamp,order_id,item_id,price,resource\n\
+                   place order,2024-01-01T10:00:00Z,1,1;2,5.5,alice\n\
+                   pick item,2024-01-01T11:00:00Z,,1,6.0,bob\n\
+                   pick item,2024-01-01T12:00:00Z,,2,,bob\n";
+        let mapping: FlatLogMapping = serde_json::from_value(serde_json::json!({
+            "activity_column": "activity",
+            "timestamp_column": "timestamp",
+            "object_columns": { "order_id": "order", "item_id": "item" },
+            "event_attributes": ["resource"],
+            "object_attributes": { "price": "item_id" },
+            "id_separator": ";"
+        }))
+        .unwrap();
+
+        let ocel = import_flat_log(csv.as_bytes(), FlatLogFormat::Csv, &mapping).unwrap();
+
+        assert_eq!(ocel.events.len(), 3);
+        // "1" is both an order and an item id
+        let ids: Vec<&str> = ocel.objects.iter().map(|o| o.id.as_str()).collect();
+        assert_eq!(ids, vec!["2", "item:1", "order:1"]);
+        assert_eq!(ocel.events[0].relationships.len(), 3);
+        assert_eq!(ocel.events[0].relationships[0].qualifier, "item_id");
+
+        let item1 = ocel.objects.iter().find(|o| o.id == "item:1").unwrap();
+        let prices: Vec<&OCELAttributeValue> = item1.attributes.iter().map(|a| &a.value).collect();
+        assert_eq!(prices, vec![&OCELAttributeValue::Float(5.5), &OCELAttributeValue::Float(6.0)]);
+
+        let names: Vec<&str> = ocel.object_types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["item", "order"]);
+    }
+
+    #[test]
+    fn test_flat_csv_ids_are_raw_strings() {
+        let csv = "id,activity,timestamp,order_id,item_id\n\
+                   001,place order,2024-01-01T10:00:00Z,1,007\n\
+                   1,pick item,2024-01-01T11:00:00Z,,7\n\
+                   2,pick item,2024-01-01T12:00:00Z,,12345678901234567890\n\
+                   3,pick item,2024-01-01T13:00:00Z,,order:1\n\
+                   4,pick item,2024-01-01T14:00:00Z,,1\n";
+        let mapping: FlatLogMapping = serde_json::from_value(serde_json::json!({
+            "activity_column": "activity",
+            "timestamp_column": "timestamp",
+            "event_id_column": "id",
+            "object_columns": { "order_id": "order", "item_id": "item" },
+        }))
+        .unwrap();
+
+        let ocel = import_flat_log(csv.as_bytes(), FlatLogFormat::Csv, &mapping).unwrap();
+
+        let event_ids: Vec<&str> = ocel.events.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(event_ids, vec!["001", "1", "2", "3", "4"]);
+        // "007" and "7" are distinct items; the disambiguated order "1" must not merge with the
+        // item that is literally called "order:1"
+        let ids: Vec<&str> = ocel.objects.iter().map(|o| o.id.as_str()).collect();
+        assert_eq!(ids, vec!["007", "12345678901234567890", "7", "item:1", "order:1", "order:1#2"]);
+        let order = ocel.objects.iter().find(|o| o.object_type == "order").unwrap();
+        assert_eq!(order.id, "order:1#2");
+    }
+}
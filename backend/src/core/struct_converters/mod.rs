@@ -1,3 +1,11 @@
 pub mod utils;
 pub mod ocel_1_ocel_2_converter;
+pub mod ocel_2_ocel_1_converter;
+pub mod ocel1_xml;
+pub mod ocel_formats;
+pub mod flat_log_converter;
+pub mod upload_stream;
+pub mod ocel_storage;
+pub mod ocpt_storage;
+pub mod ocel_validation;
 pub mod ocpt_frontend_backend;
\ No newline at end of file
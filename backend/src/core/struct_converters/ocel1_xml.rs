@@ -0,0 +1,296 @@
+//! Parse **OCEL 1.0 XML** (XMLOCEL) into the [`Ocel1`] model.
+//!
+//! The result is the same structure the JSONOCEL deserializer produces, so
+//! [`crate::core::struct_converters::ocel_1_ocel_2_converter::convert_ocel1_to_ocel`]
+//! normalizes both serializations into OCEL 2.0.
+use anyhow::{anyhow, Context, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::io::BufRead;
+
+use crate::core::struct_converters::upload_stream::{decompressed_limit_bytes, DecompressedLimitExceeded};
+use crate::models::ocel1::{Ocel1, Ocel1Event, Ocel1Object};
+
+/*
+Example XMLOCEL input (keys may also carry an `ocel:` prefix):
+
+<log>
+  <global scope="log">
+    <list key="object-types">
+      <string key="object-type" value="order"/>
+    </list>
+  </global>
+  <events>
+    <event>
+      <string key="id" value="e1"/>
+      <string key="activity" value="place order"/>
+      <date key="timestamp" value="2020-07-09T08:21:01.527+01:00"/>
+      <list key="omap">
+        <string key="object-id" value="o1"/>
+      </list>
+      <list key="vmap">
+        <float key="prepaid-amount" value="1050.0"/>
+      </list>
+    </event>
+  </events>
+  <objects>
+    <object>
+      <string key="id" value="o1"/>
+      <string key="type" value="order"/>
+      <list key="ovmap">
+        <float key="price" value="21.5"/>
+      </list>
+    </object>
+  </objects>
+</log>
+*/
+
+/// Minimal element tree of an XMLOCEL document
+#[derive(Debug, Default)]
+struct XmlNode {
+    tag: String,
+    attrs: HashMap<String, String>,
+    children: Vec<XmlNode>,
+}
+
+impl XmlNode {
+    /// The `key` attribute without an `ocel:` prefix
+    fn key(&self) -> Option<&str> {
+        self.attrs.get("key").map(|k| k.strip_prefix("ocel:").unwrap_or(k))
+    }
+
+    fn child_by_key(&self, key: &str) -> Option<&XmlNode> {
+        self.children.iter().find(|c| c.key() == Some(key))
+    }
+
+    fn value_of(&self, key: &str) -> Option<&str> {
+        self.child_by_key(key)
+            .and_then(|c| c.attrs.get("value"))
+            .map(String::as_str)
+    }
+}
+
+/// Returns `true` if the XML document is an OCEL 1.0 log.
+///
+/// OCEL 1.0 XML starts with `<global>` declarations below `<log>`, OCEL 2.0 XML with
+/// `<object-types>`/`<event-types>`. Only the first element below the root is inspected, so
+/// `bytes` may be just the beginning of the document.
+pub fn is_ocel1_xml(bytes: &[u8]) -> bool {
+    let mut reader = Reader::from_reader(bytes);
+    let mut depth = 0usize;
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                if depth == 1 {
+                    return e.local_name().as_ref() == b"global";
+                }
+                depth += 1;
+            }
+            Ok(Event::Empty(e)) if depth == 1 => return e.local_name().as_ref() == b"global",
+            Ok(Event::End(_)) => depth = depth.saturating_sub(1),
+            Ok(Event::Eof) | Err(_) => return false,
+            _ => {}
+        }
+    }
+}
+
+/// Parse an OCEL **1.0 XML** document into an [`Ocel1`].
+///
+/// # Errors
+/// - The XML is malformed, or an event/object lacks its `id`, `activity`, `timestamp` or `type`.
+/// - [`DecompressedLimitExceeded`] if the document is larger than [`decompressed_limit_bytes`].
+pub fn parse_ocel1_xml<R: BufRead>(reader: R) -> Result<Ocel1> {
+    let root = read_tree(reader, decompressed_limit_bytes()).context("parse OCEL 1.0 XML")?;
+    if root.tag != "log" {
+        return Err(anyhow!("Expected <log> root element, found <{}>", root.tag));
+    }
+
+    let mut global_log = Map::new();
+    let mut events: HashMap<String, Ocel1Event> = HashMap::new();
+    let mut objects: HashMap<String, Ocel1Object> = HashMap::new();
+
+    for section in &root.children {
+        match section.tag.as_str() {
+            "global" if section.attrs.get("scope").map(String::as_str) == Some("log") => {
+                for entry in &section.children {
+                    if let Some(key) = entry.key() {
+                        global_log.insert(format!("ocel:{key}"), typed_value(entry));
+                    }
+                }
+            }
+            "events" => {
+                for ev in section.children.iter().filter(|c| c.tag == "event") {
+                    let id = required(ev, "id", "event")?;
+                    events.insert(
+                        id.to_string(),
+                        Ocel1Event {
+                            activity: required(ev, "activity", "event")?.to_string(),
+                            timestamp: required(ev, "timestamp", "event")?.to_string(),
+                            omap: ev
+                                .child_by_key("omap")
+                                .map(|l| {
+                                    l.children
+                                        .iter()
+                                        .filter_map(|c| c.attrs.get("value").cloned())
+                                        .collect()
+                                })
+                                .unwrap_or_default(),
+                            vmap: value_map(ev.child_by_key("vmap")),
+                        },
+                    );
+                }
+            }
+            "objects" => {
+                for ob in section.children.iter().filter(|c| c.tag == "object") {
+                    let id = required(ob, "id", "object")?;
+                    objects.insert(
+                        id.to_string(),
+                        Ocel1Object {
+                            object_type: required(ob, "type", "object")?.to_string(),
+                            ovmap: value_map(ob.child_by_key("ovmap")),
+                        },
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(Ocel1 { global_log: Value::Object(global_log), events, objects })
+}
+
+fn required<'a>(node: &'a XmlNode, key: &str, kind: &str) -> Result<&'a str> {
+    node.value_of(key)
+        .ok_or_else(|| anyhow!("OCEL 1.0 XML {kind} without '{key}'"))
+}
+
+/// Converts the typed children of a `vmap`/`ovmap` list into JSON values
+fn value_map(list: Option<&XmlNode>) -> BTreeMap<String, Value> {
+    list.map(|l| {
+        l.children
+            .iter()
+            .filter_map(|c| Some((c.key()?.to_string(), typed_value(c))))
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// Maps an XMLOCEL typed element (`<string>`, `<int>`, `<float>`, `<boolean>`, `<date>`,
+/// `<list>`) to the JSON value the JSONOCEL format would carry.
+/// Values that do not parse as their declared type are kept as strings.
+fn typed_value(node: &XmlNode) -> Value {
+    if node.tag == "list" {
+        return Value::Array(node.children.iter().map(typed_value).collect());
+    }
+    let Some(raw) = node.attrs.get("value") else {
+        return Value::Null;
+    };
+    match node.tag.as_str() {
+        "int" => raw.trim().parse::<i64>().map(Value::from).ok(),
+        "float" => raw
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number),
+        "boolean" => raw.trim().parse::<bool>().map(Value::Bool).ok(),
+        _ => None,
+    }
+    .unwrap_or_else(|| Value::String(raw.clone()))
+}
+
+/// Reads the whole document into an [`XmlNode`] tree and returns the root element. The tree is
+/// held in memory, so documents longer than `limit` bytes are rejected.
+fn read_tree<R: BufRead>(reader: R, limit: usize) -> Result<XmlNode> {
+    let mut reader = Reader::from_reader(reader);
+    reader.trim_text(true);
+
+    let mut buf: Vec<u8> = Vec::new();
+    let mut stack: Vec<XmlNode> = Vec::new();
+    loop {
+        if reader.buffer_position() > limit {
+            return Err(DecompressedLimitExceeded { limit }.into());
+        }
+        buf.clear();
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => stack.push(to_node(&e)?),
+            Event::Empty(e) => {
+                let node = to_node(&e)?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(node),
+                    None => return Ok(node),
+                }
+            }
+            Event::End(_) => {
+                let node = stack.pop().ok_or_else(|| anyhow!("Unbalanced closing tag"))?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(node),
+                    None => return Ok(node),
+                }
+            }
+            Event::Eof => return Err(anyhow!("Unexpected end of document")),
+            _ => {}
+        }
+    }
+}
+
+fn to_node(e: &BytesStart) -> Result<XmlNode> {
+    let mut attrs = HashMap::new();
+    for attr in e.attributes() {
+        let attr = attr?;
+        let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).into_owned();
+        attrs.insert(key, attr.unescape_value()?.into_owned());
+    }
+    Ok(XmlNode {
+        tag: String::from_utf8_lossy(e.local_name().as_ref()).into_owned(),
+        attrs,
+        children: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ocel1_xml() {
+        let xml = br#"<?xml version="1.0" encoding="UTF-8"?>
+<log>
+  <global scope="log">
+    <list key="object-types"><string key="object-type" value="order"/></list>
+  </global>
+  <events>
+    <event>
+      <string key="id" value="e1"/>
+      <string key="activity" value="place order"/>
+      <date key="timestamp" value="2020-07-09T08:21:01+00:00"/>
+      <list key="omap"><string key="object-id" value="o1"/></list>
+      <list key="vmap"><float key="amount" value="10.5"/><int key="items" value="3"/></list>
+    </event>
+  </events>
+  <objects>
+    <object>
+      <string key="ocel:id" value="o1"/>
+      <string key="ocel:type" value="order"/>
+      <list key="ocel:ovmap"><boolean key="paid" value="true"/></list>
+    </object>
+  </objects>
+</log>"#;
+
+        assert!(is_ocel1_xml(xml));
+        let o1 = parse_ocel1_xml(&xml[..]).unwrap();
+        let ev = &o1.events["e1"];
+        assert_eq!(ev.activity, "place order");
+        assert_eq!(ev.omap, vec!["o1".to_string()]);
+        assert_eq!(ev.vmap["amount"], Value::from(10.5));
+        assert_eq!(ev.vmap["items"], Value::from(3));
+        assert_eq!(o1.objects["o1"].object_type, "order");
+        assert_eq!(o1.objects["o1"].ovmap["paid"], Value::Bool(true));
+        assert_eq!(o1.global_log["ocel:object-types"], serde_json::json!(["order"]));
+
+        let err = read_tree(&xml[..], 100).unwrap_err();
+        assert!(err.is::<DecompressedLimitExceeded>(), "{err:#}");
+    }
+}
@@ -1,14 +1,17 @@
 //! Convert **OCEL 1.0** to **OCEL 2.0**.
 //!
 //! This module provides parsing and normalization utilities to transform
-//! legacy OCEL 1.0 logs into the OCEL v2 struct used in `process_mining`.
+//! legacy OCEL 1.0 logs (JSONOCEL and XMLOCEL) into the OCEL v2 struct used in `process_mining`.
 use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use crate::models::ocel1::{Ocel1, Ocel1Event, Ocel1Object};
+use crate::core::struct_converters::ocel1_xml::parse_ocel1_xml;
+use crate::core::struct_converters::ocel_formats::{export_ocel, OcelFormat};
 use crate::core::struct_converters::utils::{
     epoch_fixed_utc, json_to_attr_value, merge_tys, parse_time_any, vty_to_attr_type, VTy,
 };
@@ -18,6 +21,116 @@ use process_mining::ocel::ocel_struct::{
     OCELObjectAttribute, OCELRelationship, OCELType, OCELTypeAttribute,
 };
 
+/// Object type assigned to objects referenced in an `omap` without an entry in `ocel:objects`
+const UNKNOWN_OBJECT_TYPE: &str = "UNKNOWN";
+
+/// Maximum number of unparseable values listed individually in a [`ConversionReport`]
+const MAX_REPORTED_VALUES: usize = 50;
+
+/// How the qualifier of the E2O relationships is chosen (OCEL 1.0 has no qualifiers)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QualifierStrategy {
+    /// Object type of the related object, e.g. `"order"`
+    #[default]
+    ObjectType,
+    /// Activity of the event, e.g. `"place order"`
+    Activity,
+    /// [`ConversionOptions::constant_qualifier`] for every relationship
+    Constant,
+    /// Empty qualifier
+    Empty,
+}
+
+/// Time assigned to the attributes of objects no event refers to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnreferencedObjectTime {
+    /// Unix epoch (1970-01-01T00:00:00Z)
+    #[default]
+    Epoch,
+    /// Time of the earliest event of the log
+    LogStart,
+}
+
+/// Options of the OCEL 1.0 → 2.0 conversion
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ConversionOptions {
+    pub qualifier: QualifierStrategy,
+    /// Qualifier used by [`QualifierStrategy::Constant`]
+    pub constant_qualifier: String,
+    pub unreferenced_object_time: UnreferencedObjectTime,
+}
+
+impl Default for ConversionOptions {
+    fn default() -> Self {
+        Self {
+            qualifier: QualifierStrategy::default(),
+            constant_qualifier: "related".to_string(),
+            unreferenced_object_time: UnreferencedObjectTime::default(),
+        }
+    }
+}
+
+/// An attribute observed with several value types, resolved by [`merge_tys`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TypeConflict {
+    /// `"event"` or `"object"`
+    pub scope: &'static str,
+    /// Event or object type carrying the attribute
+    pub type_name: String,
+    pub attribute: String,
+    /// Observed OCEL 2.0 types, e.g. `["integer", "string"]`
+    pub observed: Vec<String>,
+    pub resolved: String,
+}
+
+/// A value without a faithful OCEL 2.0 representation (stored as its JSON string)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnparseableValue {
+    /// Id of the event or object carrying the value
+    pub owner: String,
+    pub attribute: String,
+    pub value: String,
+}
+
+/// What the conversion had to invent, drop or coerce
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConversionReport {
+    /// Objects created with type `"UNKNOWN"` for dangling omap ids
+    pub synthesized_objects: usize,
+    /// Objects created from `objid`/`objtype` hints in an event's vmap
+    pub objects_from_vmap_hints: usize,
+    /// Objects no event refers to; their attributes carry the fallback time
+    pub unreferenced_objects: usize,
+    pub unreferenced_object_time: UnreferencedObjectTime,
+    pub type_conflicts: Vec<TypeConflict>,
+    /// Number of array/object values stringified
+    pub unparseable_values: usize,
+    /// The first unparseable values (at most 50)
+    pub unparseable_value_samples: Vec<UnparseableValue>,
+    /// vmap keys not carried over as event attributes, with their number of occurrences
+    pub dropped_vmap_keys: BTreeMap<String, usize>,
+    pub qualifier: QualifierStrategy,
+}
+
+impl ConversionReport {
+    /// Counts `v` as unparseable if it has no scalar OCEL 2.0 representation
+    fn check_value(&mut self, owner: &str, attribute: &str, v: &Value) {
+        if !matches!(v, Value::Array(_) | Value::Object(_)) {
+            return;
+        }
+        self.unparseable_values += 1;
+        if self.unparseable_value_samples.len() < MAX_REPORTED_VALUES {
+            self.unparseable_value_samples.push(UnparseableValue {
+                owner: owner.to_string(),
+                attribute: attribute.to_string(),
+                value: v.to_string(),
+            });
+        }
+    }
+}
 
 /// Parse an OCEL **1.0 JSON string** and return a normalized [`OCEL`] (v2).
 ///
@@ -26,19 +139,25 @@ use process_mining::ocel::ocel_struct::{
 /// # Errors
 /// - JSON is not valid OCEL 1.0, or the conversion to v2 fails.
 /// 
-pub fn convert_ocel1_str_to_ocel(s: &str) -> Result<OCEL> {
+pub fn convert_ocel1_str_to_ocel(
+    s: &str,
+    options: &ConversionOptions,
+) -> Result<(OCEL, ConversionReport)> {
     let o1: Ocel1 = serde_json::from_str(s).context("deserialize OCEL 1.0 JSON")?;
-    convert_ocel1_to_ocel(o1)
+    convert_ocel1_to_ocel(o1, options)
 }
 
 
-/// Parse an OCEL **1.0 `serde_json::Value`** and return a normalized [`OCEL`] (v2).
+/// Parse an OCEL **1.0 XML document** and return a normalized [`OCEL`] (v2).
 ///
 /// # Errors
-/// - Value cannot be deserialized as OCEL 1.0, or conversion fails.
-pub fn convert_ocel1_value_to_ocel(val: &Value) -> Result<OCEL> {
-    let o1: Ocel1 = serde_json::from_value(val.clone()).context("deserialize OCEL 1.0 value")?;
-    convert_ocel1_to_ocel(o1)
+/// - XML is not valid OCEL 1.0, or conversion fails.
+pub fn convert_ocel1_xml_to_ocel(
+    bytes: &[u8],
+    options: &ConversionOptions,
+) -> Result<(OCEL, ConversionReport)> {
+    let o1: Ocel1 = parse_ocel1_xml(bytes)?;
+    convert_ocel1_to_ocel(o1, options)
 }
 
 /// Core converter: **OCEL 1.0 → OCEL 2.0**.
@@ -51,16 +170,27 @@ pub fn convert_ocel1_value_to_ocel(val: &Value) -> Result<OCEL> {
 /// 5. Infer event/object *type schemas* (attribute names and types).
 /// 6. Sort events by time (then id) and objects by id; return an [`OCEL`].
 ///
+/// Every lossy step (synthesized objects, fallback timestamps, type conflicts, stringified
+/// values, dropped vmap keys) is recorded in the returned [`ConversionReport`].
+///
 /// # Errors
 /// - Missing/invalid timestamps, empty event set, or any failed conversion step.
 /// # Note
 /// - This conversion doesn't create any O2O relationships, since these are not captured in OCEL 1.0.
-pub fn convert_ocel1_to_ocel(mut o1: Ocel1) -> Result<OCEL> {
+pub fn convert_ocel1_to_ocel(
+    mut o1: Ocel1,
+    options: &ConversionOptions,
+) -> Result<(OCEL, ConversionReport)> {
     if o1.events.is_empty() {
         return Err(anyhow!("No events found in OCEL 1.0 input"));
     }
 
-    ensure_objects_cover_omap_and_vmap(&mut o1);
+    let mut report = ConversionReport {
+        qualifier: options.qualifier,
+        unreferenced_object_time: options.unreferenced_object_time,
+        ..Default::default()
+    };
+    ensure_objects_cover_omap_and_vmap(&mut o1, &mut report);
 
     let mut event_times: HashMap<String, DateTime<FixedOffset>> = HashMap::new();
     for (eid, ev) in &o1.events {
@@ -87,28 +217,48 @@ pub fn convert_ocel1_to_ocel(mut o1: Ocel1) -> Result<OCEL> {
 
         let mut attrs: Vec<OCELEventAttribute> = Vec::new();
         for (k, v) in &ev.vmap {
-            if k == "objid" || k == "objtype" { continue; }
+            if k == "objid" || k == "objtype" {
+                *report.dropped_vmap_keys.entry(k.clone()).or_insert(0) += 1;
+                continue;
+            }
+            report.check_value(eid, k, v);
             attrs.push(OCELEventAttribute { name: k.clone(), value: json_to_attr_value(v) });
         }
 
         let mut rels: Vec<OCELRelationship> = Vec::with_capacity(ev.omap.len());
         for oid in ev.omap.iter().cloned().collect::<BTreeSet<_>>() {
-            let qualifier = o1
-                .objects
-                .get(&oid)
-                .map(|o| o.object_type.clone())
-                .unwrap_or_else(|| "UNKNOWN".to_string());
+            let qualifier = match options.qualifier {
+                QualifierStrategy::ObjectType => o1
+                    .objects
+                    .get(&oid)
+                    .map(|o| o.object_type.clone())
+                    .unwrap_or_else(|| UNKNOWN_OBJECT_TYPE.to_string()),
+                QualifierStrategy::Activity => ev.activity.clone(),
+                QualifierStrategy::Constant => options.constant_qualifier.clone(),
+                QualifierStrategy::Empty => String::new(),
+            };
             rels.push(OCELRelationship::new(oid, qualifier));
         }
 
         events_vec.push(OCELEvent::new(eid, &ev.activity, time, attrs, rels));
     }
 
+    let fallback_time = match options.unreferenced_object_time {
+        UnreferencedObjectTime::Epoch => epoch_fixed_utc(),
+        UnreferencedObjectTime::LogStart => *event_times.values().min().expect("events are non-empty"),
+    };
     let mut objects_vec: Vec<OCELObject> = Vec::with_capacity(o1.objects.len());
     for (oid, o) in &o1.objects {
-        let t0 = object_first_seen.get(oid).cloned().unwrap_or_else(epoch_fixed_utc);
+        let t0 = match object_first_seen.get(oid) {
+            Some(t) => *t,
+            None => {
+                report.unreferenced_objects += 1;
+                fallback_time
+            }
+        };
         let mut oattrs: Vec<OCELObjectAttribute> = Vec::new();
         for (k, v) in &o.ovmap {
+            report.check_value(oid, k, v);
             oattrs.push(OCELObjectAttribute::new(k, json_to_attr_value(v), t0));
         }
         objects_vec.push(OCELObject {
@@ -119,8 +269,12 @@ pub fn convert_ocel1_to_ocel(mut o1: Ocel1) -> Result<OCEL> {
         });
     }
 
-    let event_types  = infer_event_types(&o1.events);
-    let object_types = infer_object_types(&o1.objects, o1.global_log.get("ocel:object-types"));
+    let event_types  = infer_event_types(&o1.events, &mut report.type_conflicts);
+    let object_types = infer_object_types(
+        &o1.objects,
+        o1.global_log.get("ocel:object-types"),
+        &mut report.type_conflicts,
+    );
 
     events_vec.sort_by(|a, b| match a.time.cmp(&b.time) {
         Ordering::Equal => a.id.cmp(&b.id),
@@ -128,15 +282,16 @@ pub fn convert_ocel1_to_ocel(mut o1: Ocel1) -> Result<OCEL> {
     });
     objects_vec.sort_by(|a, b| a.id.cmp(&b.id));
 
-    Ok(OCEL { event_types, object_types, events: events_vec, objects: objects_vec })
+    Ok((OCEL { event_types, object_types, events: events_vec, objects: objects_vec }, report))
 }
 
     /// Ensure that the `objects` map in `o1` covers all object IDs referenced in the `omap` and `vmap` of `o1.events`.
     ///
     /// This function is a helper for conversion of OCEL 1.0 to OCEL 2.0.
     ///
-    /// It will insert missing objects with a default object type of `"UNKNOWN"` into `o1.objects`.
-fn ensure_objects_cover_omap_and_vmap(o1: &mut Ocel1) {
+    /// It will insert missing objects with a default object type of `"UNKNOWN"` into `o1.objects`
+    /// and count the inserted objects in `report`.
+fn ensure_objects_cover_omap_and_vmap(o1: &mut Ocel1, report: &mut ConversionReport) {
     let referenced: HashSet<String> = o1
         .events
         .values()
@@ -148,6 +303,9 @@ fn ensure_objects_cover_omap_and_vmap(o1: &mut Ocel1) {
         let id  = ev.vmap.get("objid");
         let ty  = ev.vmap.get("objtype");
         if let (Some(JsString(id)), Some(JsString(ty))) = (id, ty) {
+            if !o1.objects.contains_key(id) {
+                report.objects_from_vmap_hints += 1;
+            }
             o1.objects.entry(id.clone()).or_insert(crate::models::ocel1::Ocel1Object {
                 object_type: ty.clone(),
                 ovmap: BTreeMap::new(),
@@ -155,8 +313,11 @@ fn ensure_objects_cover_omap_and_vmap(o1: &mut Ocel1) {
         }
     }
     for oid in referenced {
+        if !o1.objects.contains_key(&oid) {
+            report.synthesized_objects += 1;
+        }
         o1.objects.entry(oid).or_insert(crate::models::ocel1::Ocel1Object {
-            object_type: "UNKNOWN".to_string(),
+            object_type: UNKNOWN_OBJECT_TYPE.to_string(),
             ovmap: BTreeMap::new(),
         });
     }
@@ -170,26 +331,22 @@ fn ensure_objects_cover_omap_and_vmap(o1: &mut Ocel1) {
 /// The types of the key-value pairs are merged using the `merge_tys` function.
 ///
 /// The resulting event types are then mapped to OCEL 2.0 event types, where the attribute types are converted using the `vty_to_attr_type` function.
-fn infer_event_types(events: &HashMap<String, Ocel1Event>) -> Vec<OCELType> {
-    let mut acc: BTreeMap<String, BTreeMap<String, VTy>> = BTreeMap::new();
+/// Attributes observed with several types are recorded in `conflicts`.
+fn infer_event_types(
+    events: &HashMap<String, Ocel1Event>,
+    conflicts: &mut Vec<TypeConflict>,
+) -> Vec<OCELType> {
+    let mut acc: BTreeMap<String, BTreeMap<String, BTreeSet<VTy>>> = BTreeMap::new();
     for ev in events.values() {
         let m = acc.entry(ev.activity.clone()).or_default();
         for (k, v) in &ev.vmap {
             if k == "objid" || k == "objtype" { continue; }
             if let Some(t) = VTy::of(v) {
-                m.entry(k.clone()).and_modify(|tt| *tt = merge_tys(*tt, t)).or_insert(t);
+                m.entry(k.clone()).or_default().insert(t);
             }
         }
     }
-    acc.into_iter()
-        .map(|(name, amap)| OCELType {
-            name,
-            attributes: amap
-                .into_iter()
-                .map(|(aname, vt)| OCELTypeAttribute::new(aname, &vty_to_attr_type(vt)))
-                .collect(),
-        })
-        .collect()
+    resolve_types("event", acc, conflicts)
 }
 
 /// Infers the object types from the given objects.
@@ -205,16 +362,18 @@ fn infer_event_types(events: &HashMap<String, Ocel1Event>) -> Vec<OCELType> {
 /// The declared list is expected to be a JSON array of strings, where each string represents an object type.
 ///
 /// The resulting object types are then filtered to only include the object types that are present in both the inferred object types and the declared object types (if provided).
+/// Attributes observed with several types are recorded in `conflicts`.
 fn infer_object_types(
     objects: &HashMap<String, Ocel1Object>,
     declared_list: Option<&Value>,
+    conflicts: &mut Vec<TypeConflict>,
 ) -> Vec<OCELType> {
-    let mut acc: BTreeMap<String, BTreeMap<String, VTy>> = BTreeMap::new();
+    let mut acc: BTreeMap<String, BTreeMap<String, BTreeSet<VTy>>> = BTreeMap::new();
     for o in objects.values() {
         let m = acc.entry(o.object_type.clone()).or_default();
         for (k, v) in &o.ovmap {
             if let Some(t) = VTy::of(v) {
-                m.entry(k.clone()).and_modify(|tt| *tt = merge_tys(*tt, t)).or_insert(t);
+                m.entry(k.clone()).or_default().insert(t);
             }
         }
     }
@@ -225,30 +384,122 @@ fn infer_object_types(
             }
         }
     }
+    resolve_types("object", acc, conflicts)
+}
+
+/// Merges the observed value types of every attribute with [`merge_tys`] (in a fixed order, so
+/// the result does not depend on the map iteration order) and maps them to OCEL 2.0 types.
+fn resolve_types(
+    scope: &'static str,
+    acc: BTreeMap<String, BTreeMap<String, BTreeSet<VTy>>>,
+    conflicts: &mut Vec<TypeConflict>,
+) -> Vec<OCELType> {
     acc.into_iter()
-        .map(|(name, amap)| OCELType {
-            name,
-            attributes: amap
+        .map(|(name, amap)| {
+            let attributes = amap
                 .into_iter()
-                .map(|(aname, vt)| OCELTypeAttribute::new(aname, &vty_to_attr_type(vt)))
-                .collect(),
+                .filter_map(|(aname, observed)| {
+                    let resolved = observed.iter().copied().reduce(merge_tys)?;
+                    if observed.len() > 1 {
+                        conflicts.push(TypeConflict {
+                            scope,
+                            type_name: name.clone(),
+                            attribute: aname.clone(),
+                            observed: observed
+                                .iter()
+                                .map(|t| vty_to_attr_type(*t).to_type_string())
+                                .collect(),
+                            resolved: vty_to_attr_type(resolved).to_type_string(),
+                        });
+                    }
+                    Some(OCELTypeAttribute::new(aname, &vty_to_attr_type(resolved)))
+                })
+                .collect();
+            OCELType { name, attributes }
         })
         .collect()
 }
 
 
 
-/// Reads an OCEL 1.0 JSON file from the given `input_path` and writes the converted OCEL 2.0 JSON to the given `output_path`.
-/// 
-/// calls the [`convert_ocel1_str_to_ocel` function to convert the OCEL 1.0 JSON string to an OCEL 2.0 object.
-pub fn convert_file(input_path: &std::path::Path, output_path: &std::path::Path) -> Result<()> {
-    let s = std::fs::read_to_string(input_path).with_context(|| {
-        format!("reading OCEL 1.0 JSON from {}", input_path.to_string_lossy())
+/// Reads an OCEL 1.0 file from the given `input_path` and writes the converted OCEL 2.0 to the given `output_path`.
+///
+/// The input is parsed as XMLOCEL if its extension is `.xml`/`.xmlocel` and as JSONOCEL otherwise.
+/// The output format follows the extension of `output_path` (see [`OcelFormat::from_file_name`]),
+/// defaulting to pretty-printed JSON. Returns the [`ConversionReport`] of the conversion.
+pub fn convert_file(
+    input_path: &std::path::Path,
+    output_path: &std::path::Path,
+) -> Result<ConversionReport> {
+    let bytes = std::fs::read(input_path).with_context(|| {
+        format!("reading OCEL 1.0 from {}", input_path.to_string_lossy())
     })?;
-    let oc = convert_ocel1_str_to_ocel(&s)?;
-    let out = serde_json::to_string_pretty(&oc)?;
+    let in_format = OcelFormat::from_file_name(&input_path.to_string_lossy());
+    let options = ConversionOptions::default();
+    let (oc, report) = if in_format == Some(OcelFormat::Xml) {
+        convert_ocel1_xml_to_ocel(&bytes, &options)?
+    } else {
+        let s = std::str::from_utf8(&bytes).context("OCEL 1.0 JSON is not valid UTF-8")?;
+        convert_ocel1_str_to_ocel(s, &options)?
+    };
+    let out = match OcelFormat::from_file_name(&output_path.to_string_lossy()) {
+        Some(format) if format != OcelFormat::Json => export_ocel(&oc, format)?,
+        _ => serde_json::to_vec_pretty(&oc)?,
+    };
     std::fs::write(output_path, out).with_context(|| {
-        format!("writing OCEL JSON to {}", output_path.to_string_lossy())
+        format!("writing OCEL to {}", output_path.to_string_lossy())
     })?;
-    Ok(())
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_conversion_report() {
+        let o1 = json!({
+            "ocel:global-log": {},
+            "ocel:events": {
+                "e1": {
+                    "ocel:activity": "place order",
+                    "ocel:timestamp": "2024-01-01T10:00:00Z",
+                    "ocel:omap": ["o1", "x1"],
+                    "ocel:vmap": { "amount": 3, "tags": ["a", "b"], "objid": "o2", "objtype": "order" }
+                },
+                "e2": {
+                    "ocel:activity": "place order",
+                    "ocel:timestamp": "2024-01-02T10:00:00Z",
+                    "ocel:omap": ["o1"],
+                    "ocel:vmap": { "amount": "three" }
+                }
+            },
+            "ocel:objects": {
+                "o1": { "ocel:type": "order", "ocel:ovmap": {} },
+                "o3": { "ocel:type": "order", "ocel:ovmap": { "price": 1.5 } }
+            }
+        });
+        let options = ConversionOptions {
+            qualifier: QualifierStrategy::Activity,
+            unreferenced_object_time: UnreferencedObjectTime::LogStart,
+            ..Default::default()
+        };
+
+        let (ocel, report) = convert_ocel1_str_to_ocel(&o1.to_string(), &options).unwrap();
+
+        assert_eq!(report.synthesized_objects, 1);
+        assert_eq!(report.objects_from_vmap_hints, 1);
+        // o2 (vmap hint only) and o3 are never in an omap
+        assert_eq!(report.unreferenced_objects, 2);
+        assert_eq!(report.unparseable_values, 1);
+        assert_eq!(report.dropped_vmap_keys.get("objid"), Some(&1));
+        assert_eq!(report.type_conflicts.len(), 1);
+        assert_eq!(report.type_conflicts[0].attribute, "amount");
+        assert_eq!(report.type_conflicts[0].resolved, "string");
+
+        assert!(ocel.events.iter().flat_map(|e| &e.relationships).all(|r| r.qualifier == "place order"));
+        let o3 = ocel.objects.iter().find(|o| o.id == "o3").unwrap();
+        assert_eq!(o3.attributes[0].time, ocel.events[0].time);
+    }
 }
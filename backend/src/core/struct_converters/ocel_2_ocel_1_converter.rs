@@ -0,0 +1,249 @@
+//! Convert **OCEL 2.0** to **OCEL 1.0** (JSONOCEL).
+//!
+//! The reverse of [`crate::core::struct_converters::ocel_1_ocel_2_converter`] for downstream
+//! tools that only read OCEL 1.0. Everything OCEL 1.0 cannot express (qualifiers, O2O
+//! relationships, attribute histories) is dropped or collapsed and reported.
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::core::struct_converters::ocel_formats::{import_ocel, OcelFormat};
+use crate::core::struct_converters::ocel_1_ocel_2_converter::ConversionOptions;
+use crate::models::ocel1::{Ocel1, Ocel1Event, Ocel1Object};
+use process_mining::ocel::ocel_struct::{OCELAttributeValue, OCELObjectAttribute};
+use process_mining::OCEL;
+
+/// How time-varying object attributes are collapsed into a single `ovmap` value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeCollapsePolicy {
+    /// Keep the earliest value
+    First,
+    /// Keep the latest value
+    #[default]
+    Last,
+    /// Fail the conversion
+    Error,
+}
+
+impl AttributeCollapsePolicy {
+    /// Parses a policy name (`first`, `last`, `error`)
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "first" => Some(Self::First),
+            "last" => Some(Self::Last),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+}
+
+/// Options of the OCEL 2.0 → 1.0 conversion
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DowngradeOptions {
+    pub object_attribute_policy: AttributeCollapsePolicy,
+}
+
+/// What the downgrade had to drop or collapse
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DowngradeReport {
+    /// O2O relationships, which OCEL 1.0 cannot represent
+    pub dropped_o2o_relations: usize,
+    /// E2O relationships merged because they only differed in their qualifier
+    pub merged_e2o_relations: usize,
+    /// Object attributes with several distinct values over time
+    pub collapsed_object_attributes: usize,
+    pub warnings: Vec<String>,
+}
+
+/// Core converter: **OCEL 2.0 → OCEL 1.0**.
+///
+/// **Steps**:
+/// 1. Events: event type becomes `ocel:activity`, E2O relationships become `ocel:omap`
+///    (qualifiers are dropped), attributes become `ocel:vmap`.
+/// 2. Objects: attributes become `ocel:ovmap`, collapsing value histories per
+///    [`AttributeCollapsePolicy`]; O2O relationships are dropped.
+/// 3. Global log: attribute names and object types.
+///
+/// # Errors
+/// - A time-varying object attribute is found under [`AttributeCollapsePolicy::Error`].
+pub fn convert_ocel_to_ocel1(
+    ocel: &OCEL,
+    options: &DowngradeOptions,
+) -> Result<(Ocel1, DowngradeReport)> {
+    let mut report = DowngradeReport::default();
+    let mut attribute_names: BTreeSet<String> = BTreeSet::new();
+
+    let mut events: HashMap<String, Ocel1Event> = HashMap::with_capacity(ocel.events.len());
+    for ev in &ocel.events {
+        let omap: BTreeSet<&str> = ev.relationships.iter().map(|r| r.object_id.as_str()).collect();
+        report.merged_e2o_relations += ev.relationships.len() - omap.len();
+
+        let vmap: BTreeMap<String, Value> = ev
+            .attributes
+            .iter()
+            .map(|a| (a.name.clone(), attr_value_to_json(&a.value)))
+            .collect();
+        attribute_names.extend(vmap.keys().cloned());
+
+        events.insert(
+            ev.id.clone(),
+            Ocel1Event {
+                activity: ev.event_type.clone(),
+                timestamp: ev.time.to_rfc3339(),
+                omap: omap.into_iter().map(str::to_string).collect(),
+                vmap,
+            },
+        );
+    }
+
+    let mut o2o_per_type: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut objects: HashMap<String, Ocel1Object> = HashMap::with_capacity(ocel.objects.len());
+    for ob in &ocel.objects {
+        if !ob.relationships.is_empty() {
+            report.dropped_o2o_relations += ob.relationships.len();
+            *o2o_per_type.entry(ob.object_type.as_str()).or_insert(0) += ob.relationships.len();
+        }
+
+        let mut history: BTreeMap<&str, Vec<&OCELObjectAttribute>> = BTreeMap::new();
+        for attr in &ob.attributes {
+            history.entry(attr.name.as_str()).or_default().push(attr);
+        }
+
+        let mut ovmap: BTreeMap<String, Value> = BTreeMap::new();
+        for (name, mut values) in history {
+            values.sort_by_key(|a| a.time);
+            let distinct = values.windows(2).filter(|w| w[0].value != w[1].value).count() + 1;
+            if distinct > 1 {
+                report.collapsed_object_attributes += 1;
+                if options.object_attribute_policy == AttributeCollapsePolicy::Error {
+                    return Err(anyhow!(
+                        "Object {} has {distinct} values over time for attribute '{name}'",
+                        ob.id
+                    ));
+                }
+            }
+            let kept = match options.object_attribute_policy {
+                AttributeCollapsePolicy::First => values[0],
+                AttributeCollapsePolicy::Last | AttributeCollapsePolicy::Error => values[values.len() - 1],
+            };
+            ovmap.insert(name.to_string(), attr_value_to_json(&kept.value));
+            attribute_names.insert(name.to_string());
+        }
+
+        objects.insert(
+            ob.id.clone(),
+            Ocel1Object { object_type: ob.object_type.clone(), ovmap },
+        );
+    }
+
+    for (otype, n) in o2o_per_type {
+        report
+            .warnings
+            .push(format!("Dropped {n} O2O relationships of objects of type '{otype}'"));
+    }
+    if report.merged_e2o_relations > 0 {
+        report.warnings.push(format!(
+            "Merged {} E2O relationships differing only in their qualifier",
+            report.merged_e2o_relations
+        ));
+    }
+    if report.collapsed_object_attributes > 0 {
+        report.warnings.push(format!(
+            "Collapsed {} time-varying object attributes (policy: {:?})",
+            report.collapsed_object_attributes, options.object_attribute_policy
+        ));
+    }
+
+    let object_types: Vec<&str> = ocel.object_types.iter().map(|t| t.name.as_str()).collect();
+    let global_log = json!({
+        "ocel:version": "1.0",
+        "ocel:ordering": "timestamp",
+        "ocel:attribute-names": attribute_names,
+        "ocel:object-types": object_types,
+    });
+
+    Ok((Ocel1 { global_log, events, objects }, report))
+}
+
+fn attr_value_to_json(v: &OCELAttributeValue) -> Value {
+    match v {
+        OCELAttributeValue::Time(dt) => Value::String(dt.to_rfc3339()),
+        OCELAttributeValue::Integer(i) => Value::from(*i),
+        OCELAttributeValue::Float(f) => serde_json::Number::from_f64(*f).map_or(Value::Null, Value::Number),
+        OCELAttributeValue::Boolean(b) => Value::Bool(*b),
+        OCELAttributeValue::String(s) => Value::String(s.clone()),
+        OCELAttributeValue::Null => Value::Null,
+    }
+}
+
+/// Reads an OCEL 2.0 file (JSON, XML or SQLite, picked by extension or content) from
+/// `input_path` and writes it as OCEL 1.0 JSON to `output_path`.
+pub fn downgrade_file(
+    input_path: &std::path::Path,
+    output_path: &std::path::Path,
+    options: &DowngradeOptions,
+) -> Result<DowngradeReport> {
+    let bytes = std::fs::read(input_path).with_context(|| {
+        format!("reading OCEL 2.0 from {}", input_path.to_string_lossy())
+    })?;
+    let format = OcelFormat::from_file_name(&input_path.to_string_lossy())
+        .or_else(|| OcelFormat::sniff(&bytes))
+        .ok_or_else(|| anyhow!("Unknown OCEL file format"))?;
+    let (ocel, _) = import_ocel(&bytes, format, &ConversionOptions::default())?;
+    let (o1, report) = convert_ocel_to_ocel1(&ocel, options)?;
+    let out = serde_json::to_string_pretty(&o1)?;
+    std::fs::write(output_path, out).with_context(|| {
+        format!("writing OCEL 1.0 JSON to {}", output_path.to_string_lossy())
+    })?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::struct_converters::ocel_1_ocel_2_converter::convert_ocel1_to_ocel;
+    use chrono::DateTime;
+    use process_mining::ocel::ocel_struct::{OCELEvent, OCELObject, OCELRelationship, OCELType};
+
+    #[test]
+    fn test_downgrade_roundtrip() {
+        let t = |s: &str| DateTime::parse_from_rfc3339(s).unwrap();
+        let ocel = OCEL {
+            event_types: vec![OCELType { name: "Load".to_string(), attributes: Vec::new() }],
+            object_types: vec![OCELType { name: "Container".to_string(), attributes: Vec::new() }],
+            events: vec![OCELEvent::new(
+                "e1",
+                "Load",
+                t("2024-01-01T10:00:00+00:00"),
+                Vec::new(),
+                vec![OCELRelationship::new("c1", "load"), OCELRelationship::new("c1", "check")],
+            )],
+            objects: vec![OCELObject {
+                id: "c1".to_string(),
+                object_type: "Container".to_string(),
+                attributes: vec![
+                    OCELObjectAttribute::new("weight", 10i64, t("2024-01-01T09:00:00+00:00")),
+                    OCELObjectAttribute::new("weight", 12i64, t("2024-01-01T11:00:00+00:00")),
+                ],
+                relationships: vec![OCELRelationship::new("c2", "part of")],
+            }],
+        };
+
+        let (o1, report) = convert_ocel_to_ocel1(&ocel, &DowngradeOptions::default()).unwrap();
+        assert_eq!(report.dropped_o2o_relations, 1);
+        assert_eq!(report.merged_e2o_relations, 1);
+        assert_eq!(report.collapsed_object_attributes, 1);
+        assert_eq!(o1.objects["c1"].ovmap["weight"], Value::from(12));
+        assert_eq!(o1.events["e1"].omap, vec!["c1".to_string()]);
+
+        let strict = DowngradeOptions { object_attribute_policy: AttributeCollapsePolicy::Error };
+        assert!(convert_ocel_to_ocel1(&ocel, &strict).is_err());
+
+        let (back, _) = convert_ocel1_to_ocel(o1, &ConversionOptions::default()).unwrap();
+        assert_eq!(back.events[0].relationships.len(), 1);
+        assert_eq!(back.events[0].time, ocel.events[0].time);
+    }
+}
@@ -0,0 +1,209 @@
+//! Import and export **OCEL 2.0** in its JSON, XML and SQLite serializations.
+//!
+//! JSON and XML uploads may also be OCEL 1.0, which is normalized via
+//! [`crate::core::struct_converters::ocel_1_ocel_2_converter`].
+use anyhow::{anyhow, Context, Result};
+use std::io::BufRead;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::core::struct_converters::ocel1_xml::{is_ocel1_xml, parse_ocel1_xml};
+use crate::models::ocel1::Ocel1;
+use crate::core::struct_converters::ocel_1_ocel_2_converter::{
+    self, ConversionOptions, ConversionReport,
+};
+use process_mining::ocel::xml_ocel_import::OCELImportOptions;
+use process_mining::{
+    export_ocel_sqlite_to_vec, export_ocel_xml, import_ocel_sqlite_from_slice, import_ocel_xml,
+    OCEL,
+};
+
+/// Magic header of every SQLite 3 database file
+const SQLITE_MAGIC: &[u8] = b"SQLite format 3\0";
+
+/// Serialization formats of an OCEL file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OcelFormat {
+    /// OCEL 2.0 JSON (or OCEL 1.0 JSONOCEL on import)
+    Json,
+    /// OCEL 2.0 XML (or OCEL 1.0 XMLOCEL on import)
+    Xml,
+    /// OCEL 2.0 SQLite
+    Sqlite,
+}
+
+impl OcelFormat {
+    /// Parses a format name as given in the `file_type` form field or the `format` query
+    /// parameter. Returns `None` for unknown names (e.g. the frontend's `"ocelFile"`).
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s.trim().trim_start_matches('.').to_lowercase().as_str() {
+            "json" | "jsonocel" => Some(Self::Json),
+            "xml" | "xmlocel" => Some(Self::Xml),
+            "sqlite" | "sqlite3" | "db" => Some(Self::Sqlite),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format from the file extension of an uploaded file name
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        std::path::Path::new(name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_name)
+    }
+
+    /// Guesses the format from the first bytes of the content
+    pub fn sniff(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(SQLITE_MAGIC) {
+            return Some(Self::Sqlite);
+        }
+        let text = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+        match text.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'{') => Some(Self::Json),
+            Some(b'<') => Some(Self::Xml),
+            _ => None,
+        }
+    }
+
+    /// The MIME type used when serving this format
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Xml => "application/xml",
+            Self::Sqlite => "application/vnd.sqlite3",
+        }
+    }
+
+    /// The file extension used when serving this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Xml => "xml",
+            Self::Sqlite => "sqlite",
+        }
+    }
+}
+
+/// Imports an uploaded OCEL in the given format and returns it normalized as [`OCEL`] (v2).
+///
+/// The [`ConversionReport`] is `Some` iff the upload was converted from OCEL 1.0.
+///
+/// # Errors
+/// - The bytes cannot be decoded/parsed in the given format.
+pub fn import_ocel(
+    bytes: &[u8],
+    format: OcelFormat,
+    options: &ConversionOptions,
+) -> Result<(OCEL, Option<ConversionReport>)> {
+    import_ocel_from_reader(bytes, bytes, format, options)
+}
+
+/// Streaming variant of [`import_ocel`]: parses the OCEL directly from `reader` without
+/// buffering the whole document. `head` holds (at least) the first bytes of the content and
+/// is used to tell OCEL 1.0 from OCEL 2.0. SQLite databases are read into memory.
+///
+/// # Errors
+/// - The content cannot be decoded/parsed in the given format.
+pub fn import_ocel_from_reader<R: BufRead>(
+    head: &[u8],
+    mut reader: R,
+    format: OcelFormat,
+    options: &ConversionOptions,
+) -> Result<(OCEL, Option<ConversionReport>)> {
+    match format {
+        OcelFormat::Json if first_json_key(head).is_some_and(|k| k.starts_with("ocel:")) => {
+            let o1: Ocel1 = serde_json::from_reader(reader).context("Invalid OCEL 1.0 structure")?;
+            ocel_1_ocel_2_converter::convert_ocel1_to_ocel(o1, options)
+                .map(|(ocel, report)| (ocel, Some(report)))
+                .context("OCEL 1.0 to 2.0 conversion failed")
+        }
+        OcelFormat::Json => {
+            let ocel: OCEL = serde_json::from_reader(reader).context("Invalid OCEL 2.0 structure")?;
+            Ok((ocel, None))
+        }
+        OcelFormat::Xml if is_ocel1_xml(head) => {
+            let o1 = parse_ocel1_xml(reader)?;
+            ocel_1_ocel_2_converter::convert_ocel1_to_ocel(o1, options)
+                .map(|(ocel, report)| (ocel, Some(report)))
+                .context("OCEL 1.0 to 2.0 conversion failed")
+        }
+        OcelFormat::Xml => {
+            // The XML importer panics on malformed input instead of returning an error
+            catch_unwind(AssertUnwindSafe(|| {
+                let mut xml = quick_xml::Reader::from_reader(reader);
+                import_ocel_xml(&mut xml, OCELImportOptions { verbose: false, ..Default::default() })
+            }))
+            .map(|ocel| (ocel, None))
+            .map_err(|_| anyhow!("Invalid OCEL 2.0 XML"))
+        }
+        OcelFormat::Sqlite => {
+            let mut bytes: Vec<u8> = Vec::new();
+            reader.read_to_end(&mut bytes).context("read OCEL 2.0 SQLite database")?;
+            import_ocel_sqlite_from_slice(&bytes)
+                .map(|ocel| (ocel, None))
+                .context("Invalid OCEL 2.0 SQLite database")
+        }
+    }
+}
+
+/// The first object key of a JSON document (`None` if `head` does not start with one)
+fn first_json_key(head: &[u8]) -> Option<&str> {
+    let text = head.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(head);
+    let start = text.iter().position(|b| !b.is_ascii_whitespace())?;
+    let rest = text[start..].strip_prefix(b"{")?;
+    let quote = rest.iter().position(|b| !b.is_ascii_whitespace())?;
+    let key = rest[quote..].strip_prefix(b"\"")?;
+    let end = key.iter().position(|&b| b == b'"')?;
+    std::str::from_utf8(&key[..end]).ok()
+}
+
+/// Serializes an [`OCEL`] in the given format.
+///
+/// # Errors
+/// - The serializer of the format fails.
+pub fn export_ocel(ocel: &OCEL, format: OcelFormat) -> Result<Vec<u8>> {
+    match format {
+        OcelFormat::Json => serde_json::to_vec(ocel).context("serialize OCEL as JSON"),
+        OcelFormat::Xml => {
+            let mut out: Vec<u8> = Vec::new();
+            export_ocel_xml(&mut out, ocel).context("serialize OCEL as XML")?;
+            Ok(out)
+        }
+        OcelFormat::Sqlite => export_ocel_sqlite_to_vec(ocel).context("serialize OCEL as SQLite"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+    use process_mining::ocel::ocel_struct::{OCELEvent, OCELObject, OCELRelationship, OCELType};
+
+    #[test]
+    fn test_xml_and_sqlite_roundtrip() {
+        let ocel = OCEL {
+            event_types: vec![OCELType { name: "Load".to_string(), attributes: Vec::new() }],
+            object_types: vec![OCELType { name: "Container".to_string(), attributes: Vec::new() }],
+            events: vec![OCELEvent::new(
+                "e1",
+                "Load",
+                DateTime::parse_from_rfc3339("2024-01-01T10:00:00+00:00").unwrap(),
+                Vec::new(),
+                vec![OCELRelationship::new("c1", "load")],
+            )],
+            objects: vec![OCELObject {
+                id: "c1".to_string(),
+                object_type: "Container".to_string(),
+                attributes: Vec::new(),
+                relationships: Vec::new(),
+            }],
+        };
+
+        for format in [OcelFormat::Json, OcelFormat::Xml, OcelFormat::Sqlite] {
+            let bytes = export_ocel(&ocel, format).unwrap();
+            assert_eq!(OcelFormat::sniff(&bytes), Some(format));
+            let (imported, report) = import_ocel(&bytes, format, &ConversionOptions::default()).unwrap();
+            assert_eq!(imported, ocel, "{format:?}");
+            assert!(report.is_none());
+        }
+    }
+}
@@ -0,0 +1,444 @@
+//! Compact binary on-disk storage of normalized OCEL 2.0 logs.
+//!
+//! Uploads are stored as `./temp/ocel_v2_{id}.bin`: a short header followed by a bincode
+//! encoding of a pre-indexed form of the [`OCEL`]. Type names, attribute names and qualifiers
+//! are interned into a string table and E2O/O2O relationships point to object indices, so
+//! loading neither parses JSON nor re-resolves object ids. JSON is only produced when a client
+//! asks for it. Logs stored as `ocel_v2_{id}.json` by earlier versions are still read.
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, FixedOffset, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use process_mining::ocel::ocel_struct::{
+    OCELAttributeValue, OCELEvent, OCELEventAttribute, OCELObject, OCELObjectAttribute,
+    OCELRelationship, OCELType, OCELTypeAttribute,
+};
+use process_mining::OCEL;
+
+/// Directory all uploaded logs are stored in
+pub const STORAGE_DIR: &str = "./temp";
+
+/// Leading bytes of every binary OCEL file
+const MAGIC: &[u8; 8] = b"OCELBIN\0";
+
+/// Version of the binary layout, bumped on every incompatible change
+const FORMAT_VERSION: u32 = 1;
+
+/// Path of the binary OCEL stored under `id`
+pub fn binary_path(id: &str) -> PathBuf {
+    Path::new(STORAGE_DIR).join(format!("ocel_v2_{id}.bin"))
+}
+
+/// Path of an OCEL stored as JSON under `id` (written by earlier versions)
+pub fn legacy_json_path(id: &str) -> PathBuf {
+    Path::new(STORAGE_DIR).join(format!("ocel_v2_{id}.json"))
+}
+
+/// Stores `ocel` under `id` in the binary format, replacing a legacy JSON file of the same id.
+///
+/// The file is written next to its final location and renamed, so readers never see a
+/// partially written log.
+pub fn store_ocel(id: &str, ocel: &OCEL) -> Result<PathBuf> {
+    let path = binary_path(id);
+    let part = path.with_extension("bin.part");
+    let mut writer = BufWriter::new(File::create(&part).context("create OCEL file")?);
+    write_ocel(&mut writer, ocel)?;
+    writer.flush()?;
+    drop(writer);
+    std::fs::rename(&part, &path).context("move OCEL file into place")?;
+    let legacy = legacy_json_path(id);
+    if legacy.exists() {
+        std::fs::remove_file(&legacy).context("remove legacy OCEL file")?;
+    }
+    Ok(path)
+}
+
+/// Loads the OCEL stored under `id`, or `None` if there is none.
+///
+/// # Errors
+/// - The stored file cannot be read or is not a valid OCEL.
+pub fn load_ocel(id: &str) -> Result<Option<OCEL>> {
+    let path = binary_path(id);
+    if path.exists() {
+        let reader = BufReader::new(File::open(&path).context("open OCEL file")?);
+        return read_ocel(reader).map(Some);
+    }
+    let legacy = legacy_json_path(id);
+    if legacy.exists() {
+        let reader = BufReader::new(File::open(&legacy).context("open OCEL file")?);
+        let ocel = serde_json::from_reader(reader).context("Stored file is not a valid OCEL")?;
+        return Ok(Some(ocel));
+    }
+    Ok(None)
+}
+
+/// [`load_ocel`] on the blocking thread pool, for use in request handlers
+pub async fn load_ocel_async(id: &str) -> Result<Option<OCEL>> {
+    let id = id.to_string();
+    tokio::task::spawn_blocking(move || load_ocel(&id)).await?
+}
+
+/// Writes `ocel` in the binary format
+pub fn write_ocel<W: Write>(mut writer: W, ocel: &OCEL) -> Result<()> {
+    writer.write_all(MAGIC)?;
+    writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+    bincode::serialize_into(writer, &StoredOcel::from_ocel(ocel)).context("encode OCEL")?;
+    Ok(())
+}
+
+/// Reads an OCEL written by [`write_ocel`]
+pub fn read_ocel<R: Read>(mut reader: R) -> Result<OCEL> {
+    let mut header = [0u8; 12];
+    reader.read_exact(&mut header).context("read OCEL header")?;
+    if &header[..8] != MAGIC {
+        bail!("Not a binary OCEL file");
+    }
+    let version = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+    if version != FORMAT_VERSION {
+        bail!("Unsupported binary OCEL version {version} (expected {FORMAT_VERSION})");
+    }
+    let stored: StoredOcel = bincode::deserialize_from(reader).context("decode OCEL")?;
+    stored.into_ocel()
+}
+
+/// Pre-indexed OCEL as stored on disk; `u32` fields index into `strings`
+#[derive(Serialize, Deserialize)]
+struct StoredOcel {
+    strings: Vec<String>,
+    event_types: Vec<StoredType>,
+    object_types: Vec<StoredType>,
+    events: Vec<StoredEvent>,
+    objects: Vec<StoredObject>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredType {
+    name: u32,
+    /// Attribute name and value type
+    attributes: Vec<(u32, u32)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredEvent {
+    id: String,
+    event_type: u32,
+    time: StoredTime,
+    attributes: Vec<(u32, StoredValue)>,
+    relationships: Vec<StoredRelationship>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredObject {
+    id: String,
+    object_type: u32,
+    attributes: Vec<StoredObjectAttribute>,
+    relationships: Vec<StoredRelationship>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredObjectAttribute {
+    name: u32,
+    value: StoredValue,
+    time: StoredTime,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredRelationship {
+    object: ObjectRef,
+    qualifier: u32,
+}
+
+/// Target of a relationship: an index into the objects, or the raw id if it names no object
+#[derive(Serialize, Deserialize)]
+enum ObjectRef {
+    Index(u32),
+    Dangling(String),
+}
+
+/// Seconds and nanoseconds since the epoch (UTC) and the UTC offset in seconds
+#[derive(Serialize, Deserialize)]
+struct StoredTime {
+    secs: i64,
+    nanos: u32,
+    offset: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+enum StoredValue {
+    Time(StoredTime),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    String(String),
+    Null,
+}
+
+impl StoredTime {
+    fn from_datetime(dt: &DateTime<FixedOffset>) -> Self {
+        Self {
+            secs: dt.timestamp(),
+            nanos: dt.timestamp_subsec_nanos(),
+            offset: dt.offset().local_minus_utc(),
+        }
+    }
+
+    fn to_datetime(&self) -> Result<DateTime<FixedOffset>> {
+        let offset = FixedOffset::east_opt(self.offset)
+            .ok_or_else(|| anyhow!("Invalid UTC offset {}", self.offset))?;
+        offset
+            .timestamp_opt(self.secs, self.nanos)
+            .single()
+            .ok_or_else(|| anyhow!("Invalid timestamp {}.{}", self.secs, self.nanos))
+    }
+}
+
+impl StoredValue {
+    fn from_value(v: &OCELAttributeValue) -> Self {
+        match v {
+            OCELAttributeValue::Time(dt) => Self::Time(StoredTime::from_datetime(dt)),
+            OCELAttributeValue::Integer(i) => Self::Integer(*i),
+            OCELAttributeValue::Float(f) => Self::Float(*f),
+            OCELAttributeValue::Boolean(b) => Self::Boolean(*b),
+            OCELAttributeValue::String(s) => Self::String(s.clone()),
+            OCELAttributeValue::Null => Self::Null,
+        }
+    }
+
+    fn into_value(self) -> Result<OCELAttributeValue> {
+        Ok(match self {
+            Self::Time(t) => OCELAttributeValue::Time(t.to_datetime()?),
+            Self::Integer(i) => OCELAttributeValue::Integer(i),
+            Self::Float(f) => OCELAttributeValue::Float(f),
+            Self::Boolean(b) => OCELAttributeValue::Boolean(b),
+            Self::String(s) => OCELAttributeValue::String(s),
+            Self::Null => OCELAttributeValue::Null,
+        })
+    }
+}
+
+/// Interns strings while encoding
+#[derive(Default)]
+struct Interner {
+    strings: Vec<String>,
+    index: HashMap<String, u32>,
+}
+
+impl Interner {
+    fn intern(&mut self, s: &str) -> u32 {
+        if let Some(&i) = self.index.get(s) {
+            return i;
+        }
+        let i = self.strings.len() as u32;
+        self.strings.push(s.to_string());
+        self.index.insert(s.to_string(), i);
+        i
+    }
+}
+
+impl StoredOcel {
+    fn from_ocel(ocel: &OCEL) -> Self {
+        let mut interner = Interner::default();
+        let object_index: HashMap<&str, u32> = ocel
+            .objects
+            .iter()
+            .enumerate()
+            .map(|(i, o)| (o.id.as_str(), i as u32))
+            .collect();
+
+        let store_types = |types: &[OCELType], interner: &mut Interner| -> Vec<StoredType> {
+            types
+                .iter()
+                .map(|t| StoredType {
+                    name: interner.intern(&t.name),
+                    attributes: t
+                        .attributes
+                        .iter()
+                        .map(|a| (interner.intern(&a.name), interner.intern(&a.value_type)))
+                        .collect(),
+                })
+                .collect()
+        };
+        let event_types = store_types(&ocel.event_types, &mut interner);
+        let object_types = store_types(&ocel.object_types, &mut interner);
+
+        let store_relationships = |rels: &[OCELRelationship], interner: &mut Interner| {
+            rels.iter()
+                .map(|r| StoredRelationship {
+                    object: match object_index.get(r.object_id.as_str()) {
+                        Some(&i) => ObjectRef::Index(i),
+                        None => ObjectRef::Dangling(r.object_id.clone()),
+                    },
+                    qualifier: interner.intern(&r.qualifier),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let events = ocel
+            .events
+            .iter()
+            .map(|e| StoredEvent {
+                id: e.id.clone(),
+                event_type: interner.intern(&e.event_type),
+                time: StoredTime::from_datetime(&e.time),
+                attributes: e
+                    .attributes
+                    .iter()
+                    .map(|a| (interner.intern(&a.name), StoredValue::from_value(&a.value)))
+                    .collect(),
+                relationships: store_relationships(&e.relationships, &mut interner),
+            })
+            .collect();
+
+        let objects = ocel
+            .objects
+            .iter()
+            .map(|o| StoredObject {
+                id: o.id.clone(),
+                object_type: interner.intern(&o.object_type),
+                attributes: o
+                    .attributes
+                    .iter()
+                    .map(|a| StoredObjectAttribute {
+                        name: interner.intern(&a.name),
+                        value: StoredValue::from_value(&a.value),
+                        time: StoredTime::from_datetime(&a.time),
+                    })
+                    .collect(),
+                relationships: store_relationships(&o.relationships, &mut interner),
+            })
+            .collect();
+
+        Self { strings: interner.strings, event_types, object_types, events, objects }
+    }
+
+    fn into_ocel(self) -> Result<OCEL> {
+        let strings = self.strings;
+        let s = |i: u32| -> Result<String> {
+            strings
+                .get(i as usize)
+                .cloned()
+                .ok_or_else(|| anyhow!("String index {i} out of range"))
+        };
+        let object_ids: Vec<String> = self.objects.iter().map(|o| o.id.clone()).collect();
+        let relationships = |rels: Vec<StoredRelationship>| -> Result<Vec<OCELRelationship>> {
+            rels.into_iter()
+                .map(|r| {
+                    let object_id = match r.object {
+                        ObjectRef::Index(i) => object_ids
+                            .get(i as usize)
+                            .cloned()
+                            .ok_or_else(|| anyhow!("Object index {i} out of range"))?,
+                        ObjectRef::Dangling(id) => id,
+                    };
+                    Ok(OCELRelationship { object_id, qualifier: s(r.qualifier)? })
+                })
+                .collect()
+        };
+        let types = |types: Vec<StoredType>| -> Result<Vec<OCELType>> {
+            types
+                .into_iter()
+                .map(|t| {
+                    Ok(OCELType {
+                        name: s(t.name)?,
+                        attributes: t
+                            .attributes
+                            .into_iter()
+                            .map(|(name, value_type)| {
+                                Ok(OCELTypeAttribute { name: s(name)?, value_type: s(value_type)? })
+                            })
+                            .collect::<Result<_>>()?,
+                    })
+                })
+                .collect()
+        };
+
+        let event_types = types(self.event_types)?;
+        let object_types = types(self.object_types)?;
+        let events = self
+            .events
+            .into_iter()
+            .map(|e| {
+                Ok(OCELEvent {
+                    id: e.id,
+                    event_type: s(e.event_type)?,
+                    time: e.time.to_datetime()?,
+                    attributes: e
+                        .attributes
+                        .into_iter()
+                        .map(|(name, value)| {
+                            Ok(OCELEventAttribute { name: s(name)?, value: value.into_value()? })
+                        })
+                        .collect::<Result<_>>()?,
+                    relationships: relationships(e.relationships)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        let objects = self
+            .objects
+            .into_iter()
+            .map(|o| {
+                Ok(OCELObject {
+                    id: o.id,
+                    object_type: s(o.object_type)?,
+                    attributes: o
+                        .attributes
+                        .into_iter()
+                        .map(|a| {
+                            Ok(OCELObjectAttribute {
+                                name: s(a.name)?,
+                                value: a.value.into_value()?,
+                                time: a.time.to_datetime()?,
+                            })
+                        })
+                        .collect::<Result<_>>()?,
+                    relationships: relationships(o.relationships)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(OCEL { event_types, object_types, events, objects })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use process_mining::ocel::ocel_struct::OCELAttributeType;
+
+    #[test]
+    fn test_binary_roundtrip() {
+        let t = |s: &str| DateTime::parse_from_rfc3339(s).unwrap();
+        let ocel = OCEL {
+            event_types: vec![OCELType {
+                name: "Load".to_string(),
+                attributes: vec![OCELTypeAttribute::new("weight", &OCELAttributeType::Float)],
+            }],
+            object_types: vec![OCELType { name: "Container".to_string(), attributes: Vec::new() }],
+            events: vec![OCELEvent::new(
+                "e1",
+                "Load",
+                t("2024-01-01T10:00:00.123456789+02:00"),
+                vec![OCELEventAttribute { name: "weight".to_string(), value: OCELAttributeValue::Float(1.5) }],
+                vec![OCELRelationship::new("c1", "load"), OCELRelationship::new("missing", "load")],
+            )],
+            objects: vec![OCELObject {
+                id: "c1".to_string(),
+                object_type: "Container".to_string(),
+                attributes: vec![OCELObjectAttribute::new("since", t("2023-12-31T00:00:00-05:00"), t("2024-01-01T09:00:00+00:00"))],
+                relationships: vec![OCELRelationship::new("c1", "self")],
+            }],
+        };
+
+        let mut bytes = Vec::new();
+        write_ocel(&mut bytes, &ocel).unwrap();
+        let back = read_ocel(&bytes[..]).unwrap();
+        assert_eq!(back, ocel);
+        assert_eq!(back.events[0].time.offset(), ocel.events[0].time.offset());
+
+        assert!(read_ocel(&b"{\"events\": []}"[..]).is_err());
+    }
+}
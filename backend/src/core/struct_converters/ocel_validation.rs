@@ -0,0 +1,516 @@
+//! Schema and integrity validation of (normalized) OCEL 2.0 logs.
+//!
+//! Checks for duplicate ids, undeclared event/object types, undeclared or mistyped attributes,
+//! E2O/O2O relationships to missing objects and objects without any E2O relationship (which
+//! e.g. [`OCLanguageAbstraction::create_from_ocel`] requires to be removed).
+//!
+//! [`ValidationMode::Report`] only reports issues, [`ValidationMode::Lenient`] repairs every
+//! issue and reports it, [`ValidationMode::Strict`] rejects logs with any issue.
+//!
+//! [`OCLanguageAbstraction::create_from_ocel`]: crate::core::conformance::object_centric_language_abstraction_struct::OCLanguageAbstraction::create_from_ocel
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use process_mining::OCEL;
+use process_mining::ocel::ocel_struct::{
+    OCELAttributeType, OCELAttributeValue, OCELRelationship, OCELType, OCELTypeAttribute,
+};
+
+/// Maximum number of issues of each kind listed in [`ValidationReport::samples`]
+const MAX_SAMPLES_PER_KIND: usize = 20;
+
+/// What to do with a log that has issues
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationMode {
+    /// Keep the log unchanged and report its issues
+    #[default]
+    Report,
+    /// Reject the log
+    Strict,
+    /// Repair the log and report what was repaired
+    Lenient,
+}
+
+impl ValidationMode {
+    /// Parses a mode name (`report`, `strict`, `lenient`)
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "report" => Some(Self::Report),
+            "strict" => Some(Self::Strict),
+            "lenient" => Some(Self::Lenient),
+            _ => None,
+        }
+    }
+}
+
+/// Kind of an integrity issue, in the order they are checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// Repaired by keeping the first event with the id
+    DuplicateEventId,
+    /// Repaired by keeping the first object with the id
+    DuplicateObjectId,
+    /// Repaired by declaring the event type
+    UndeclaredEventType,
+    /// Repaired by declaring the object type
+    UndeclaredObjectType,
+    /// Repaired by declaring the attribute with the type of its first value
+    UndeclaredAttribute,
+    /// Repaired by converting the value to the declared type, or dropping it
+    AttributeTypeMismatch,
+    /// Repaired by dropping the relationship
+    MissingE2OObject,
+    /// Repaired by removing the object
+    OrphanObject,
+    /// Repaired by dropping the relationship
+    MissingO2OObject,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationIssue {
+    pub kind: IssueKind,
+    pub message: String,
+    /// How the issue was repaired (lenient mode only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repair: Option<String>,
+}
+
+/// Issues found in a log
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ValidationReport {
+    pub mode: ValidationMode,
+    /// Number of issues per kind
+    pub counts: BTreeMap<IssueKind, usize>,
+    /// The first issues of each kind
+    pub samples: Vec<ValidationIssue>,
+    /// Whether the issues were repaired
+    pub repaired: bool,
+}
+
+impl ValidationReport {
+    /// Whether no issues were found
+    pub fn is_valid(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Total number of issues
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    fn push(&mut self, kind: IssueKind, message: String, repair: impl Into<String>) {
+        let count = self.counts.entry(kind).or_insert(0);
+        *count += 1;
+        if *count <= MAX_SAMPLES_PER_KIND {
+            let repair = (self.mode == ValidationMode::Lenient).then(|| repair.into());
+            self.samples.push(ValidationIssue {
+                kind,
+                message,
+                repair,
+            });
+        }
+    }
+}
+
+/// Validates `ocel`.
+///
+/// In lenient mode, all issues are repaired and the repaired log is returned along with the
+/// report. In strict mode, the report is returned as error if there is any issue; otherwise the
+/// log is returned unchanged. In report mode, the log is always returned unchanged (issues are
+/// found by repairing a copy).
+pub fn validate_ocel(
+    mut ocel: OCEL,
+    mode: ValidationMode,
+) -> Result<(OCEL, ValidationReport), ValidationReport> {
+    let mut report = ValidationReport {
+        mode,
+        ..Default::default()
+    };
+    if mode == ValidationMode::Report {
+        repair_ocel(&mut ocel.clone(), &mut report);
+    } else {
+        repair_ocel(&mut ocel, &mut report);
+    }
+    report.repaired = mode == ValidationMode::Lenient && !report.is_valid();
+    if mode == ValidationMode::Strict && !report.is_valid() {
+        return Err(report);
+    }
+    Ok((ocel, report))
+}
+
+/// Finds and repairs all issues of `ocel`, recording them in `report`
+fn repair_ocel(ocel: &mut OCEL, report: &mut ValidationReport) {
+    // 1. Duplicate ids
+    let mut seen: HashSet<String> = HashSet::with_capacity(ocel.events.len());
+    ocel.events.retain(|e| {
+        let first = seen.insert(e.id.clone());
+        if !first {
+            report.push(
+                IssueKind::DuplicateEventId,
+                format!("Event id '{}' is used more than once", e.id),
+                "kept the first event",
+            );
+        }
+        first
+    });
+    let mut seen: HashSet<String> = HashSet::with_capacity(ocel.objects.len());
+    ocel.objects.retain(|o| {
+        let first = seen.insert(o.id.clone());
+        if !first {
+            report.push(
+                IssueKind::DuplicateObjectId,
+                format!("Object id '{}' is used more than once", o.id),
+                "kept the first object",
+            );
+        }
+        first
+    });
+
+    // 2. Types
+    let mut declared: HashSet<String> = ocel.event_types.iter().map(|t| t.name.clone()).collect();
+    for e in &ocel.events {
+        if !declared.contains(&e.event_type) {
+            report.push(
+                IssueKind::UndeclaredEventType,
+                format!("Event type '{}' is not declared", e.event_type),
+                "declared the event type",
+            );
+            declared.insert(e.event_type.clone());
+            ocel.event_types.push(OCELType {
+                name: e.event_type.clone(),
+                attributes: Vec::new(),
+            });
+        }
+    }
+    let mut declared: HashSet<String> = ocel.object_types.iter().map(|t| t.name.clone()).collect();
+    for o in &ocel.objects {
+        if !declared.contains(&o.object_type) {
+            report.push(
+                IssueKind::UndeclaredObjectType,
+                format!("Object type '{}' is not declared", o.object_type),
+                "declared the object type",
+            );
+            declared.insert(o.object_type.clone());
+            ocel.object_types.push(OCELType {
+                name: o.object_type.clone(),
+                attributes: Vec::new(),
+            });
+        }
+    }
+
+    // 3. Attributes
+    let mut event_schema = Schema::new(&mut ocel.event_types, "event");
+    for e in &mut ocel.events {
+        e.attributes.retain_mut(|a| {
+            event_schema.check(&e.event_type, &e.id, &a.name, &mut a.value, report)
+        });
+    }
+    let mut object_schema = Schema::new(&mut ocel.object_types, "object");
+    for o in &mut ocel.objects {
+        o.attributes.retain_mut(|a| {
+            object_schema.check(&o.object_type, &o.id, &a.name, &mut a.value, report)
+        });
+    }
+
+    // 4. E2O relationships to missing objects
+    let object_ids: HashSet<String> = ocel.objects.iter().map(|o| o.id.clone()).collect();
+    for e in &mut ocel.events {
+        e.relationships.retain(|r| {
+            let exists = object_ids.contains(&r.object_id);
+            if !exists {
+                report.push(
+                    IssueKind::MissingE2OObject,
+                    format!(
+                        "Event '{}' references missing object '{}'",
+                        e.id, r.object_id
+                    ),
+                    "dropped the relationship",
+                );
+            }
+            exists
+        });
+    }
+
+    // 5. Objects without E2O relationships
+    let related: HashSet<&str> = ocel
+        .events
+        .iter()
+        .flat_map(|e| e.relationships.iter().map(|r| r.object_id.as_str()))
+        .collect();
+    let orphans: HashSet<String> = ocel
+        .objects
+        .iter()
+        .filter(|o| !related.contains(o.id.as_str()))
+        .map(|o| o.id.clone())
+        .collect();
+    ocel.objects.retain(|o| {
+        let orphan = orphans.contains(&o.id);
+        if orphan {
+            report.push(
+                IssueKind::OrphanObject,
+                format!("Object '{}' is not related to any event", o.id),
+                "removed the object",
+            );
+        }
+        !orphan
+    });
+
+    // 6. O2O relationships to missing (or removed) objects
+    let object_ids: HashSet<String> = ocel.objects.iter().map(|o| o.id.clone()).collect();
+    for o in &mut ocel.objects {
+        let source = o.id.clone();
+        o.relationships.retain(|r: &OCELRelationship| {
+            let exists = object_ids.contains(&r.object_id);
+            if !exists {
+                let why = if orphans.contains(&r.object_id) {
+                    "removed orphan"
+                } else {
+                    "missing"
+                };
+                report.push(
+                    IssueKind::MissingO2OObject,
+                    format!(
+                        "Object '{source}' references {why} object '{}'",
+                        r.object_id
+                    ),
+                    "dropped the relationship",
+                );
+            }
+            exists
+        });
+    }
+}
+
+/// Declared attribute types per event/object type, extended while repairing
+struct Schema<'a> {
+    types: &'a mut Vec<OCELType>,
+    index: HashMap<String, usize>,
+    scope: &'static str,
+}
+
+impl<'a> Schema<'a> {
+    fn new(types: &'a mut Vec<OCELType>, scope: &'static str) -> Self {
+        let index = types
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (t.name.clone(), i))
+            .collect();
+        Self {
+            types,
+            index,
+            scope,
+        }
+    }
+
+    /// Checks one attribute value of `owner` (of type `type_name`), declaring or converting it
+    /// as needed. Returns whether the attribute is kept.
+    fn check(
+        &mut self,
+        type_name: &str,
+        owner: &str,
+        name: &str,
+        value: &mut OCELAttributeValue,
+        report: &mut ValidationReport,
+    ) -> bool {
+        let scope = self.scope;
+        let Some(&i) = self.index.get(type_name) else {
+            return true;
+        };
+        let declared = &mut self.types[i].attributes;
+        let Some(attr) = declared.iter().find(|a| a.name == name) else {
+            let observed = match OCELAttributeType::from(&*value) {
+                OCELAttributeType::Null => OCELAttributeType::String,
+                t => t,
+            };
+            report.push(
+                IssueKind::UndeclaredAttribute,
+                format!("Attribute '{name}' of {scope} type '{type_name}' is not declared"),
+                format!("declared it as {}", observed.to_type_string()),
+            );
+            declared.push(OCELTypeAttribute::new(name, &observed));
+            return true;
+        };
+        if is_compatible(value, &attr.value_type) {
+            return true;
+        }
+
+        let declared_type = attr.value_type.clone();
+        let observed = OCELAttributeType::from(&*value).to_type_string();
+        let message = format!(
+            "Attribute '{name}' of {scope} '{owner}' is {observed} ('{value}'), declared as {declared_type}"
+        );
+        match coerce(value, &declared_type) {
+            Some(converted) => {
+                *value = converted;
+                report.push(
+                    IssueKind::AttributeTypeMismatch,
+                    message,
+                    format!("converted to {declared_type}"),
+                );
+                true
+            }
+            None => {
+                report.push(
+                    IssueKind::AttributeTypeMismatch,
+                    message,
+                    "dropped the value",
+                );
+                false
+            }
+        }
+    }
+}
+
+/// Whether `value` may be stored under the declared type (integers are valid floats; types
+/// other than the OCEL 2.0 ones are not checked)
+fn is_compatible(value: &OCELAttributeValue, declared: &str) -> bool {
+    match (value, OCELAttributeType::from_type_str(declared)) {
+        (_, OCELAttributeType::Null) | (OCELAttributeValue::Null, _) => true,
+        (OCELAttributeValue::Integer(_), OCELAttributeType::Float) => true,
+        (v, t) => OCELAttributeType::from(v).to_type_string() == t.to_type_string(),
+    }
+}
+
+/// Converts `value` to the declared type, if it can be represented there
+fn coerce(value: &OCELAttributeValue, declared: &str) -> Option<OCELAttributeValue> {
+    let text = value.to_string();
+    match OCELAttributeType::from_type_str(declared) {
+        OCELAttributeType::String => Some(OCELAttributeValue::String(text)),
+        OCELAttributeType::Integer => match value {
+            OCELAttributeValue::Float(f) if f.fract() == 0.0 && f.abs() < i64::MAX as f64 => {
+                Some(OCELAttributeValue::Integer(*f as i64))
+            }
+            OCELAttributeValue::String(s) => s.trim().parse().ok().map(OCELAttributeValue::Integer),
+            _ => None,
+        },
+        OCELAttributeType::Float => match value {
+            OCELAttributeValue::String(s) => s.trim().parse().ok().map(OCELAttributeValue::Float),
+            _ => None,
+        },
+        OCELAttributeType::Boolean => match value {
+            OCELAttributeValue::String(s) => s
+                .trim()
+                .to_lowercase()
+                .parse()
+                .ok()
+                .map(OCELAttributeValue::Boolean),
+            OCELAttributeValue::Integer(0) => Some(OCELAttributeValue::Boolean(false)),
+            OCELAttributeValue::Integer(1) => Some(OCELAttributeValue::Boolean(true)),
+            _ => None,
+        },
+        OCELAttributeType::Time => match value {
+            OCELAttributeValue::String(s) => DateTime::parse_from_rfc3339(s.trim())
+                .ok()
+                .map(OCELAttributeValue::Time),
+            _ => None,
+        },
+        OCELAttributeType::Null => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use process_mining::ocel::ocel_struct::{OCELEvent, OCELEventAttribute, OCELObject};
+
+    fn broken_ocel() -> OCEL {
+        let t = DateTime::parse_from_rfc3339("2024-01-01T10:00:00+00:00").unwrap();
+        let event = |id: &str, objects: &[&str], attributes: Vec<OCELEventAttribute>| {
+            let rels = objects
+                .iter()
+                .map(|o| OCELRelationship::new(*o, ""))
+                .collect();
+            OCELEvent::new(id, "Load", t, attributes, rels)
+        };
+        let object = |id: &str, rels: Vec<OCELRelationship>| OCELObject {
+            id: id.to_string(),
+            object_type: "Container".to_string(),
+            attributes: Vec::new(),
+            relationships: rels,
+        };
+        OCEL {
+            event_types: vec![OCELType {
+                name: "Load".to_string(),
+                attributes: vec![OCELTypeAttribute::new("weight", &OCELAttributeType::Float)],
+            }],
+            object_types: Vec::new(),
+            events: vec![
+                event(
+                    "e1",
+                    &["c1", "ghost"],
+                    vec![OCELEventAttribute {
+                        name: "weight".to_string(),
+                        value: "12.5".into(),
+                    }],
+                ),
+                event("e1", &["c1"], Vec::new()),
+                event(
+                    "e2",
+                    &["c1"],
+                    vec![
+                        OCELEventAttribute {
+                            name: "weight".to_string(),
+                            value: "heavy".into(),
+                        },
+                        OCELEventAttribute {
+                            name: "crane".to_string(),
+                            value: 3i64.into(),
+                        },
+                    ],
+                ),
+            ],
+            objects: vec![
+                object(
+                    "c1",
+                    vec![
+                        OCELRelationship::new("c2", "next"),
+                        OCELRelationship::new("c3", "next"),
+                    ],
+                ),
+                object("c2", Vec::new()),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_validation_modes() {
+        let (ocel, report) = validate_ocel(broken_ocel(), ValidationMode::Report).unwrap();
+        assert_eq!(ocel, broken_ocel());
+        assert!(!report.repaired);
+        assert_eq!(report.counts.get(&IssueKind::OrphanObject), Some(&1));
+        assert!(report.samples.iter().all(|s| s.repair.is_none()));
+
+        let report = validate_ocel(broken_ocel(), ValidationMode::Strict).unwrap_err();
+        assert!(!report.repaired);
+        assert!(report.samples.iter().all(|s| s.repair.is_none()));
+
+        let (ocel, report) = validate_ocel(broken_ocel(), ValidationMode::Lenient).unwrap();
+        let count = |k: IssueKind| report.counts.get(&k).copied().unwrap_or(0);
+        assert!(report.repaired);
+        assert_eq!(count(IssueKind::DuplicateEventId), 1);
+        assert_eq!(count(IssueKind::UndeclaredObjectType), 1);
+        assert_eq!(count(IssueKind::UndeclaredAttribute), 1);
+        assert_eq!(count(IssueKind::AttributeTypeMismatch), 2);
+        assert_eq!(count(IssueKind::MissingE2OObject), 1);
+        assert_eq!(count(IssueKind::OrphanObject), 1);
+        assert_eq!(count(IssueKind::MissingO2OObject), 2);
+
+        assert_eq!(ocel.events.len(), 2);
+        assert_eq!(ocel.events[0].relationships.len(), 1);
+        assert_eq!(
+            ocel.events[0].attributes[0].value,
+            OCELAttributeValue::Float(12.5)
+        );
+        assert_eq!(ocel.events[1].attributes.len(), 1);
+        assert_eq!(ocel.objects.len(), 1);
+        assert!(ocel.objects[0].relationships.is_empty());
+        assert_eq!(ocel.event_types[0].attributes[1].value_type, "integer");
+
+        // A repaired log is valid
+        let (_, again) = validate_ocel(ocel, ValidationMode::Strict).unwrap();
+        assert!(again.is_valid());
+    }
+}
@@ -1,4 +1,9 @@
 //! Convert **[OcptFE]** to **[OCPT]** and viceversa.
+//!
+//! The conversion is lossless: node uuids, loop bounds, exhibits of unrelated object types and
+//! unknown fields of the frontend shape (kept in `extra`) survive both directions, so that
+//! FE→BE→FE returns the frontend tree in its normal form (see [`backend_to_frontend`]) and
+//! BE→FE→BE returns the backend tree.
 use anyhow::{anyhow, Result};
 use std::collections::{HashMap, HashSet};
 use crate::models::ocpt::{OcptFE, HierarchyNode, ActivityValue, ObjectTypeFE as FeObjectType, OCPT, OCPTLeaf, OCPTLeafLabel, OCPTNode, OCPTOperator, OCPTOperatorType};
@@ -7,7 +12,9 @@ use crate::models::ocpt::{OcptFE, HierarchyNode, ActivityValue, ObjectTypeFE as
 /// Converts a frontend OCPT [OcptFE] to a backend OCPT [OCPT].
 pub fn frontend_to_backend(front: OcptFE) -> Result<OCPT> {
     let root = frontend_node_to_backend(&front.hierarchy)?;
-    Ok(OCPT::new(root))
+    let mut ocpt = OCPT::new(root);
+    ocpt.extra = front.extra;
+    Ok(ocpt)
 }
 
 
@@ -17,6 +24,9 @@ pub fn frontend_to_backend(front: OcptFE) -> Result<OCPT> {
 /// sorts them alphabetically, and then converts the backend OCPT hierarchy to a frontend hierarchy.
 /// 
 /// The resulting [OcptFE] is then composed of the sorted object types and the converted [HierarchyNode]s.
+///
+/// The frontend tree is in normal form: operators are named `sequence`, `xor`, `parallel` and
+/// `loop`, object types are sorted by name and their exhibits ordered `div`, `con`, `def`.
 pub fn backend_to_frontend(ocpt: &OCPT) -> OcptFE {
     // Collect all object types appearing in any leaf (related OR marked)
     let mut all_ots: HashSet<String> = HashSet::new();
@@ -30,6 +40,7 @@ pub fn backend_to_frontend(ocpt: &OCPT) -> OcptFE {
     OcptFE {
         ots: ots_vec,
         hierarchy,
+        extra: ocpt.extra.clone(),
     }
 }
 
@@ -43,19 +54,30 @@ pub fn backend_to_frontend(ocpt: &OCPT) -> OcptFE {
 ///
 /// The function recursively converts all child nodes until all nodes have been converted.
 
-fn frontend_node_to_backend(node: &HierarchyNode) -> Result<OCPTNode> {
+pub(crate) fn frontend_node_to_backend(node: &HierarchyNode) -> Result<OCPTNode> {
     match node {
-        HierarchyNode::Operator { value, children } => {
-            let op_type = parse_operator(value)?;
+        HierarchyNode::Operator { value, uuid, loop_bound, children, extra } => {
+            let op_type = match parse_operator(value)? {
+                OCPTOperatorType::Loop(bound) => OCPTOperatorType::Loop(loop_bound.or(bound)),
+                op_type => op_type,
+            };
             let mut op = OCPTOperator::new(op_type);
+            if let Some(uuid) = uuid {
+                op.uuid = *uuid;
+            }
             op.children = children
                 .iter()
                 .map(frontend_node_to_backend)
                 .collect::<Result<Vec<_>>>()?;
+            op.extra = extra.clone();
             Ok(OCPTNode::Operator(op))
         }
-        HierarchyNode::Activity { value } => {
-            let leaf = frontend_activity_to_leaf(value);
+        HierarchyNode::Activity { value, uuid, extra } => {
+            let mut leaf = frontend_activity_to_leaf(value);
+            if let Some(uuid) = uuid {
+                leaf.uuid = *uuid;
+            }
+            leaf.extra = extra.clone();
             Ok(OCPTNode::Leaf(leaf))
         }
     }
@@ -69,9 +91,10 @@ fn frontend_node_to_backend(node: &HierarchyNode) -> Result<OCPTNode> {
 /// - "exclusivechoice" or "xor" or "choice" -> OCPTOperatorType::ExclusiveChoice
 /// - "concurrency" or "parallel" or "and" or "par" -> OCPTOperatorType::Concurrency
 /// - "loop" -> OCPTOperatorType::Loop(None)
+/// - "loop:n" -> OCPTOperatorType::Loop(Some(n))
 
 
-fn parse_operator(s: &str) -> Result<OCPTOperatorType> {
+pub(crate) fn parse_operator(s: &str) -> Result<OCPTOperatorType> {
     let k = s.trim().to_lowercase();
     Ok(match k.as_str() {
         "sequence" | "seq" => OCPTOperatorType::Sequence,
@@ -79,9 +102,8 @@ fn parse_operator(s: &str) -> Result<OCPTOperatorType> {
         "concurrency" | "parallel" | "and" | "par" => OCPTOperatorType::Concurrency,
         "loop" => OCPTOperatorType::Loop(None),
         v if v.starts_with("loop:") => {
-            // Optional: parse count after "loop:" if you want to support it
-            // let n = v[5..].parse::<u32>().ok();
-            OCPTOperatorType::Loop(None)
+            let n = v[5..].parse::<u32>().map_err(|_| anyhow!("Invalid loop bound: {v}"))?;
+            OCPTOperatorType::Loop(Some(n))
         }
         other => return Err(anyhow!("Unknown operator: {other}")),
     })
@@ -90,7 +112,8 @@ fn parse_operator(s: &str) -> Result<OCPTOperatorType> {
 /// Converts a frontend [ActivityValue] value to a backend [OCPTLeaf] node.
 ///
 /// - If is_tau is true, then the leaf is created with no activity
-/// - Otherwise, the leaf is created with the activity and all object types as related
+/// - Otherwise, the leaf is created with the activity
+/// - All object types are related, unless marked with `related: false`
 ///
 /// - If an object type has "exhibits" information, then the leaf is updated accordingly
 /// - "div" tags mark an object type as divergent
@@ -104,7 +127,7 @@ fn parse_operator(s: &str) -> Result<OCPTOperatorType> {
 /// # Returns
 ///
 /// The converted backend [OCPTLeaf] node
-fn frontend_activity_to_leaf(v: &ActivityValue) -> OCPTLeaf {
+pub(crate) fn frontend_activity_to_leaf(v: &ActivityValue) -> OCPTLeaf {
     let is_tau = v.isSilent.unwrap_or(false);
     let mut leaf = if is_tau {
         OCPTLeaf::new(None)
@@ -116,7 +139,9 @@ fn frontend_activity_to_leaf(v: &ActivityValue) -> OCPTLeaf {
     for ot in &v.ots {
         let name = ot.ot.clone();
         // Mark as related by default if it appears
-        leaf.related_ob_types.insert(name.clone());
+        if ot.related != Some(false) {
+            leaf.related_ob_types.insert(name.clone());
+        }
 
         if let Some(tags) = &ot.exhibits {
             for t in tags {
@@ -157,11 +182,17 @@ fn backend_node_to_frontend(node: &OCPTNode) -> HierarchyNode {
     match node {
         OCPTNode::Operator(op) => HierarchyNode::Operator {
             value: stringify_operator(&op.operator_type),
+            uuid: Some(op.uuid),
+            loop_bound: match op.operator_type {
+                OCPTOperatorType::Loop(bound) => bound,
+                _ => None,
+            },
             children: op.children.iter().map(backend_node_to_frontend).collect(),
+            extra: op.extra.clone(),
         },
         OCPTNode::Leaf(leaf) => {
             let value = backend_leaf_to_activity_value(leaf);
-            HierarchyNode::Activity { value }
+            HierarchyNode::Activity { value, uuid: Some(leaf.uuid), extra: leaf.extra.clone() }
         }
     }
 }
@@ -170,78 +201,76 @@ fn backend_node_to_frontend(node: &OCPTNode) -> HierarchyNode {
 ///
 /// The conversion is as follows:
 /// - `Sequence` -> `"sequence"`
-/// - `ExclusiveChoice` -> `"exclusiveChoice"`
+/// - `ExclusiveChoice` -> `"xor"`
 /// - `Concurrency` -> `"parallel"`
-/// - `Loop(_cnt)` -> `"loop"` (ignoring the count parameter in the frontend)
+/// - `Loop(_cnt)` -> `"loop"` (the count is given as `loopBound` of the node)
 fn stringify_operator(op: &OCPTOperatorType) -> String {
     match op {
         OCPTOperatorType::Sequence => "sequence".to_string(),
-        OCPTOperatorType::ExclusiveChoice => "exclusiveChoice".to_string(),
+        OCPTOperatorType::ExclusiveChoice => "xor".to_string(),
         OCPTOperatorType::Concurrency => "parallel".to_string(),
-        OCPTOperatorType::Loop(_cnt) => "loop".to_string(), // ignore parameter in FE
+        OCPTOperatorType::Loop(_cnt) => "loop".to_string(),
     }
 }
 
 /// Converts a backend [OCPTLeaf] to a frontend [ActivityValue].
 ///
-/// - If the leaf is a tau, it is converted to an activity value with isSilent set to true and no object types
-/// - If the leaf is an activity, its value is converted to an activity value with isSilent set to false and object types built from the leaf's related, divergent, convergent, and deficient object types
+/// - If the leaf is a tau, it is converted to an activity value with isSilent set to true and an empty activity
+/// - If the leaf is an activity, its value is converted to an activity value with isSilent set to false
+/// - Object types are built from the leaf's related, divergent, convergent, and deficient object types;
+///   object types with exhibits that are not related are marked with `related: false`
 ///
 /// The resulting activity value has its object types sorted alphabetically by object type name.
 
 fn backend_leaf_to_activity_value(leaf: &OCPTLeaf) -> ActivityValue {
-    match &leaf.activity_label {
-        OCPTLeafLabel::Tau => ActivityValue {
-            isSilent: Some(true),
-            activity: "".to_string(),
-            ots: vec![], // silent node has no OT exhibits in FE
-        },
-        OCPTLeafLabel::Activity(act) => {
-            // Build FE OT entries, merging marks per object type.
-            // Index ot -> (related, divergent, convergent, deficient)
-            let mut marks: HashMap<&str, (bool, bool, bool, bool)> = HashMap::new();
-            for ot in &leaf.related_ob_types {
-                marks.entry(ot.as_str()).or_insert((true, false, false, false)).0 = true;
+    let (is_silent, activity) = match &leaf.activity_label {
+        OCPTLeafLabel::Tau => (true, String::new()),
+        OCPTLeafLabel::Activity(act) => (false, act.clone()),
+    };
+    // Build FE OT entries, merging marks per object type.
+    // Index ot -> (related, divergent, convergent, deficient)
+    let mut marks: HashMap<&str, (bool, bool, bool, bool)> = HashMap::new();
+    for ot in &leaf.related_ob_types {
+        marks.entry(ot.as_str()).or_insert((true, false, false, false)).0 = true;
+    }
+    for ot in &leaf.divergent_ob_types {
+        marks.entry(ot.as_str()).or_insert((false, false, false, false)).1 = true;
+    }
+    for ot in &leaf.convergent_ob_types {
+        marks.entry(ot.as_str()).or_insert((false, false, false, false)).2 = true;
+    }
+    for ot in &leaf.deficient_ob_types {
+        marks.entry(ot.as_str()).or_insert((false, false, false, false)).3 = true;
+    }
+
+    let mut ots: Vec<FeObjectType> = marks
+        .into_iter()
+        .map(|(ot, (related, divergent, convergent, deficient))| {
+            let mut exhibits: Vec<String> = Vec::new();
+            if divergent {
+                exhibits.push("div".into());
             }
-            for ot in &leaf.divergent_ob_types {
-                marks.entry(ot.as_str()).or_insert((false, false, false, false)).1 = true;
+            if convergent {
+                exhibits.push("con".into());
             }
-            for ot in &leaf.convergent_ob_types {
-                marks.entry(ot.as_str()).or_insert((false, false, false, false)).2 = true;
+            if deficient {
+                exhibits.push("def".into());
             }
-            for ot in &leaf.deficient_ob_types {
-                marks.entry(ot.as_str()).or_insert((false, false, false, false)).3 = true;
+            // If it was "related" only, exhibits can be omitted.
+            FeObjectType {
+                ot: ot.to_string(),
+                exhibits: if exhibits.is_empty() { None } else { Some(exhibits) },
+                related: (!related).then_some(false),
             }
+        })
+        .collect();
 
-            let mut ots: Vec<FeObjectType> = marks
-                .into_iter()
-                .map(|(ot, (_related, divergent, convergent, deficient))| {
-                    let mut exhibits: Vec<String> = Vec::new();
-                    if divergent {
-                        exhibits.push("div".into());
-                    }
-                    if convergent {
-                        exhibits.push("con".into());
-                    }
-                    if deficient {
-                        exhibits.push("def".into());
-                    }
-                    // If it was "related" only, exhibits can be omitted.
-                    FeObjectType {
-                        ot: ot.to_string(),
-                        exhibits: if exhibits.is_empty() { None } else { Some(exhibits) },
-                    }
-                })
-                .collect();
-
-            ots.sort_by(|a, b| a.ot.cmp(&b.ot));
+    ots.sort_by(|a, b| a.ot.cmp(&b.ot));
 
-            ActivityValue {
-                isSilent: Some(false),
-                activity: act.clone(),
-                ots,
-            }
-        }
+    ActivityValue {
+        isSilent: Some(is_silent),
+        activity,
+        ots,
     }
 }
 
@@ -273,73 +302,190 @@ fn collect_all_ots_from_node(node: &OCPTNode, acc: &mut HashSet<String>) {
 
 #[cfg(test)]
 mod tests {
-    #[tokio::test]
-    async fn test_convert_and_store_ocpt_123_roundtrip() {
-        use tokio::fs;
+    #[test]
+    fn test_convert_fixture_ocpt_roundtrip() {
         use crate::core::struct_converters::ocpt_frontend_backend::{frontend_to_backend, backend_to_frontend};
-        use crate::models::ocpt::{OcptFE,OCPT};
-
-        // Hard-coded file path in ./temp
-        let path = "./temp/ocpt_123.json";
-
-        // Read file content
-        let content = fs::read_to_string(path)
-            .await
-            .expect("❌ failed to read ./temp/ocpt_123.json");
-
-        // Try to parse as frontend struct first
-        if let Ok(fe_struct) = serde_json::from_str::<OcptFE>(&content) {
-            println!("📥 Parsed as frontend OCPT, converting to backend...");
-
-            // Convert frontend → backend
-            let ocpt_backend = frontend_to_backend(fe_struct)
-                .expect("❌ frontend→backend conversion failed");
-            assert!(ocpt_backend.is_valid(), "frontend OCPT should yield valid backend OCPT");
-
-            // Store converted backend
-            let out_backend = "./temp/ocpt_123_backend.json";
-            let pretty_backend = serde_json::to_string_pretty(&ocpt_backend).unwrap();
-            fs::write(out_backend, pretty_backend)
-                .await
-                .expect("❌ failed to write backend OCPT");
-            println!("✅ Stored converted backend at {out_backend}");
-
-            // Convert backend → frontend
-            let ocpt_frontend = backend_to_frontend(&ocpt_backend);
-
-            // Store converted frontend
-            let out_frontend = "./temp/ocpt_123_frontend.json";
-            let pretty_frontend = serde_json::to_string_pretty(&ocpt_frontend).unwrap();
-            fs::write(out_frontend, pretty_frontend)
-                .await
-                .expect("❌ failed to write frontend OCPT");
-            println!("✅ Stored roundtrip frontend at {out_frontend}");
+        use crate::core::ocpt::reduce::canonical_string;
+        use crate::models::ocpt::{OcptFE, OCPT};
+
+        let content = crate::tests::read_fixture("orders_ocpt_frontend.json");
+        let fe_struct: OcptFE = serde_json::from_str(&content).expect("fixture is a frontend OCPT");
+        assert!(serde_json::from_str::<OCPT>(&content).is_err(), "fixture is not a backend OCPT");
+
+        // Convert frontend → backend
+        let ocpt_backend = frontend_to_backend(fe_struct).expect("frontend→backend conversion failed");
+        assert!(ocpt_backend.is_valid(), "frontend OCPT should yield valid backend OCPT");
+        assert_eq!(ocpt_backend.find_all_node_uuids().len(), 8);
+
+        // The stored backend JSON parses back to the same tree
+        let pretty_backend = serde_json::to_string_pretty(&ocpt_backend).unwrap();
+        let stored: OCPT = serde_json::from_str(&pretty_backend).unwrap();
+        assert_eq!(canonical_string(&stored.root), canonical_string(&ocpt_backend.root));
+
+        // Convert backend → frontend → backend
+        let ocpt_frontend = backend_to_frontend(&ocpt_backend);
+        assert_eq!(ocpt_frontend.ots, vec!["item".to_string(), "order".to_string()]);
+        let again = frontend_to_backend(ocpt_frontend.clone()).unwrap();
+        assert_eq!(again.find_all_node_uuids(), ocpt_backend.find_all_node_uuids());
+        assert_eq!(backend_to_frontend(&again), ocpt_frontend);
+    }
+
+    mod round_trip {
+        use crate::core::struct_converters::ocpt_frontend_backend::{backend_to_frontend, frontend_to_backend};
+        use crate::models::ocpt::{ActivityValue, HierarchyNode, ObjectTypeFE, OcptFE};
+        use proptest::prelude::*;
+        use std::collections::BTreeSet;
+        use uuid::Uuid;
+
+        const OBJECT_TYPES: [&str; 3] = ["item", "order", "package"];
+
+        fn extra() -> impl Strategy<Value = serde_json::Map<String, serde_json::Value>> {
+            prop::option::of((0..100u32, any::<bool>())).prop_map(|layout| {
+                let mut extra = serde_json::Map::new();
+                if let Some((id, expanded)) = layout {
+                    extra.insert("id".to_string(), id.into());
+                    extra.insert("isExpanded".to_string(), expanded.into());
+                }
+                extra
+            })
+        }
+
+        fn uuid() -> impl Strategy<Value = Uuid> {
+            any::<u128>().prop_map(Uuid::from_u128)
+        }
+
+        /// Object types of a leaf in normal form: sorted, exhibits in `div`, `con`, `def` order,
+        /// unrelated object types only with exhibits
+        fn object_types() -> impl Strategy<Value = Vec<ObjectTypeFE>> {
+            prop::collection::vec((any::<bool>(), [any::<bool>(); 3], any::<bool>()), OBJECT_TYPES.len()).prop_map(
+                |marks| {
+                    OBJECT_TYPES
+                        .iter()
+                        .zip(marks)
+                        .filter(|(_, (present, _, _))| *present)
+                        .map(|(ot, (_, tags, related))| {
+                            let exhibits: Vec<String> = ["div", "con", "def"]
+                                .iter()
+                                .zip(tags)
+                                .filter(|(_, set)| *set)
+                                .map(|(tag, _)| tag.to_string())
+                                .collect();
+                            let related = exhibits.is_empty() || related;
+                            ObjectTypeFE {
+                                ot: ot.to_string(),
+                                exhibits: (!exhibits.is_empty()).then_some(exhibits),
+                                related: (!related).then_some(false),
+                            }
+                        })
+                        .collect()
+                },
+            )
+        }
+
+        fn leaf() -> impl Strategy<Value = HierarchyNode> {
+            (prop::option::of("[a-z ]{1,8}"), object_types(), uuid(), extra()).prop_map(|(activity, ots, uuid, extra)| {
+                HierarchyNode::Activity {
+                    value: ActivityValue {
+                        isSilent: Some(activity.is_none()),
+                        activity: activity.unwrap_or_default(),
+                        ots,
+                    },
+                    uuid: Some(uuid),
+                    extra,
+                }
+            })
+        }
+
+        fn hierarchy() -> impl Strategy<Value = HierarchyNode> {
+            leaf().prop_recursive(4, 32, 4, |inner| {
+                let operator = prop::sample::select(vec!["sequence", "xor", "parallel", "loop"]);
+                (operator, prop::option::of(1..10u32), prop::collection::vec(inner, 1..4), uuid(), extra())
+                    .prop_map(|(value, bound, children, uuid, extra)| HierarchyNode::Operator {
+                        loop_bound: bound.filter(|_| value == "loop"),
+                        value: value.to_string(),
+                        uuid: Some(uuid),
+                        children,
+                        extra,
+                    })
+            })
+        }
+
+        fn collect_ots(node: &HierarchyNode, acc: &mut BTreeSet<String>) {
+            match node {
+                HierarchyNode::Operator { children, .. } => children.iter().for_each(|c| collect_ots(c, acc)),
+                HierarchyNode::Activity { value, .. } => acc.extend(value.ots.iter().map(|ot| ot.ot.clone())),
+            }
         }
-        // Otherwise try backend struct directly
-        else if let Ok(be_struct) = serde_json::from_str::<OCPT>(&content) {
-            println!("📥 Parsed as backend OCPT directly, no conversion needed");
-            assert!(be_struct.is_valid(), "backend OCPT should already be valid");
-
-            // Store normalized backend copy
-            let out_backend = "./temp/ocpt_123_backend.json";
-            let pretty_backend = serde_json::to_string_pretty(&be_struct).unwrap();
-            fs::write(out_backend, pretty_backend)
-                .await
-                .expect("❌ failed to write backend OCPT");
-            println!("✅ Stored backend copy at {out_backend}");
-
-            // Convert backend → frontend
-            let ocpt_frontend = backend_to_frontend(&be_struct);
-
-            // Store converted frontend
-            let out_frontend = "./temp/ocpt_123_frontend.json";
-            let pretty_frontend = serde_json::to_string_pretty(&ocpt_frontend).unwrap();
-            fs::write(out_frontend, pretty_frontend)
-                .await
-                .expect("❌ failed to write frontend OCPT");
-            println!("✅ Stored converted frontend at {out_frontend}");
-        } else {
-            panic!("❌ ocpt_123.json is neither valid frontend nor backend OCPT JSON");
+
+        fn ocpt_fe() -> impl Strategy<Value = OcptFE> {
+            (hierarchy(), prop::option::of("[a-z]{1,8}")).prop_map(|(hierarchy, name)| {
+                let mut ots = BTreeSet::new();
+                collect_ots(&hierarchy, &mut ots);
+                let mut extra = serde_json::Map::new();
+                if let Some(name) = name {
+                    extra.insert("name".to_string(), name.into());
+                }
+                OcptFE { ots: ots.into_iter().collect(), hierarchy, extra }
+            })
+        }
+
+        fn round_trip(fe: &OcptFE) -> OcptFE {
+            let json = serde_json::to_string(fe).unwrap();
+            let parsed: OcptFE = serde_json::from_str(&json).unwrap();
+            backend_to_frontend(&frontend_to_backend(parsed).unwrap())
+        }
+
+        proptest! {
+            #[test]
+            fn test_fe_be_fe_is_identity(fe in ocpt_fe()) {
+                prop_assert_eq!(round_trip(&fe), fe);
+            }
+
+            #[test]
+            fn test_be_fe_be_keeps_backend_tree(fe in ocpt_fe()) {
+                let be = frontend_to_backend(fe).unwrap();
+                let again = frontend_to_backend(backend_to_frontend(&be)).unwrap();
+                prop_assert_eq!(backend_to_frontend(&again), backend_to_frontend(&be));
+                prop_assert_eq!(again.find_all_node_uuids(), be.find_all_node_uuids());
+            }
+        }
+
+        #[test]
+        fn test_round_trip_normalizes_loose_input() {
+            let loose: OcptFE = serde_json::from_value(serde_json::json!({
+                "ots": ["order", "item"],
+                "hierarchy": {"value": "exclusiveChoice", "id": 1, "children": [
+                    {"value": "loop:3", "children": [
+                        {"value": {"activity": "pick", "ots": [{"ot": "order", "exhibits": ["def", "div"]}, {"ot": "item"}]}},
+                        {"value": {"activity": "", "isSilent": true, "ots": []}}
+                    ]},
+                    {"value": {"activity": "", "isSilent": true, "ots": []}, "isExpanded": false}
+                ]}
+            }))
+            .unwrap();
+            let normal = round_trip(&loose);
+            assert_eq!(round_trip(&normal), normal);
+
+            let HierarchyNode::Operator { value, uuid, extra, children, .. } = &normal.hierarchy else {
+                panic!("expected an operator");
+            };
+            assert_eq!(value, "xor");
+            assert!(uuid.is_some());
+            assert_eq!(extra["id"], 1);
+            let HierarchyNode::Operator { value, loop_bound, children: loop_children, .. } = &children[0] else {
+                panic!("expected a loop");
+            };
+            assert_eq!((value.as_str(), *loop_bound), ("loop", Some(3)));
+            let HierarchyNode::Activity { value: pick, .. } = &loop_children[0] else {
+                panic!("expected an activity");
+            };
+            assert_eq!(pick.ots.iter().map(|ot| ot.ot.as_str()).collect::<Vec<_>>(), ["item", "order"]);
+            assert_eq!(pick.ots[1].exhibits, Some(vec!["div".to_string(), "def".to_string()]));
+            let HierarchyNode::Activity { value: tau, extra, .. } = &children[1] else {
+                panic!("expected an activity");
+            };
+            assert_eq!((tau.isSilent, tau.activity.as_str()), (Some(true), ""));
+            assert_eq!(extra["isExpanded"], false);
         }
     }
 }
\ No newline at end of file
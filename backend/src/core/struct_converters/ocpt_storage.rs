@@ -0,0 +1,46 @@
+//! Stored OCPTs (`./temp/ocpt_{id}.json`), in either the backend or the frontend shape.
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::core::struct_converters::ocel_storage::STORAGE_DIR;
+use crate::core::struct_converters::ocpt_frontend_backend::frontend_to_backend;
+use crate::models::ocpt::{OcptFE, OCPT};
+
+/// Path of the OCPT stored under `id`
+pub fn ocpt_path(id: &str) -> PathBuf {
+    Path::new(STORAGE_DIR).join(format!("ocpt_{id}.json"))
+}
+
+/// Parses an OCPT given as backend or frontend JSON into the **backend** OCPT
+pub fn parse_ocpt(content: &str) -> Result<OCPT> {
+    // Try backend first
+    if let Ok(be) = serde_json::from_str::<OCPT>(content) {
+        return Ok(be);
+    }
+
+    // Try frontend -> convert to backend
+    let fe = serde_json::from_str::<OcptFE>(content)
+        .context("parse OCPT (backend or frontend) failed")?;
+    frontend_to_backend(fe).context("frontend→backend OCPT conversion failed")
+}
+
+/// Loads the OCPT stored under `id` as **backend** OCPT, or `None` if there is none
+pub fn load_ocpt(id: &str) -> Result<Option<OCPT>> {
+    let path = ocpt_path(id);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("read {}", path.to_string_lossy()))?;
+    parse_ocpt(&content)
+        .with_context(|| format!("at {}", path.to_string_lossy()))
+        .map(Some)
+}
+
+/// Stores `ocpt` under `id` as **backend** OCPT
+pub fn save_ocpt(id: &str, ocpt: &OCPT) -> Result<()> {
+    let path = ocpt_path(id);
+    std::fs::create_dir_all(STORAGE_DIR).context("create storage dir")?;
+    std::fs::write(&path, serde_json::to_string_pretty(ocpt)?)
+        .with_context(|| format!("write {}", path.to_string_lossy()))
+}
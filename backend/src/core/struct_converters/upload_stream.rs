@@ -0,0 +1,292 @@
+//! Disk-backed, streaming import of uploaded event logs.
+//!
+//! Uploads are written to a temporary file chunk by chunk, transparently decompressed
+//! (gzip/zstd) and parsed from a buffered reader, so neither the raw upload nor an intermediate
+//! `String`/`serde_json::Value` of it is ever held in memory. The decompressed content is
+//! limited as well (see [`decompressed_limit_bytes`]), so compression bombs are rejected.
+use anyhow::{anyhow, Context, Result};
+use flate2::read::MultiGzDecoder;
+use std::cell::Cell;
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::core::struct_converters::flat_log_converter::{
+    import_flat_log, FlatLogFormat, FlatLogMapping,
+};
+use crate::core::struct_converters::ocel_1_ocel_2_converter::{ConversionOptions, ConversionReport};
+use crate::core::struct_converters::ocel_formats::{import_ocel_from_reader, OcelFormat};
+use process_mining::{import_ocel_sqlite_from_path, OCEL};
+
+/// Environment variable overriding the maximum upload size (in MB)
+pub const UPLOAD_LIMIT_ENV: &str = "OCEL_UPLOAD_LIMIT_MB";
+
+/// Maximum upload size (in MB) if [`UPLOAD_LIMIT_ENV`] is not set
+pub const DEFAULT_UPLOAD_LIMIT_MB: usize = 1024;
+
+/// Environment variable overriding the maximum decompressed size of an upload (in MB)
+pub const DECOMPRESSED_LIMIT_ENV: &str = "OCEL_DECOMPRESSED_LIMIT_MB";
+
+/// Maximum decompressed size (in MB) if [`DECOMPRESSED_LIMIT_ENV`] is not set
+pub const DEFAULT_DECOMPRESSED_LIMIT_MB: usize = 4096;
+
+/// Number of (decompressed) bytes read ahead to detect the format of an upload
+const HEAD_LEN: usize = 64 * 1024;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// The maximum size of an uploaded file in bytes, read from [`UPLOAD_LIMIT_ENV`]
+pub fn upload_limit_bytes() -> usize {
+    std::env::var(UPLOAD_LIMIT_ENV)
+        .ok()
+        .and_then(|mb| mb.trim().parse::<usize>().ok())
+        .unwrap_or(DEFAULT_UPLOAD_LIMIT_MB)
+        .saturating_mul(1024 * 1024)
+}
+
+/// The maximum number of bytes an upload may decompress to, read from [`DECOMPRESSED_LIMIT_ENV`]
+pub fn decompressed_limit_bytes() -> usize {
+    std::env::var(DECOMPRESSED_LIMIT_ENV)
+        .ok()
+        .and_then(|mb| mb.trim().parse::<usize>().ok())
+        .unwrap_or(DEFAULT_DECOMPRESSED_LIMIT_MB)
+        .saturating_mul(1024 * 1024)
+}
+
+/// The content of an upload decompresses to more than the allowed number of bytes
+#[derive(Debug, Clone, Copy)]
+pub struct DecompressedLimitExceeded {
+    pub limit: usize,
+}
+
+impl std::fmt::Display for DecompressedLimitExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "File exceeds the decompressed size limit of {} MB", self.limit / (1024 * 1024))
+    }
+}
+
+impl std::error::Error for DecompressedLimitExceeded {}
+
+/// Fails with [`DecompressedLimitExceeded`] once more than `remaining` bytes were read and
+/// records that in `exceeded`, as parsers may not pass the I/O error on unchanged.
+struct LimitedReader<R> {
+    inner: R,
+    limit: usize,
+    remaining: usize,
+    exceeded: Rc<Cell<bool>>,
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        // One byte more than allowed tells "exactly at the limit" from "beyond it"
+        let len = buf.len().min(self.remaining.saturating_add(1));
+        let n = self.inner.read(&mut buf[..len])?;
+        if n > self.remaining {
+            self.exceeded.set(true);
+            return Err(std::io::Error::other(DecompressedLimitExceeded { limit: self.limit }));
+        }
+        self.remaining -= n;
+        Ok(n)
+    }
+}
+
+/// An opened upload, see [`open_decompressed`]
+struct DecompressedFile {
+    compression: Compression,
+    /// The first decompressed bytes, for format detection
+    head: Vec<u8>,
+    /// The whole decompressed content (including `head`)
+    reader: BufReader<Box<dyn Read>>,
+    /// Set once the content exceeded the decompressed limit
+    exceeded: Rc<Cell<bool>>,
+    limit: usize,
+}
+
+/// Replaces `e` by [`DecompressedLimitExceeded`] if the limit caused it
+fn limit_error(exceeded: &Cell<bool>, limit: usize, e: anyhow::Error) -> anyhow::Error {
+    if exceeded.get() || e.chain().any(|c| c.is::<DecompressedLimitExceeded>()) {
+        anyhow::Error::new(DecompressedLimitExceeded { limit })
+    } else {
+        e
+    }
+}
+
+/// Compression of an uploaded file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// Detects the compression from the magic bytes at the start of the file
+    pub fn sniff(bytes: &[u8]) -> Self {
+        if bytes.starts_with(GZIP_MAGIC) {
+            Self::Gzip
+        } else if bytes.starts_with(ZSTD_MAGIC) {
+            Self::Zstd
+        } else {
+            Self::None
+        }
+    }
+}
+
+/// Strips a `.gz`/`.zst` suffix so the remaining extension names the log format
+/// (e.g. `log.jsonocel.gz` -> `log.jsonocel`)
+pub fn strip_compression_suffix(name: &str) -> &str {
+    let lower = name.to_lowercase();
+    for suffix in [".gz", ".gzip", ".zst", ".zstd"] {
+        if lower.ends_with(suffix) {
+            return &name[..name.len() - suffix.len()];
+        }
+    }
+    name
+}
+
+/// A temporary upload file in `./temp`, removed when dropped
+pub struct TempUpload {
+    path: PathBuf,
+}
+
+impl TempUpload {
+    pub fn in_temp_dir() -> Self {
+        Self { path: PathBuf::from(format!("./temp/upload_{}.part", uuid::Uuid::new_v4())) }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempUpload {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Opens a file with transparent gzip/zstd decompression of at most `limit` bytes.
+fn open_decompressed(path: &Path, limit: usize) -> Result<DecompressedFile> {
+    let mut file = BufReader::new(File::open(path).context("open uploaded file")?);
+    let mut magic = [0u8; 4];
+    let n = file.read(&mut magic)?;
+    let raw = Cursor::new(magic[..n].to_vec()).chain(file);
+
+    let compression = Compression::sniff(&magic[..n]);
+    let decoded: Box<dyn Read> = match compression {
+        Compression::None => Box::new(raw),
+        Compression::Gzip => Box::new(MultiGzDecoder::new(raw)),
+        Compression::Zstd => Box::new(zstd::Decoder::new(raw).context("init zstd decoder")?),
+    };
+    let exceeded = Rc::new(Cell::new(false));
+    let mut content = LimitedReader { inner: decoded, limit, remaining: limit, exceeded: exceeded.clone() };
+
+    let mut head: Vec<u8> = Vec::with_capacity(HEAD_LEN);
+    (&mut content)
+        .take(HEAD_LEN as u64)
+        .read_to_end(&mut head)
+        .context("decompress uploaded file")
+        .map_err(|e| limit_error(&exceeded, limit, e))?;
+    let reader: Box<dyn Read> = Box::new(Cursor::new(head.clone()).chain(content));
+    Ok(DecompressedFile { compression, head, reader: BufReader::new(reader), exceeded, limit })
+}
+
+/// Imports an OCEL (any [`OcelFormat`], optionally gzip/zstd-compressed) from a file.
+///
+/// If `format` is `None`, it is detected from the decompressed content.
+/// Uncompressed SQLite databases are opened in place instead of being read into memory.
+///
+/// # Errors
+/// - The file cannot be read/decompressed, the format is unknown, or the import fails.
+/// - [`DecompressedLimitExceeded`] if the content exceeds [`decompressed_limit_bytes`].
+pub fn import_ocel_file(
+    path: &Path,
+    format: Option<OcelFormat>,
+    options: &ConversionOptions,
+) -> Result<(OCEL, Option<ConversionReport>)> {
+    import_ocel_file_within(path, format, options, decompressed_limit_bytes())
+}
+
+fn import_ocel_file_within(
+    path: &Path,
+    format: Option<OcelFormat>,
+    options: &ConversionOptions,
+    limit: usize,
+) -> Result<(OCEL, Option<ConversionReport>)> {
+    let DecompressedFile { compression, head, reader, exceeded, limit } = open_decompressed(path, limit)?;
+    let format = format
+        .or_else(|| OcelFormat::sniff(&head))
+        .ok_or_else(|| anyhow!("Unknown OCEL file format"))?;
+
+    if format == OcelFormat::Sqlite && compression == Compression::None {
+        let ocel = import_ocel_sqlite_from_path(path).context("Invalid OCEL 2.0 SQLite database")?;
+        return Ok((ocel, None));
+    }
+    import_ocel_from_reader(&head, reader, format, options)
+        .map_err(|e| limit_error(&exceeded, limit, e))
+}
+
+/// Imports a flat XES/CSV log (optionally gzip/zstd-compressed) from a file.
+///
+/// # Errors
+/// - The file cannot be read/decompressed, or the import fails.
+/// - [`DecompressedLimitExceeded`] if the content exceeds [`decompressed_limit_bytes`].
+pub fn import_flat_log_file(
+    path: &Path,
+    format: FlatLogFormat,
+    mapping: &FlatLogMapping,
+) -> Result<OCEL> {
+    let DecompressedFile { reader, exceeded, limit, .. } = open_decompressed(path, decompressed_limit_bytes())?;
+    import_flat_log(reader, format, mapping).map_err(|e| limit_error(&exceeded, limit, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_import_compressed_ocel_file() {
+        let json = br#"{"objectTypes":[],"eventTypes":[],"objects":[],"events":[]}"#;
+        std::fs::create_dir_all("./temp").unwrap();
+
+        let gz = TempUpload::in_temp_dir();
+        let mut enc = flate2::write::GzEncoder::new(File::create(gz.path()).unwrap(), Default::default());
+        enc.write_all(json).unwrap();
+        enc.finish().unwrap();
+
+        let zst = TempUpload::in_temp_dir();
+        std::fs::write(zst.path(), zstd::encode_all(&json[..], 0).unwrap()).unwrap();
+
+        for upload in [&gz, &zst] {
+            let (ocel, report) = import_ocel_file(upload.path(), None, &ConversionOptions::default()).unwrap();
+            assert!(ocel.events.is_empty());
+            assert!(report.is_none());
+        }
+
+        assert_eq!(strip_compression_suffix("log.jsonocel.GZ"), "log.jsonocel");
+    }
+
+    #[test]
+    fn test_decompressed_limit() {
+        // 4 MB of trailing JSON whitespace compress to a few KB
+        let mut json = br#"{"objectTypes":[],"eventTypes":[],"objects":[],"events":[]}"#.to_vec();
+        json.resize(4 * 1024 * 1024, b' ');
+        std::fs::create_dir_all("./temp").unwrap();
+        let bomb = TempUpload::in_temp_dir();
+        std::fs::write(bomb.path(), zstd::encode_all(&json[..], 19).unwrap()).unwrap();
+        assert!(std::fs::metadata(bomb.path()).unwrap().len() < 64 * 1024);
+
+        let options = ConversionOptions::default();
+        let err = import_ocel_file_within(bomb.path(), None, &options, 1024 * 1024).unwrap_err();
+        assert!(err.is::<DecompressedLimitExceeded>(), "{err:#}");
+        // Also when the limit is hit while the format is still being detected
+        let err = import_ocel_file_within(bomb.path(), None, &options, 1024).unwrap_err();
+        assert!(err.is::<DecompressedLimitExceeded>(), "{err:#}");
+
+        let (ocel, _) = import_ocel_file_within(bomb.path(), None, &options, json.len()).unwrap();
+        assert!(ocel.events.is_empty());
+    }
+}
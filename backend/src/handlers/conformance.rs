@@ -1,94 +1,59 @@
 use axum::{
     extract::Path as AxumPath,
+    extract::State,
     http::StatusCode,
     response::IntoResponse,
     Json,
 };
 use serde_json::json;
-use tokio::fs as tokio_fs;
 
 use crate::core::conformance::object_centric_language_abstraction_struct::{
     compute_fitness_precision, OCLanguageAbstraction,
 };
-use crate::models::ocel::{IndexLinkedOCEL, OCEL};
+use crate::core::ocpt::validation::validate_ocpt;
+use crate::models::ocel::IndexLinkedOCEL;
 
-// OCPT backend + (optionally) FE type & converter if needed
+// OCPT backend (stored FE OCPTs are converted when loaded)
 use crate::models::ocpt::OCPT as BackendOCPT;
-use crate::models::ocpt::OcptFE as FrontendOcpt;
-use crate::core::struct_converters::ocpt_frontend_backend::frontend_to_backend;
-
-/// Helper: Load an OCPT from disk, accepting either FE or BE JSON.
-/// Always returns the **backend** OCPT.
-async fn load_backend_ocpt(path: &str) -> Result<BackendOCPT, String> {
-    let content = tokio_fs::read_to_string(path)
-        .await
-        .map_err(|e| format!("read {}: {e}", path))?;
-
-    // Try backend first
-    if let Ok(be) = serde_json::from_str::<BackendOCPT>(&content) {
-        return Ok(be);
+use crate::core::struct_converters::ocpt_storage::ocpt_path;
+use crate::state::AppState;
+use std::sync::Arc;
+
+/// Helper: the cached OCPT `id` (stored in FE or BE JSON) as **backend** OCPT
+async fn load_backend_ocpt(state: &AppState, id: &str) -> Result<Arc<BackendOCPT>, String> {
+    match state.ocpt(id).await {
+        Ok(Some(ocpt)) => Ok(ocpt),
+        Ok(None) => Err(format!("read {}: not found", ocpt_path(id).to_string_lossy())),
+        Err(e) => Err(format!("{e:#}")),
     }
-
-    // Try frontend -> convert to backend
-    let fe = serde_json::from_str::<FrontendOcpt>(&content)
-        .map_err(|e| format!("parse OCPT (backend or frontend) failed at {}: {e}", path))?;
-
-    frontend_to_backend(fe)
-        .map_err(|e| format!("frontend→backend OCPT conversion failed at {}: {e}", path))
 }
 
 /// GET /v1/conformance/ocpt/{ocpt_id}/ocel/{ocel_id}"
-/// -> loads ./temp/ocpt_{ocpt_id}.json and (./temp/ocel_v2_{ocel_id}.json || ./temp/ocel_{ocel_id}.json)
+/// -> loads ./temp/ocpt_{ocpt_id}.json and the stored OCEL v2 {ocel_id}
 pub async fn get_conformance_ocpt_ocel(
+    State(state): State<AppState>,
     AxumPath((ocpt_id, ocel_id)): AxumPath<(String, String)>,
 ) -> impl IntoResponse {
-    // OCPT path (model)
-    let ocpt_path = format!("./temp/ocpt_{}.json", ocpt_id);
-
-    // OCEL path (log): prefer v2, fall back to plain
-    let ocel_v2_path = format!("./temp/ocel_v2_{}.json", ocel_id);
-    let ocel_plain_path = format!("./temp/ocel_{}.json", ocel_id);
-
     // --- Load OCPT (FE or BE) ---
-    let ocpt_backend = match load_backend_ocpt(&ocpt_path).await {
+    let ocpt_backend = match load_backend_ocpt(&state, &ocpt_id).await {
         Ok(x) => x,
         Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
     };
 
-    // --- Load OCEL (prefer v2) ---
-    let ocel_data = match tokio_fs::read_to_string(&ocel_v2_path).await {
-        Ok(s) => s,
-        Err(_) => match tokio_fs::read_to_string(&ocel_plain_path).await {
-            Ok(s) => s,
-            Err(e2) => {
-                return (
-                    StatusCode::NOT_FOUND,
-                    format!(
-                        "OCEL not found. Tried:\n  {}\n  {}\nError: {}",
-                        ocel_v2_path, ocel_plain_path, e2
-                    ),
-                )
-                    .into_response()
-            }
-        },
-    };
-
-    let ocel_struct: OCEL = match serde_json::from_str(&ocel_data) {
-        Ok(o) => o,
+    // --- Load OCEL ---
+    let locel: Arc<IndexLinkedOCEL> = match state.linked_ocel(&ocel_id).await {
+        Ok(Some(o)) => o,
+        Ok(None) => return (StatusCode::NOT_FOUND, format!("No OCEL found for fileId: {ocel_id}")).into_response(),
         Err(e) => {
             return (
                 StatusCode::BAD_REQUEST,
-                format!(
-                    "Failed to parse OCEL JSON ({} or {}): {}",
-                    ocel_v2_path, ocel_plain_path, e
-                ),
+                format!("Failed to load stored OCEL {}: {:#}", ocel_id, e),
             )
                 .into_response()
         }
     };
 
     // --- Conformance ---
-    let locel: IndexLinkedOCEL = IndexLinkedOCEL::from_ocel(ocel_struct);
     let model_abs = OCLanguageAbstraction::create_from_oc_process_tree(&ocpt_backend);
     let log_abs = OCLanguageAbstraction::create_from_ocel(&locel);
     let (fitness, precision) = compute_fitness_precision(&log_abs, &model_abs);
@@ -103,16 +68,14 @@ pub async fn get_conformance_ocpt_ocel(
 /// GET /v1/conformance/ocpt_1/{ocpt_id_1}/ocpt_2/{ocpt_id_2}
 /// -> loads ./temp/ocpt_{ocpt_id_1}.json and ./temp/ocpt_{ocpt_id_2}.json
 pub async fn get_conformance_ocpt_ocpt(
+    State(state): State<AppState>,
     AxumPath((ocpt_id_1, ocpt_id_2)): AxumPath<(String, String)>,
 ) -> impl IntoResponse {
-    let ocpt_1_path = format!("./temp/ocpt_{}.json", ocpt_id_1);
-    let ocpt_2_path = format!("./temp/ocpt_{}.json", ocpt_id_2);
-
-    let ocpt_1 = match load_backend_ocpt(&ocpt_1_path).await {
+    let ocpt_1 = match load_backend_ocpt(&state, &ocpt_id_1).await {
         Ok(x) => x,
         Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
     };
-    let ocpt_2 = match load_backend_ocpt(&ocpt_2_path).await {
+    let ocpt_2 = match load_backend_ocpt(&state, &ocpt_id_2).await {
         Ok(x) => x,
         Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
     };
@@ -127,3 +90,64 @@ pub async fn get_conformance_ocpt_ocpt(
     }))
     .into_response()
 }
+
+/// GET /v1/language/ocpt/{file_id}
+/// -> the start, end, directly-follows, related, divergent, convergent, deficient and optional
+///    event types per object type that the stored OCPT allows (422 for invalid trees)
+pub async fn get_language_ocpt(
+    State(state): State<AppState>,
+    AxumPath(file_id): AxumPath<String>,
+) -> impl IntoResponse {
+    let ocpt = match load_backend_ocpt(&state, &file_id).await {
+        Ok(x) => x,
+        Err(e) => return (StatusCode::NOT_FOUND, e).into_response(),
+    };
+    if !ocpt.is_valid() {
+        let resp = json!({
+            "error": "The OCPT is invalid",
+            "validation_errors": validate_ocpt(&ocpt, None),
+        });
+        return (StatusCode::UNPROCESSABLE_ENTITY, Json(resp)).into_response();
+    }
+    let abstraction = OCLanguageAbstraction::create_from_oc_process_tree(&ocpt);
+
+    Json(json!({
+        "file_id": file_id,
+        "kind": "ocpt",
+        "object_types": abstraction.per_ob_type(),
+    }))
+    .into_response()
+}
+
+/// GET /v1/language/ocel/{file_id}
+/// -> the same abstraction as for OCPTs, computed from the stored OCEL
+pub async fn get_language_ocel(
+    State(state): State<AppState>,
+    AxumPath(file_id): AxumPath<String>,
+) -> impl IntoResponse {
+    let locel = match state.linked_ocel(&file_id).await {
+        Ok(Some(locel)) => locel,
+        Ok(None) => return (StatusCode::NOT_FOUND, format!("No OCEL found for fileId: {file_id}")).into_response(),
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, format!("Failed to load stored OCEL {file_id}: {e:#}")).into_response()
+        }
+    };
+    let object_types = match tokio::task::spawn_blocking(move || {
+        OCLanguageAbstraction::create_from_ocel(&locel).per_ob_type()
+    })
+    .await
+    {
+        Ok(object_types) => object_types,
+        Err(e) => {
+            eprintln!("❌ Language abstraction of OCEL {file_id} failed: {e:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to compute language abstraction").into_response();
+        }
+    };
+
+    Json(json!({
+        "file_id": file_id,
+        "kind": "ocel",
+        "object_types": object_types,
+    }))
+    .into_response()
+}
@@ -0,0 +1,67 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use crate::core::dfg::discovery::{dfg_to_graph, discover_oc_dfg, DfgOptions};
+use crate::models::dfg::Graph;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct DfgQuery {
+    /// `raw` (default): the DFGs with frequencies and start/end activities;
+    /// `graph`: one node/edge graph per object type
+    format: Option<String>,
+}
+
+/// GET /v1/dfg/ocel/{file_id}?object_types=a,b&min_activity_frequency=n&min_edge_frequency=n&format=raw|graph
+/// -> the directly-follows graph of each (selected) object type of the stored OCEL
+pub async fn get_oc_dfg(
+    State(state): State<AppState>,
+    Path(file_id): Path<String>,
+    Query(query): Query<DfgQuery>,
+    Query(options): Query<DfgOptions>,
+) -> Response {
+    let as_graph = match query.format.as_deref() {
+        None | Some("raw") => false,
+        Some("graph") => true,
+        Some(name) => return (StatusCode::BAD_REQUEST, format!("Unknown DFG format: {name}")).into_response(),
+    };
+    let locel = match state.linked_ocel(&file_id).await {
+        Ok(Some(locel)) => locel,
+        Ok(None) => return (StatusCode::NOT_FOUND, format!("No OCEL v2 file found for fileId: {file_id}")).into_response(),
+        Err(e) => {
+            eprintln!("❌ load stored OCEL failed: {e:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Stored file is not a valid OCEL").into_response();
+        }
+    };
+
+    let discovered = tokio::task::spawn_blocking(move || {
+        let dfg = discover_oc_dfg(&locel, &options)?;
+        let payload = if as_graph {
+            let graphs: BTreeMap<&String, Graph> = dfg
+                .object_type_to_dfg
+                .iter()
+                .map(|(ot, ot_dfg)| (ot, dfg_to_graph(ot_dfg)))
+                .collect();
+            serde_json::json!({ "file_id": file_id, "graphs": graphs })
+        } else {
+            serde_json::json!({ "file_id": file_id, "dfgs": dfg.object_type_to_dfg })
+        };
+        Ok::<_, String>(payload)
+    })
+    .await;
+
+    match discovered {
+        Ok(Ok(payload)) => (StatusCode::OK, Json(payload)).into_response(),
+        Ok(Err(msg)) => (StatusCode::BAD_REQUEST, msg).into_response(),
+        Err(e) => {
+            eprintln!("❌ DFG discovery failed: {e:?}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to discover DFG").into_response()
+        }
+    }
+}
@@ -0,0 +1,35 @@
+//! Helpers shared by handlers serving files.
+
+/// `Content-Disposition` value (`attachment` or `inline`) for a file named `file_name`: an ASCII
+/// fallback restricted to `[A-Za-z0-9._-]` plus the percent-encoded original as RFC 5987
+/// `filename*`, so ids with quotes, semicolons or non-ASCII characters cannot break the header.
+pub fn content_disposition(disposition: &str, file_name: &str) -> String {
+    let fallback: String = file_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') { c } else { '_' })
+        .collect();
+    let encoded: String = file_name
+        .bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'-' | b'~') {
+                (b as char).to_string()
+            } else {
+                format!("%{b:02X}")
+            }
+        })
+        .collect();
+    format!("{disposition}; filename=\"{fallback}\"; filename*=UTF-8''{encoded}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_content_disposition() {
+        let value = content_disposition("attachment", "ocel_a\"b;ö.json");
+        assert_eq!(value, "attachment; filename=\"ocel_a_b__.json\"; filename*=UTF-8''ocel_a%22b%3B%C3%B6.json");
+        assert!(HeaderValue::from_str(&value).is_ok());
+    }
+}
@@ -1,75 +1,131 @@
-use crate::core::event_object_frequencies::{histogram_builder::build_event_object_histograms, histogram_filtering::filter_ocel_histograms};
+use crate::core::event_object_frequencies::{histogram_builder::{build_event_object_histograms, build_object_event_histograms}, histogram_filtering::filter_ocel_histograms, sublog_suggestions::{suggest_sublogs, SuggestionOptions}};
 
 
-use tokio::fs as tokio_fs;
 use axum::{
     extract::Path as AxumPath,
     extract::Json as AxumJson,
+    extract::Query,
+    extract::State,
     http::StatusCode,
     response::IntoResponse,
 };
 use crate::models::ocel::OCEL;
+use crate::state::AppState;
+use std::sync::Arc;
 
 /// GET /v1/event_object_frequencies/:file_id
-/// -> loads ./temp/ocpt_{file_id}.json and ./temp/ocel_{file_id}.json
-pub async fn get_event_object_frequencies(AxumPath(ocel_file_id): AxumPath<String>) -> impl IntoResponse {
-    let ocel_path = format!("./temp/ocel_v2_{}.json", ocel_file_id);
-
-    let ocel_data: String = match tokio_fs::read_to_string(&ocel_path).await {
-        Ok(s) => s,
-        Err(e) => {
+/// -> loads the stored OCEL v2 {file_id} and returns the event-to-object histograms
+pub async fn get_event_object_frequencies(
+    State(state): State<AppState>,
+    AxumPath(ocel_file_id): AxumPath<String>,
+) -> impl IntoResponse {
+    let ocel: Arc<OCEL> = match state.ocel(&ocel_file_id).await {
+        Ok(Some(o)) => o,
+        Ok(None) => {
             return (
                 StatusCode::NOT_FOUND,
-                format!("OCEL not found at {}: {}", ocel_path, e),
+                format!("OCEL not found for fileId: {}", ocel_file_id),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                format!("Failed to load OCEL {}: {:#}", ocel_file_id, e),
             )
                 .into_response()
         }
     };
 
-    let ocel: OCEL = match serde_json::from_str(&ocel_data) {
-        Ok(o) => o,
+    let histogram = build_event_object_histograms(&ocel);
+
+    (StatusCode::OK, axum::Json(histogram)).into_response()
+}
+
+
+/// GET /v1/event_object_frequencies/ocel_object_event/:file_id
+/// -> loads the stored OCEL v2 {file_id} and returns the object-to-event histograms
+pub async fn get_object_event_frequencies(
+    State(state): State<AppState>,
+    AxumPath(ocel_file_id): AxumPath<String>,
+) -> impl IntoResponse {
+    let ocel: Arc<OCEL> = match state.ocel(&ocel_file_id).await {
+        Ok(Some(o)) => o,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                format!("OCEL not found for fileId: {}", ocel_file_id),
+            )
+                .into_response()
+        }
         Err(e) => {
             return (
                 StatusCode::BAD_REQUEST,
-                format!("Failed to parse OCEL JSON ({}): {}", ocel_path, e),
+                format!("Failed to load OCEL {}: {:#}", ocel_file_id, e),
             )
                 .into_response()
         }
     };
 
-    let histogram = build_event_object_histograms(&ocel);
+    let histogram = build_object_event_histograms(&ocel);
 
     (StatusCode::OK, axum::Json(histogram)).into_response()
 }
 
 
+/// GET /v1/event_object_frequencies/ocel_suggestions/:file_id?min_support=0.05&max_suggestions=10&valley_ratio=0.5
+/// -> proposes selections (`SelectionPayload` scheme) that can be posted to /ocel_filter/:file_id
+pub async fn get_sublog_suggestions(
+    State(state): State<AppState>,
+    AxumPath(ocel_file_id): AxumPath<String>,
+    Query(options): Query<SuggestionOptions>,
+) -> impl IntoResponse {
+    let ocel: Arc<OCEL> = match state.ocel(&ocel_file_id).await {
+        Ok(Some(o)) => o,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                format!("OCEL not found for fileId: {}", ocel_file_id),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                format!("Failed to load OCEL {}: {:#}", ocel_file_id, e),
+            )
+                .into_response()
+        }
+    };
+
+    let suggestions = suggest_sublogs(&ocel, &options);
+
+    (StatusCode::OK, axum::Json(suggestions)).into_response()
+}
+
+
 /// POST /v1/ocel_filter/:file_id
 /// Body: JSON following the `SelectionPayload` scheme
 /// Returns: array of filtered OCELs
 pub async fn post_ocel_filter(
+    State(state): State<AppState>,
     AxumPath(ocel_file_id): AxumPath<String>,
     AxumJson(selection_json): AxumJson<serde_json::Value>,
 ) -> impl IntoResponse {
-    let ocel_path = format!("./temp/ocel_v2_{}.json", ocel_file_id);
-
     // 1. Load the OCEL
-    let ocel_data: String = match tokio_fs::read_to_string(&ocel_path).await {
-        Ok(s) => s,
-        Err(e) => {
+    let ocel: Arc<OCEL> = match state.ocel(&ocel_file_id).await {
+        Ok(Some(o)) => o,
+        Ok(None) => {
             return (
                 StatusCode::NOT_FOUND,
-                format!("OCEL not found at {}: {}", ocel_path, e),
+                format!("OCEL not found for fileId: {}", ocel_file_id),
             )
                 .into_response()
         }
-    };
-
-    let ocel: OCEL = match serde_json::from_str(&ocel_data) {
-        Ok(o) => o,
         Err(e) => {
             return (
                 StatusCode::BAD_REQUEST,
-                format!("Failed to parse OCEL JSON ({}): {}", ocel_path, e),
+                format!("Failed to load OCEL {}: {:#}", ocel_file_id, e),
             )
                 .into_response()
         }
@@ -77,7 +133,12 @@ pub async fn post_ocel_filter(
 
     // 2. Call filtering function
     let filtered_ocels = match serde_json::to_string(&selection_json) {
-        Ok(json_str) => filter_ocel_histograms(&ocel, &json_str),
+        Ok(json_str) => match filter_ocel_histograms(&ocel, &json_str) {
+            Ok(filtered) => filtered,
+            Err(e) => {
+                return (StatusCode::BAD_REQUEST, format!("Invalid selection JSON: {}", e)).into_response()
+            }
+        },
         Err(e) => {
             return (
                 StatusCode::BAD_REQUEST,
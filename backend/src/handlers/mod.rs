@@ -1,4 +1,8 @@
 pub mod ocel;
 pub mod ocpt;
 pub mod conformance;
-pub mod event_object_frequencies;
\ No newline at end of file
+pub mod event_object_frequencies;
+pub mod render;
+pub mod dfg;
+pub mod download;
+pub mod ocpt_edit;pub mod playout;
@@ -1,91 +1,110 @@
 use axum::{
     Json,
-    http::StatusCode,
+    http::{header, StatusCode},
     response::IntoResponse,
-    extract::Path,
+    extract::{Path, Query, State},
 };
-use axum_extra::extract::Multipart; 
+use axum_extra::extract::{multipart::Field, Multipart};
+use serde::Deserialize;
 use std::path::PathBuf;
 use tokio::fs;
+use tokio::io::AsyncWriteExt;
 use serde_json;
-use bytes::Bytes;
-use serde_json::Value;
-use crate::core::struct_converters::{self, ocel_1_ocel_2_converter};
+use crate::core::struct_converters::flat_log_converter::{FlatLogFormat, FlatLogMapping};
+use crate::core::struct_converters::ocel_1_ocel_2_converter::{
+    ConversionOptions, ConversionReport,
+};
+use crate::core::struct_converters::ocel_2_ocel_1_converter::{
+    convert_ocel_to_ocel1, AttributeCollapsePolicy, DowngradeOptions,
+};
+use crate::core::struct_converters::ocel_formats::{
+    export_ocel, import_ocel, OcelFormat,
+};
+use crate::core::struct_converters::ocel_storage::store_ocel;
+use crate::core::struct_converters::ocel_validation::{validate_ocel, ValidationMode};
+use crate::core::struct_converters::upload_stream::{
+    import_flat_log_file, import_ocel_file, strip_compression_suffix, upload_limit_bytes,
+    DecompressedLimitExceeded,
+    TempUpload,
+};
 use crate::models::ocel::OCEL;
+use crate::handlers::download::content_disposition;
+use crate::state::AppState;
+use std::sync::Arc;
 
 
 // --- helpers ---
 
-fn is_ocel_v1(v: &Value) -> bool {
-    v.get("ocel:events").is_some() && v.get("ocel:global-log").is_some()
-}
-
-fn is_ocel_v2(v: &Value) -> bool {
-    v.get("objectTypes").is_some() && v.get("eventTypes").is_some()
-}
-
 async fn ensure_temp_dir() -> Result<(), std::io::Error> {
     fs::create_dir_all("./temp").await
 }
 
 // ========== POST: raw JSON body (accept v1 or v2, always store v2) ==========
-pub async fn post_ocel_json(Json(payload): Json<Value>) -> impl IntoResponse {
+// OCEL 1.0 conversion options are given as query parameters, e.g. `?qualifier=activity`
+pub async fn post_ocel_json(
+    Query(options): Query<ConversionOptions>,
+    body: axum::body::Bytes,
+) -> impl IntoResponse {
     if let Err(e) = ensure_temp_dir().await {
         eprintln!("❌ create ./temp failed: {e:?}");
         return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to prepare storage").into_response();
     }
 
-    // Normalize into OCEL (v2 struct)
-    let ocel_struct: OCEL = if is_ocel_v1(&payload) {
-        match ocel_1_ocel_2_converter::convert_ocel1_value_to_ocel(&payload) {
-            Ok(oc) => oc,
-            Err(e) => {
-                eprintln!("❌ v1→v2 conversion failed: {e:?}");
-                return (StatusCode::BAD_REQUEST, "OCEL 1.0 to 2.0 conversion failed").into_response();
-            }
-        }
-    } else if is_ocel_v2(&payload) {
-        // Validate/normalize the v2 by parsing into OCEL
-        match serde_json::from_value::<OCEL>(payload) {
-            Ok(oc) => oc,
-            Err(e) => {
-                eprintln!("❌ OCEL 2.0 payload does not match struct: {e}");
-                return (StatusCode::BAD_REQUEST, "Invalid OCEL 2.0 structure").into_response();
-            }
+    // Normalize into OCEL (v2 struct), parsed straight from the body
+    let (ocel_struct, report): (OCEL, Option<ConversionReport>) = match import_ocel(&body, OcelFormat::Json, &options) {
+        Ok(res) => res,
+        Err(e) => {
+            eprintln!("❌ OCEL normalization failed: {e:?}");
+            return (StatusCode::BAD_REQUEST, e.to_string()).into_response();
         }
-    } else {
-        return (StatusCode::BAD_REQUEST, "Unknown OCEL structure").into_response();
     };
 
     // Persist normalized v2
-    let filename = format!("./temp/ocel_v2_{}.json", uuid::Uuid::new_v4()); // or your own id/timestamp
-    let pretty = match serde_json::to_string_pretty(&ocel_struct) {
-        Ok(s) => s,
-        Err(e) => {
-            eprintln!("❌ serialize OCEL failed: {e:?}");
-            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to serialize OCEL").into_response();
+    let id = uuid::Uuid::new_v4().to_string(); // or your own id/timestamp
+    let filename = match tokio::task::spawn_blocking(move || store_ocel(&id, &ocel_struct)).await {
+        Ok(Ok(path)) => path,
+        res => {
+            eprintln!("❌ write file failed: {res:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save file").into_response();
         }
     };
-    if let Err(e) = fs::write(&filename, pretty).await {
-        eprintln!("❌ write file failed: {e:?}");
-        return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save file").into_response();
-    }
 
     let resp = serde_json::json!({
         "status": "ok",
         "converted_to_v2": true, // we always end up with normalized v2
-        "saved_as": filename
+        "saved_as": filename,
+        "conversion_report": report
     });
     (StatusCode::OK, Json(resp)).into_response()
 }
 
-// ========== POST: multipart upload (accept v1 or v2, always store v2) ==========
-pub async fn post_ocel_binary(mut multipart: Multipart) -> impl IntoResponse {
+// ========== POST: multipart upload (JSON v1/v2, XML, SQLite or flat XES/CSV, always store v2) ==========
+// The log is validated before it is stored; see `ValidationMode` for the `validation` field.
+// The file is streamed to disk (up to `upload_limit_bytes()`), may be gzip/zstd-compressed
+// (decompressing to at most `decompressed_limit_bytes()`) and is parsed incrementally from there.
+pub async fn post_ocel_binary(
+    State(state): State<AppState>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
     let mut file_id: Option<String> = None;
-    let mut file_bytes: Option<Bytes> = None;
+    let mut upload: Option<TempUpload> = None;
+    let mut file_name: Option<String> = None;
     let mut file_type: Option<String> = None;
+    let mut mapping: Option<String> = None;
+    let mut conversion_options: Option<String> = None;
+    let mut validation: Option<String> = None;
+
+    if let Err(e) = ensure_temp_dir().await {
+        eprintln!("❌ create ./temp failed: {e:?}");
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to prepare storage").into_response();
+    }
 
-    while let Some(field) = multipart.next_field().await.unwrap() {
+    loop {
+        let mut field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return (e.status(), e.body_text()).into_response(),
+        };
         match field.name().unwrap_or("") {
             "file_id" => {
                 let v = field.text().await.unwrap_or_default();
@@ -93,110 +112,334 @@ pub async fn post_ocel_binary(mut multipart: Multipart) -> impl IntoResponse {
                 file_id = Some(v);
             }
             "file" => {
-                let data = field.bytes().await.unwrap_or_default();
-                println!("📥 file bytes: {}", data.len());
-                file_bytes = Some(data);
+                file_name = field.file_name().map(str::to_string);
+                let target = TempUpload::in_temp_dir();
+                match write_field_to_disk(&mut field, target.path(), upload_limit_bytes()).await {
+                    Ok(n) => println!("📥 file bytes: {n}"),
+                    Err(resp) => return resp,
+                }
+                upload = Some(target);
             }
             "file_type" => {
                 file_type = Some(field.text().await.unwrap_or_default());
             }
+            "mapping" => {
+                mapping = Some(field.text().await.unwrap_or_default());
+            }
+            "conversion_options" => {
+                conversion_options = Some(field.text().await.unwrap_or_default());
+            }
+            "validation" => {
+                validation = Some(field.text().await.unwrap_or_default());
+            }
             other => println!("⚠️ Unknown form field: {other}"),
         }
     }
 
-    let (id, bytes) = match (file_id, file_bytes) {
-        (Some(i), Some(b)) => (i, b),
+    let (id, upload) = match (file_id, upload) {
+        (Some(i), Some(u)) => (i, u),
         _ => return (StatusCode::BAD_REQUEST, "Missing file or fileId").into_response(),
     };
+    // `report` (default) only lists integrity issues, `strict` rejects logs with issues and
+    // `lenient` repairs them
+    let validation: ValidationMode = match validation.as_deref() {
+        Some(name) => match ValidationMode::from_name(name) {
+            Some(m) => m,
+            None => return (StatusCode::BAD_REQUEST, format!("Unknown validation mode: {name}")).into_response(),
+        },
+        None => ValidationMode::default(),
+    };
+    // "log.jsonocel.gz" names a compressed JSONOCEL
+    let file_name = file_name.as_deref().map(strip_compression_suffix);
+
+    // Flat XES/CSV logs are converted using the column mapping sent along
+    let flat_format = file_type
+        .as_deref()
+        .and_then(FlatLogFormat::from_name)
+        .or_else(|| file_name.and_then(FlatLogFormat::from_file_name));
+    if let Some(flat_format) = flat_format {
+        let mapping: FlatLogMapping = match mapping.as_deref().map(serde_json::from_str) {
+            Some(Ok(m)) => m,
+            Some(Err(e)) => return (StatusCode::BAD_REQUEST, format!("Invalid mapping: {e}")).into_response(),
+            None => return (StatusCode::BAD_REQUEST, "Missing mapping for flat event log").into_response(),
+        };
+        let path = upload.path().to_path_buf();
+        let imported = tokio::task::spawn_blocking(move || import_flat_log_file(&path, flat_format, &mapping)).await;
+        return match imported {
+            Ok(Ok(ocel_struct)) => persist_uploaded_ocel(&state, id, ocel_struct, None, validation).await,
+            Ok(Err(e)) if e.is::<DecompressedLimitExceeded>() => {
+                (StatusCode::PAYLOAD_TOO_LARGE, e.to_string()).into_response()
+            }
+            Ok(Err(e)) => {
+                eprintln!("❌ flat log import ({flat_format:?}) failed: {e:?}");
+                (StatusCode::BAD_REQUEST, format!("{e:#}")).into_response()
+            }
+            Err(e) => {
+                eprintln!("❌ flat log import task failed: {e:?}");
+                (StatusCode::INTERNAL_SERVER_ERROR, "Import failed").into_response()
+            }
+        };
+    }
+
+    // Pick the format: explicit file_type, then the file extension; otherwise it is detected
+    // from the (decompressed) content
+    let format = file_type
+        .as_deref()
+        .and_then(OcelFormat::from_name)
+        .or_else(|| file_name.and_then(OcelFormat::from_file_name));
+
+    // OCEL 1.0 conversion options (JSON, see `ConversionOptions`)
+    let options: ConversionOptions = match conversion_options.as_deref().map(serde_json::from_str) {
+        Some(Ok(o)) => o,
+        Some(Err(e)) => return (StatusCode::BAD_REQUEST, format!("Invalid conversion_options: {e}")).into_response(),
+        None => ConversionOptions::default(),
+    };
 
-    // Decode and parse JSON
-    let text = match str::from_utf8(&bytes) {
-        Ok(t) => t,
+    // Normalize into OCEL (v2 struct)
+    let path = upload.path().to_path_buf();
+    let imported = tokio::task::spawn_blocking(move || import_ocel_file(&path, format, &options)).await;
+    let (ocel_struct, report): (OCEL, Option<ConversionReport>) = match imported {
+        Ok(Ok(res)) => res,
+        Ok(Err(e)) if e.is::<DecompressedLimitExceeded>() => {
+            return (StatusCode::PAYLOAD_TOO_LARGE, e.to_string()).into_response();
+        }
+        Ok(Err(e)) => {
+            eprintln!("❌ OCEL import ({format:?}) failed: {e:?}");
+            return (StatusCode::BAD_REQUEST, e.to_string()).into_response();
+        }
         Err(e) => {
-            eprintln!("❌ UTF-8 decode failed: {e}");
-            return (StatusCode::BAD_REQUEST, "File is not valid UTF-8").into_response();
+            eprintln!("❌ OCEL import task failed: {e:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Import failed").into_response();
         }
     };
-    let value: Value = match serde_json::from_str(text) {
-        Ok(v) => v,
+    drop(upload);
+
+    persist_uploaded_ocel(&state, id, ocel_struct, report, validation).await
+}
+
+/// Streams a multipart field to `path` chunk by chunk, rejecting uploads larger than `limit`
+async fn write_field_to_disk(
+    field: &mut Field,
+    path: &std::path::Path,
+    limit: usize,
+) -> Result<usize, axum::response::Response> {
+    let mut file = match fs::File::create(path).await {
+        Ok(f) => f,
         Err(e) => {
-            eprintln!("❌ Invalid JSON: {e}");
-            return (StatusCode::BAD_REQUEST, "Invalid JSON format").into_response();
+            eprintln!("❌ create upload file failed: {e:?}");
+            return Err((StatusCode::INTERNAL_SERVER_ERROR, "Failed to save file").into_response());
         }
     };
-
-    if let Err(e) = ensure_temp_dir().await {
-        eprintln!("❌ create ./temp failed: {e:?}");
-        return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to prepare storage").into_response();
+    let mut written = 0usize;
+    loop {
+        let chunk = match field.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break,
+            Err(e) => return Err((e.status(), e.body_text()).into_response()),
+        };
+        written += chunk.len();
+        if written > limit {
+            return Err((
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("File exceeds the upload limit of {} MB", limit / (1024 * 1024)),
+            )
+                .into_response());
+        }
+        if let Err(e) = file.write_all(&chunk).await {
+            eprintln!("❌ write upload chunk failed: {e:?}");
+            return Err((StatusCode::INTERNAL_SERVER_ERROR, "Failed to save file").into_response());
+        }
+    }
+    if let Err(e) = file.flush().await {
+        eprintln!("❌ flush upload file failed: {e:?}");
+        return Err((StatusCode::INTERNAL_SERVER_ERROR, "Failed to save file").into_response());
     }
+    Ok(written)
+}
 
-    // Normalize into OCEL (v2 struct)
-    let ocel_struct: OCEL = if is_ocel_v1(&value) {
-        match ocel_1_ocel_2_converter::convert_ocel1_value_to_ocel(&value) {
-            Ok(oc) => oc,
-            Err(e) => {
-                eprintln!("❌ v1→v2 conversion failed: {e:?}");
-                return (StatusCode::BAD_REQUEST, "OCEL 1.0 to 2.0 conversion failed").into_response();
-            }
+/// Validates a normalized OCEL v2 (see `ValidationMode`), stores it under the given file id
+/// (replacing what is cached for it) and returns the upload response, including the OCEL 1.0
+/// conversion report if there is one. Strictly validated logs with issues are rejected with 422.
+async fn persist_uploaded_ocel(
+    state: &AppState,
+    id: String,
+    ocel_struct: OCEL,
+    report: Option<ConversionReport>,
+    mode: ValidationMode,
+) -> axum::response::Response {
+    // Validate (and repair) before persisting normalized v2 in the binary storage format
+    let target = id.clone();
+    let stored = tokio::task::spawn_blocking(move || {
+        validate_ocel(ocel_struct, mode).map(|(ocel, validation)| (store_ocel(&target, &ocel), validation))
+    })
+    .await;
+    let (filename, validation) = match stored {
+        Ok(Ok((Ok(path), validation))) => (path, validation),
+        Ok(Err(validation)) => {
+            println!("⚠️ OCEL {id} rejected: {} validation issues", validation.total());
+            let resp = serde_json::json!({
+                "status": "invalid",
+                "validation_report": validation,
+            });
+            return (StatusCode::UNPROCESSABLE_ENTITY, Json(resp)).into_response();
         }
-    } else if is_ocel_v2(&value) {
-        match serde_json::from_value::<OCEL>(value) {
-            Ok(oc) => oc,
-            Err(e) => {
-                eprintln!("❌ OCEL 2.0 payload does not match struct: {e}");
-                return (StatusCode::BAD_REQUEST, "Invalid OCEL 2.0 structure").into_response();
-            }
+        Ok(Ok((Err(e), _))) => {
+            eprintln!("❌ write file failed: {e:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save file").into_response();
         }
-    } else {
-        return (StatusCode::BAD_REQUEST, "Unknown OCEL structure").into_response();
-    };
-
-    // Persist normalized v2
-    let filename = format!("./temp/ocel_v2_{}.json", id);
-    let pretty = match serde_json::to_string_pretty(&ocel_struct) {
-        Ok(s) => s,
         Err(e) => {
-            eprintln!("❌ serialize OCEL failed: {e:?}");
-            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to serialize OCEL").into_response();
+            eprintln!("❌ validation task failed: {e:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save file").into_response();
         }
     };
-    if let Err(e) = fs::write(&filename, pretty).await {
-        eprintln!("❌ write file failed: {e:?}");
-        return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save file").into_response();
+    state.invalidate(&id);
+    if validation.repaired {
+        println!("⚠️ OCEL {id}: repaired {} validation issues", validation.total());
     }
 
     let resp = serde_json::json!({
         "status": "ok",
         "converted_to_v2": true,
-        "saved_as": filename
+        "saved_as": filename,
+        "conversion_report": report,
+        "validation_report": validation
     });
     (StatusCode::OK, Json(resp)).into_response()
 }
 
+#[derive(Deserialize)]
+pub struct OcelExportQuery {
+    /// `json` (default), `xml` or `sqlite`
+    format: Option<String>,
+}
+
 // ========== GET: only serve v2 files ==========
-pub async fn get_ocel(Path(file_id): Path<String>) -> impl IntoResponse {
-    let path_json = PathBuf::from(format!("./temp/ocel_v2_{file_id}.json"));
-    if !path_json.exists() {
-        return (StatusCode::NOT_FOUND, format!("No OCEL v2 file found for fileId: {file_id}")).into_response();
-    }
-    match fs::read_to_string(&path_json).await {
-        Ok(content) => match serde_json::from_str::<Value>(&content) {
-            Ok(json) => (StatusCode::OK, Json(json)).into_response(),
-            Err(e) => {
-                eprintln!("❌ parse stored OCEL failed: {e}");
-                (StatusCode::INTERNAL_SERVER_ERROR, "Stored file is not valid JSON").into_response()
-            }
+// JSON (or XML/SQLite) is produced from the stored binary OCEL on request
+pub async fn get_ocel(
+    State(state): State<AppState>,
+    Path(file_id): Path<String>,
+    Query(query): Query<OcelExportQuery>,
+) -> impl IntoResponse {
+    let format = match query.format.as_deref() {
+        None => OcelFormat::Json,
+        Some(name) => match OcelFormat::from_name(name) {
+            Some(f) => f,
+            None => return (StatusCode::BAD_REQUEST, format!("Unknown export format: {name}")).into_response(),
         },
+    };
+    let ocel: Arc<OCEL> = match read_stored_ocel(&state, &file_id).await {
+        Ok(o) => o,
+        Err(resp) => return resp,
+    };
+    if format != OcelFormat::Json {
+        return export_stored_ocel(&ocel, &file_id, format);
+    }
+    (StatusCode::OK, Json(&*ocel)).into_response()
+}
+
+
+/// Loads the (cached) OCEL v2 stored under `file_id`, or returns the error response to send
+async fn read_stored_ocel(state: &AppState, file_id: &str) -> Result<Arc<OCEL>, axum::response::Response> {
+    match state.ocel(file_id).await {
+        Ok(Some(ocel)) => Ok(ocel),
+        Ok(None) => Err((StatusCode::NOT_FOUND, format!("No OCEL v2 file found for fileId: {file_id}")).into_response()),
         Err(e) => {
-            eprintln!("❌ read file failed: {e}");
-            (StatusCode::INTERNAL_SERVER_ERROR, "Could not read file").into_response()
+            eprintln!("❌ load stored OCEL failed: {e:?}");
+            Err((StatusCode::INTERNAL_SERVER_ERROR, "Stored file is not a valid OCEL").into_response())
         }
     }
 }
 
+/// Serializes a stored OCEL v2 as XML or SQLite and serves it as a download
+fn export_stored_ocel(
+    ocel: &OCEL,
+    file_id: &str,
+    format: OcelFormat,
+) -> axum::response::Response {
+    match export_ocel(ocel, format) {
+        Ok(bytes) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, format.content_type().to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    content_disposition("attachment", &format!("ocel_{file_id}.{}", format.extension())),
+                ),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Err(e) => {
+            eprintln!("❌ export OCEL ({format:?}) failed: {e:?}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to export OCEL").into_response()
+        }
+    }
+}
 
+#[derive(Deserialize)]
+pub struct Ocel1ExportQuery {
+    #[serde(default)]
+    object_attribute_policy: AttributeCollapsePolicy,
+    /// Wrap the log as `{ "ocel": …, "report": … }` instead of serving the plain file
+    #[serde(default)]
+    report: bool,
+}
 
-pub async fn delete_ocel(Path(file_id): Path<String>) -> impl IntoResponse {
+// ========== GET: stored v2 file downgraded to OCEL 1.0 JSON ==========
+// Time-varying object attributes are collapsed per `?object_attribute_policy=first|last|error`.
+// `?report=true` returns the conversion report (dropped O2O relationships, warnings) next to the
+// log in the body; the plain download only carries the warning count in `X-Conversion-Warnings`.
+pub async fn get_ocel1(
+    State(state): State<AppState>,
+    Path(file_id): Path<String>,
+    Query(query): Query<Ocel1ExportQuery>,
+) -> impl IntoResponse {
+    let ocel: Arc<OCEL> = match read_stored_ocel(&state, &file_id).await {
+        Ok(o) => o,
+        Err(resp) => return resp,
+    };
+
+    let options = DowngradeOptions { object_attribute_policy: query.object_attribute_policy };
+    let (o1, report) = match convert_ocel_to_ocel1(&ocel, &options) {
+        Ok(res) => res,
+        Err(e) => return (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()).into_response(),
+    };
+    for warning in &report.warnings {
+        println!("⚠️ OCEL 1.0 export of {file_id}: {warning}");
+    }
+
+    if query.report {
+        return Json(serde_json::json!({ "ocel": o1, "report": report })).into_response();
+    }
+
+    match serde_json::to_vec_pretty(&o1) {
+        Ok(body) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "application/json".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    content_disposition("attachment", &format!("ocel1_{file_id}.jsonocel")),
+                ),
+                (
+                    header::HeaderName::from_static("x-conversion-warnings"),
+                    report.warnings.len().to_string(),
+                ),
+            ],
+            body,
+        )
+            .into_response(),
+        Err(e) => {
+            eprintln!("❌ serialize OCEL 1.0 failed: {e:?}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to export OCEL 1.0").into_response()
+        }
+    }
+}
+
+pub async fn delete_ocel(
+    State(state): State<AppState>,
+    Path(file_id): Path<String>,
+) -> impl IntoResponse {
     println!("🗑️ DELETE /v1/objects/ocel/{}", file_id);
 
     let base_path = PathBuf::from("./temp");
@@ -206,6 +449,7 @@ pub async fn delete_ocel(Path(file_id): Path<String>) -> impl IntoResponse {
         base_path.join(format!("ocel_v1_{}.json", file_id)),
         base_path.join(format!("ocel_v1_{}.jsonocel", file_id)),
         base_path.join(format!("ocel_v2_{}.json", file_id)),
+        base_path.join(format!("ocel_v2_{}.bin", file_id)),
         base_path.join(format!("ocel_v2_{}.jsonocel", file_id)),
     ];
 
@@ -215,20 +459,20 @@ pub async fn delete_ocel(Path(file_id): Path<String>) -> impl IntoResponse {
         .filter(|p| p.exists())
         .collect();
 
-    if existing.len() > 1 {
-        eprintln!("❌ Conflict: Multiple OCEL versions found for fileId '{}'", file_id);
-        return (StatusCode::CONFLICT, "Conflict: multiple versions found").into_response();
-    } else if existing.is_empty() {
+    if existing.is_empty() {
         eprintln!("❌ No OCEL file found for fileId '{}'", file_id);
         return (StatusCode::NOT_FOUND, format!("No OCEL file found for fileId: {}", file_id)).into_response();
     }
 
-    match fs::remove_file(&existing[0]).await {
-        Ok(_) => (StatusCode::NO_CONTENT, "Deleted file").into_response(),
-        Err(e) => {
+    // Remove every stored variant (e.g. a legacy JSON next to its binary re-upload)
+    for path in &existing {
+        if let Err(e) = fs::remove_file(path).await {
             eprintln!("❌ Failed to delete file: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to delete file").into_response()
+            state.invalidate(&file_id);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to delete file").into_response();
         }
     }
+    state.invalidate(&file_id);
+    (StatusCode::NO_CONTENT, "Deleted file").into_response()
 }
 
@@ -2,26 +2,51 @@ use axum::{
     Json,
     http::StatusCode,
     response::IntoResponse,
-    extract::Path,
+    extract::{Path, Query, State},
+    http::header,
     response::Response,
 };
 use axum_extra::extract::Multipart; 
 use serde_json;
+use std::collections::HashSet;
 use std::path::PathBuf;
 use tokio::fs;
 use serde::Deserialize;
 use serde_json::Value;
-use std::path::Path as FsPath;
-use crate::core::df2_miner::ocpt_generator::generate_ocpt_from_fileid;
+use crate::core::df2_miner::cuts::CutFinder;
+use crate::core::df2_miner::ocpt_generator::{generate_ocpt_from_ocel, MiningOptions};
+use crate::core::ocpt::OcptFormat;
+use crate::core::ocpt::bpmn::export_bpmn;
+use crate::core::ocpt::history::remove_history;
+use crate::core::ocpt::import::{import_pm4py, import_ptml, parse_annotations, OcptImportFormat};
+use crate::core::ocpt::flatten::{flatten_ocpt, ocpt_object_types};
+use crate::core::ocpt::pnml::export_pnml;
+use crate::core::ocpt::ptml::export_ptml;
+use crate::core::ocpt::validation::validate_ocpt;
 use crate::core::struct_converters::ocpt_frontend_backend::{frontend_to_backend, backend_to_frontend};
 use crate::models::ocpt::{OcptFE, OCPT};
+use crate::handlers::download::content_disposition;
+use crate::state::AppState;
 
 
 
 
-pub async fn post_ocpt(mut multipart: Multipart) -> Response {
+/// POST /v1/upload/ocpt (multipart: `file_id`, `file`, optional `format`, `annotations` and
+/// `canonicalize`)
+///
+/// `file` is a backend or frontend OCPT JSON, a PM4Py OCPT JSON, or a PTML process tree. PTML
+/// needs an `annotations` file with the object types of its activities. `format` (`json`,
+/// `ptml`, `pm4py`) defaults to PTML for XML content and JSON otherwise. Imported PTML and
+/// PM4Py trees are rejected with 422 if they are not valid OCPTs; otherwise the response lists
+/// the `validation_errors` of the tree (see `core/ocpt/validation.rs`). With `canonicalize=true`
+/// the tree is reduced and its commutative children ordered before validation and storage (see
+/// `core/ocpt/reduce.rs`).
+pub async fn post_ocpt(State(state): State<AppState>, mut multipart: Multipart) -> Response {
     let mut file_id: Option<String> = None;
     let mut file_bytes: Option<bytes::Bytes> = None;
+    let mut format: Option<String> = None;
+    let mut annotation_bytes: Option<bytes::Bytes> = None;
+    let mut canonicalize = false;
 
     // --- extract multipart fields ---
     while let Some(field) = match multipart.next_field().await {
@@ -33,6 +58,9 @@ pub async fn post_ocpt(mut multipart: Multipart) -> Response {
         match field.name().unwrap_or("") {
             "file_id" => file_id = Some(field.text().await.unwrap_or_default()),
             "file"   => file_bytes = Some(field.bytes().await.unwrap_or_default()),
+            "format" => format = Some(field.text().await.unwrap_or_default()),
+            "annotations" => annotation_bytes = Some(field.bytes().await.unwrap_or_default()),
+            "canonicalize" => canonicalize = field.text().await.unwrap_or_default().trim() == "true",
             _ => {}
         }
     }
@@ -42,54 +70,66 @@ pub async fn post_ocpt(mut multipart: Multipart) -> Response {
         _ => return (StatusCode::BAD_REQUEST, "Missing file or fileId").into_response(),
     };
 
-    // --- parse JSON ---
     let text = match str::from_utf8(&bytes) {
         Ok(t) => t,
         Err(e) => return (StatusCode::BAD_REQUEST, format!("File not UTF-8: {e}")).into_response(),
     };
-    let value: Value = match serde_json::from_str(text) {
-        Ok(v) => v,
-        Err(e) => return (StatusCode::BAD_REQUEST, format!("Invalid JSON: {e}")).into_response(),
+    let format = match format.as_deref().map(str::trim).filter(|f| !f.is_empty()) {
+        None => OcptImportFormat::detect(text),
+        Some(name) => match OcptImportFormat::from_name(name) {
+            Some(f) => f,
+            None => return (StatusCode::BAD_REQUEST, format!("Unknown OCPT format: {name}")).into_response(),
+        },
     };
 
+    // --- normalize to backend OCPT ---
+    let parsed = match format {
+        OcptImportFormat::Ptml => {
+            let Some(annotation_bytes) = annotation_bytes else {
+                return (StatusCode::BAD_REQUEST, "PTML upload needs an annotations file").into_response();
+            };
+            let annotations = match str::from_utf8(&annotation_bytes)
+                .map_err(anyhow::Error::from)
+                .and_then(parse_annotations)
+            {
+                Ok(a) => a,
+                Err(e) => return (StatusCode::BAD_REQUEST, format!("{e:#}")).into_response(),
+            };
+            match import_ptml(text, &annotations) {
+                Ok(ocpt) => ParsedOcpt { ocpt, source: "ptml", declared_ob_types: None },
+                Err(e) => return (StatusCode::BAD_REQUEST, format!("Failed to import PTML: {e:#}")).into_response(),
+            }
+        }
+        OcptImportFormat::Json | OcptImportFormat::Pm4py => {
+            let value: Value = match serde_json::from_str(text) {
+                Ok(v) => v,
+                Err(e) => return (StatusCode::BAD_REQUEST, format!("Invalid JSON: {e}")).into_response(),
+            };
+            match parse_ocpt_json(value, format) {
+                Ok(parsed) => parsed,
+                Err(msg) => return (StatusCode::BAD_REQUEST, msg).into_response(),
+            }
+        }
+    };
+    let ParsedOcpt { ocpt: mut ocpt_backend, source, declared_ob_types } = parsed;
+    if canonicalize {
+        ocpt_backend.canonicalize();
+    }
+    let validation_errors = validate_ocpt(&ocpt_backend, declared_ob_types.as_ref());
+    if matches!(source, "ptml" | "pm4py") && !validation_errors.is_empty() {
+        let resp = serde_json::json!({
+            "error": format!("Imported {source} tree is not a valid OCPT"),
+            "validation_errors": validation_errors,
+        });
+        return (StatusCode::UNPROCESSABLE_ENTITY, Json(resp)).into_response();
+    }
+
     // --- ensure ./temp exists ---
     if let Err(e) = ensure_temp_dir().await {
         return (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to prepare storage: {e}"))
             .into_response();
     }
 
-    // --- normalize to backend OCPT ---
-    // 1) Try backend shape directly
-    let ocpt_backend: OCPT = match serde_json::from_value::<OCPT>(value.clone()) {
-        Ok(be) => be,
-        Err(be_err) => {
-            // 2) Fallback: try frontend shape and convert
-            match serde_json::from_value::<OcptFE>(value.clone()) {
-                Ok(front) => match frontend_to_backend(front) {
-                    Ok(be) => be,
-                    Err(conv_err) => {
-                        return (
-                            StatusCode::BAD_REQUEST,
-                            format!("Failed to convert FE OCPT -> BE OCPT: {conv_err}"),
-                        )
-                            .into_response()
-                    }
-                },
-                Err(fe_err) => {
-                    // Neither backend nor frontend matched
-                    return (
-                        StatusCode::BAD_REQUEST,
-                        format!(
-                            "Unknown OCPT structure (not backend nor frontend). \
-                             Backend parse error: {be_err}; Frontend parse error: {fe_err}"
-                        ),
-                    )
-                        .into_response()
-                }
-            }
-        }
-    };
-
     // --- persist normalized backend OCPT ---
     let path = format!("./temp/ocpt_{id}.json");
     let pretty = match serde_json::to_string_pretty(&ocpt_backend) {
@@ -109,18 +149,68 @@ pub async fn post_ocpt(mut multipart: Multipart) -> Response {
         )
             .into_response();
     }
+    // A new tree starts a new version history
+    if let Err(e) = remove_history(&id) {
+        eprintln!("❌ remove OCPT history failed: {e:?}");
+    }
+    state.invalidate(&id);
 
     // --- response ---
     let resp = serde_json::json!({
         "status": "ok",
         "kind": "ocpt",
         "normalized": true,
+        "source": source,
+        "canonicalized": canonicalize,
         "saved_as": path,
-        "is_valid": ocpt_backend.is_valid(),
+        "is_valid": validation_errors.is_empty(),
+        "validation_errors": validation_errors,
     });
     (StatusCode::OK, Json(resp)).into_response()
 }
 
+/// An uploaded OCPT normalized to the backend shape
+struct ParsedOcpt {
+    ocpt: OCPT,
+    /// `backend`, `frontend`, `ptml` or `pm4py`
+    source: &'static str,
+    /// The `ots` of a frontend OCPT
+    declared_ob_types: Option<HashSet<String>>,
+}
+
+/// Normalizes an uploaded OCPT JSON. `Json` tries the backend shape, then the frontend shape,
+/// then PM4Py's representation.
+fn parse_ocpt_json(value: Value, format: OcptImportFormat) -> Result<ParsedOcpt, String> {
+    let pm4py = |ocpt| ParsedOcpt { ocpt, source: "pm4py", declared_ob_types: None };
+    if format == OcptImportFormat::Pm4py {
+        return import_pm4py(value)
+            .map(pm4py)
+            .map_err(|e| format!("Failed to import PM4Py OCPT: {e:#}"));
+    }
+    // 1) Try backend shape directly
+    let be_err = match serde_json::from_value::<OCPT>(value.clone()) {
+        Ok(ocpt) => return Ok(ParsedOcpt { ocpt, source: "backend", declared_ob_types: None }),
+        Err(e) => e,
+    };
+    // 2) Fallback: try frontend shape and convert
+    let fe_err = match serde_json::from_value::<OcptFE>(value.clone()) {
+        Ok(front) => {
+            let declared_ob_types = Some(front.ots.iter().cloned().collect());
+            return frontend_to_backend(front)
+                .map(|ocpt| ParsedOcpt { ocpt, source: "frontend", declared_ob_types })
+                .map_err(|conv_err| format!("Failed to convert FE OCPT -> BE OCPT: {conv_err}"));
+        }
+        Err(e) => e,
+    };
+    // 3) Fallback: PM4Py representation
+    import_pm4py(value).map(pm4py).map_err(|pm4py_err| {
+        format!(
+            "Unknown OCPT structure (not backend, frontend nor PM4Py). \
+             Backend parse error: {be_err}; Frontend parse error: {fe_err}; PM4Py parse error: {pm4py_err:#}"
+        )
+    })
+}
+
 async fn ensure_temp_dir() -> std::io::Result<()> {
     let dir = PathBuf::from("./temp");
     if !dir.exists() {
@@ -129,69 +219,93 @@ async fn ensure_temp_dir() -> std::io::Result<()> {
     Ok(())
 }
 
-// Helper: read a backend OCPT from disk and convert to the FE shape.
-async fn read_ocpt_as_frontend(path: &str) -> Result<OcptFE, String> {
-    let content = fs::read_to_string(path)
-        .await
-        .map_err(|e| format!("read {}: {e}", path))?;
-
-    // 1) Try FE first
-    if let Ok(fe) = serde_json::from_str::<OcptFE>(&content) {
-        return Ok(fe);
-    }
-
-    // 2) Fallback to BE → FE
-    let be: OCPT = serde_json::from_str(&content)
-        .map_err(|e| format!("parse backend OCPT {}: {e}", path))?;
-
-    if !be.is_valid() {
-        return Err("backend OCPT failed is_valid()".to_string());
-    }
-
-    Ok(backend_to_frontend(&be))
+#[derive(Deserialize)]
+pub struct OcptExportQuery {
+    /// Download the stored OCPT as `json`, `ptml`, `pnml` or `bpmn` (default: the frontend JSON
+    /// response, mining the OCPT if needed)
+    format: Option<String>,
+    /// PTML only: the object type to flatten the OCPT onto (default: all object types)
+    object_type: Option<String>,
+    /// Mining only: `exact` or `optimised` (default) cut detection
+    cuts: Option<String>,
 }
 
-pub async fn get_ocpt(Path(file_id): Path<String>) -> impl IntoResponse {
+pub async fn get_ocpt(
+    State(state): State<AppState>,
+    Path(file_id): Path<String>,
+    Query(query): Query<OcptExportQuery>,
+) -> impl IntoResponse {
     println!("📥 GET /v1/objects/ocpt/{}", file_id);
 
-    let ocpt_path = format!("./temp/ocpt_{}.json", file_id);
-    let v2_path   = format!("./temp/ocel_v2_{}.json", file_id);
+    let format = match query.format.as_deref() {
+        None => None,
+        Some(name) => match OcptFormat::from_name(name) {
+            Some(f) => Some(f),
+            None => return (StatusCode::BAD_REQUEST, format!("Unknown export format: {name}")).into_response(),
+        },
+    };
+    let cut_finder = match query.cuts.as_deref() {
+        None => CutFinder::default(),
+        Some(name) => match CutFinder::from_name(name) {
+            Some(finder) => finder,
+            None => return (StatusCode::BAD_REQUEST, format!("Unknown cut finder: {name}")).into_response(),
+        },
+    };
+    if let Some(format) = format {
+        return export_stored_ocpt(&state, &file_id, format, query.object_type.as_deref()).await;
+    }
 
-    // 1) OCPT already exists → load backend struct, convert, return FE shape (keep same id)
-    if FsPath::new(&ocpt_path).exists() {
-        match read_ocpt_as_frontend(&ocpt_path).await {
-            Ok(frontend_ocpt) => {
-                let payload = serde_json::json!({
-                    "file_id": file_id,      // existing mined OCPT id
-                    "ocpt": frontend_ocpt
-                });
-                return (StatusCode::OK, Json(payload)).into_response();
-            }
-            Err(e) => {
-                eprintln!("❌ convert stored OCPT failed: {}", e);
-                return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to convert stored OCPT to frontend format").into_response();
-            }
+    // 1) OCPT already exists → take the cached backend struct, convert, return FE shape (keep same id)
+    match state.ocpt(&file_id).await {
+        Ok(Some(ocpt)) => {
+            let payload = serde_json::json!({
+                "file_id": file_id,      // existing mined OCPT id
+                "ocpt": backend_to_frontend(&ocpt)
+            });
+            return (StatusCode::OK, Json(payload)).into_response();
+        }
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("❌ load stored OCPT failed: {e:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Stored file is not a valid OCPT").into_response();
         }
     }
 
     // 2) Have OCEL v2 → generate NEW OCPT (uuidv4), convert, return with new id
-    if FsPath::new(&v2_path).exists() {
-        println!("🛠️  Generating OCPT from ocel_v2_{}.json", file_id);
+    let ocel = match state.ocel(&file_id).await {
+        Ok(ocel) => ocel,
+        Err(e) => {
+            eprintln!("❌ load stored OCEL failed: {e:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Stored file is not a valid OCEL").into_response();
+        }
+    };
+    if let Some(ocel) = ocel {
+        println!("🛠️  Generating OCPT from stored OCEL v2 {}", file_id);
 
         // Your updated generator returns the new uuidv4
-        let new_file_id  = generate_ocpt_from_fileid(&file_id);
-        let new_ocpt_path = format!("./temp/ocpt_{}.json", new_file_id);
-
-        match read_ocpt_as_frontend(&new_ocpt_path).await {
-            Ok(frontend_ocpt) => {
+        let options = MiningOptions { cut_finder };
+        let mined = match tokio::task::spawn_blocking(move || generate_ocpt_from_ocel(&ocel, &options)).await {
+            Ok(mined) => mined,
+            Err(e) => {
+                eprintln!("❌ OCPT generation failed: {e:?}");
+                return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to generate OCPT").into_response();
+            }
+        };
+        match state.ocpt(&mined.file_id).await {
+            Ok(Some(ocpt)) => {
                 let payload = serde_json::json!({
-                    "file_id": new_file_id,  // the new uuidv4 for the freshly mined OCPT
-                    "ocpt": frontend_ocpt
+                    "file_id": mined.file_id,  // the new uuidv4 for the freshly mined OCPT
+                    "content_hash": mined.content_hash,
+                    "ocpt": backend_to_frontend(&ocpt)
                 });
                 return (StatusCode::OK, Json(payload)).into_response();
             }
+            Ok(None) => {
+                eprintln!("❌ generated OCPT {} was not stored", mined.file_id);
+                return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to store generated OCPT").into_response();
+            }
             Err(e) => {
-                eprintln!("❌ convert newly generated OCPT failed: {}", e);
+                eprintln!("❌ load newly generated OCPT failed: {e:?}");
                 return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to convert generated OCPT to frontend format").into_response();
             }
         }
@@ -203,11 +317,125 @@ pub async fn get_ocpt(Path(file_id): Path<String>) -> impl IntoResponse {
     (StatusCode::NOT_FOUND, msg).into_response()
 }
 
-pub async fn delete_ocpt(Path(file_id): Path<String>) -> impl IntoResponse {
+/// Exports the stored OCPT as frontend JSON, PTML, PNML or BPMN and serves it as a download.
+/// PTML without `object_type` is a JSON object mapping each object type to its PTML document.
+async fn export_stored_ocpt(
+    state: &AppState,
+    file_id: &str,
+    format: OcptFormat,
+    object_type: Option<&str>,
+) -> Response {
+    let ocpt = match state.ocpt(file_id).await {
+        Ok(Some(ocpt)) => ocpt,
+        Ok(None) => {
+            return (StatusCode::NOT_FOUND, format!("No OCPT found for fileId: {file_id}")).into_response()
+        }
+        Err(e) => {
+            eprintln!("❌ load stored OCPT failed: {e:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Stored file is not a valid OCPT").into_response();
+        }
+    };
+
+    let name = format!("ocpt_{file_id}");
+    let (file_name, document) = match (format, object_type) {
+        (OcptFormat::Ptml, Some(ot)) => {
+            if !ocpt_object_types(&ocpt).contains(ot) {
+                return (StatusCode::BAD_REQUEST, format!("Unknown object type: {ot}")).into_response();
+            }
+            (format!("{name}_{ot}"), export_ptml(&flatten_ocpt(&ocpt, ot), ot))
+        }
+        (OcptFormat::Ptml, None) => {
+            let per_type: serde_json::Map<String, Value> = ocpt_object_types(&ocpt)
+                .into_iter()
+                .map(|ot| {
+                    let ptml = export_ptml(&flatten_ocpt(&ocpt, &ot), &ot);
+                    (ot, Value::String(ptml))
+                })
+                .collect();
+            return (StatusCode::OK, Json(per_type)).into_response();
+        }
+        (OcptFormat::Pnml, _) => (name.clone(), export_pnml(&ocpt, &name)),
+        (OcptFormat::Bpmn, _) => (name.clone(), export_bpmn(&ocpt, &name)),
+        (OcptFormat::Json, _) => match serde_json::to_string_pretty(&backend_to_frontend(&ocpt)) {
+            Ok(json) => (name, json),
+            Err(e) => {
+                eprintln!("❌ serialize OCPT failed: {e:?}");
+                return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to export OCPT").into_response();
+            }
+        },
+    };
+
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                content_disposition("attachment", &format!("{file_name}.{}", format.extension())),
+            ),
+        ],
+        document,
+    )
+        .into_response()
+}
+
+#[derive(Deserialize)]
+pub struct FlattenedQuery {
+    /// Only this object type (default: all object types)
+    object_type: Option<String>,
+}
+
+/// GET /v1/ocpt/{file_id}/flattened?object_type=...
+/// -> the stored OCPT projected onto each object type as traditional process tree, as JSON
+///    tree and in PM4Py notation (see `core/ocpt/flatten.rs`)
+pub async fn get_ocpt_flattened(
+    State(state): State<AppState>,
+    Path(file_id): Path<String>,
+    Query(query): Query<FlattenedQuery>,
+) -> Response {
+    let ocpt = match state.ocpt(&file_id).await {
+        Ok(Some(ocpt)) => ocpt,
+        Ok(None) => {
+            return (StatusCode::NOT_FOUND, format!("No OCPT found for fileId: {file_id}")).into_response()
+        }
+        Err(e) => {
+            eprintln!("❌ load stored OCPT failed: {e:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Stored file is not a valid OCPT").into_response();
+        }
+    };
+
+    let object_types = ocpt_object_types(&ocpt);
+    let selected: Vec<String> = match query.object_type {
+        Some(ot) if !object_types.contains(&ot) => {
+            return (StatusCode::BAD_REQUEST, format!("Unknown object type: {ot}")).into_response();
+        }
+        Some(ot) => vec![ot],
+        None => object_types.into_iter().collect(),
+    };
+    let trees: serde_json::Map<String, Value> = selected
+        .into_iter()
+        .map(|ot| {
+            let tree = flatten_ocpt(&ocpt, &ot);
+            let flat = serde_json::json!({ "notation": tree.to_string(), "tree": tree });
+            (ot, flat)
+        })
+        .collect();
+
+    let resp = serde_json::json!({ "file_id": file_id, "object_types": trees });
+    (StatusCode::OK, Json(resp)).into_response()
+}
+
+pub async fn delete_ocpt(State(state): State<AppState>, Path(file_id): Path<String>) -> impl IntoResponse {
     println!("🗑️ DELETE /v1/objects/ocpt/{}", file_id);
     let ocpt_path = format!("./temp/ocpt_{}.json", file_id);
     match fs::remove_file(&ocpt_path).await {
-        Ok(_) => (StatusCode::NO_CONTENT, "Deleted file").into_response(),
+        Ok(_) => {
+            if let Err(e) = remove_history(&file_id) {
+                eprintln!("❌ remove OCPT history failed: {e:?}");
+            }
+            state.invalidate(&file_id);
+            (StatusCode::NO_CONTENT, "Deleted file").into_response()
+        }
         Err(e) => {
             eprintln!("❌ Failed to delete file {}: {}", ocpt_path, e);
             (StatusCode::INTERNAL_SERVER_ERROR, "Failed to delete file").into_response()
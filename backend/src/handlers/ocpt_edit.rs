@@ -0,0 +1,185 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+
+use crate::core::ocpt::edit::{apply_edit, OcptEdit};
+use crate::core::ocpt::history::{diff_ocpts, load_history, save_history, OcptHistory};
+use crate::core::ocpt::validation::validate_ocpt;
+use crate::core::struct_converters::ocpt_storage::{load_ocpt, save_ocpt};
+use crate::models::ocpt::OCPT;
+use crate::state::AppState;
+
+/// POST /v1/ocpt/{file_id}/edits  (JSON body: one edit tagged by `op`, see `core/ocpt/edit.rs`)
+/// -> applies the edit to the stored OCPT and records the result as new version. Edits that
+/// cannot be applied are rejected with 400, edits leaving an invalid tree with 422.
+pub async fn post_ocpt_edit(
+    State(state): State<AppState>,
+    Path(file_id): Path<String>,
+    Json(edit): Json<OcptEdit>,
+) -> Response {
+    let _guard = state.ocpt_edits.lock().await;
+    let state = state.clone();
+    run_blocking(move || {
+        let Some(mut history) = history_or_version0(&file_id)? else {
+            return Ok(no_ocpt(&file_id));
+        };
+        // Edit a copy of the latest version, so that a fresh history's version 0 and the edited
+        // tree share their node uuids
+        let Some(latest) = history.latest() else {
+            return Ok(no_ocpt(&file_id));
+        };
+        let mut ocpt: OCPT = serde_json::from_value(serde_json::to_value(&latest.ocpt)?)?;
+        let node = match apply_edit(&mut ocpt, &edit) {
+            Ok(node) => node,
+            Err(msg) => return Ok((StatusCode::BAD_REQUEST, msg).into_response()),
+        };
+        if !ocpt.is_valid() {
+            let resp = serde_json::json!({
+                "error": "The edit would leave an invalid OCPT",
+                "validation_errors": validate_ocpt(&ocpt, None),
+            });
+            return Ok((StatusCode::UNPROCESSABLE_ENTITY, Json(resp)).into_response());
+        }
+
+        save_ocpt(&file_id, &ocpt)?;
+        let tree = serde_json::to_value(&ocpt)?;
+        let version = history.push(Some(edit), ocpt);
+        save_history(&file_id, &history)?;
+        state.invalidate(&file_id);
+        let resp = serde_json::json!({
+            "file_id": file_id,
+            "version": version,
+            "node": node,
+            "ocpt": tree,
+        });
+        Ok((StatusCode::OK, Json(resp)).into_response())
+    })
+    .await
+}
+
+/// POST /v1/ocpt/{file_id}/undo
+/// -> drops the latest version and restores the previous one (409 if there is none)
+pub async fn post_ocpt_undo(State(state): State<AppState>, Path(file_id): Path<String>) -> Response {
+    let _guard = state.ocpt_edits.lock().await;
+    let state = state.clone();
+    run_blocking(move || {
+        let Some(mut history) = history_or_version0(&file_id)? else {
+            return Ok(no_ocpt(&file_id));
+        };
+        let Some(restored) = history.undo() else {
+            return Ok((StatusCode::CONFLICT, "Nothing to undo").into_response());
+        };
+        save_ocpt(&file_id, &restored.ocpt)?;
+        let resp = serde_json::json!({
+            "file_id": file_id,
+            "version": restored.version,
+            "ocpt": restored.ocpt,
+        });
+        save_history(&file_id, &history)?;
+        state.invalidate(&file_id);
+        Ok((StatusCode::OK, Json(resp)).into_response())
+    })
+    .await
+}
+
+/// GET /v1/ocpt/{file_id}/versions
+/// -> the versions of the stored OCPT with their edits (oldest first)
+pub async fn get_ocpt_versions(State(state): State<AppState>, Path(file_id): Path<String>) -> Response {
+    let _guard = state.ocpt_edits.lock().await;
+    run_blocking(move || {
+        let Some(history) = history_or_version0(&file_id)? else {
+            return Ok(no_ocpt(&file_id));
+        };
+        let versions: Vec<_> = history
+            .versions
+            .iter()
+            .map(|v| serde_json::json!({ "version": v.version, "created_at": v.created_at, "edit": v.edit }))
+            .collect();
+        let resp = serde_json::json!({ "file_id": file_id, "versions": versions });
+        Ok((StatusCode::OK, Json(resp)).into_response())
+    })
+    .await
+}
+
+/// GET /v1/ocpt/{file_id}/versions/{version}
+/// -> the OCPT (backend shape, with node uuids) as of `version`
+pub async fn get_ocpt_version(
+    State(state): State<AppState>,
+    Path((file_id, version)): Path<(String, u32)>,
+) -> Response {
+    let _guard = state.ocpt_edits.lock().await;
+    run_blocking(move || {
+        let Some(history) = history_or_version0(&file_id)? else {
+            return Ok(no_ocpt(&file_id));
+        };
+        let Some(v) = history.get(version) else {
+            return Ok((StatusCode::NOT_FOUND, format!("No version {version} of OCPT {file_id}")).into_response());
+        };
+        Ok((StatusCode::OK, Json(serde_json::json!({ "file_id": file_id, "version": v }))).into_response())
+    })
+    .await
+}
+
+#[derive(Deserialize)]
+pub struct DiffQuery {
+    /// Default: the version before `to`
+    from: Option<u32>,
+    /// Default: the latest version
+    to: Option<u32>,
+}
+
+/// GET /v1/ocpt/{file_id}/diff?from=a&to=b
+/// -> the nodes added, removed and changed between two versions
+pub async fn get_ocpt_diff(
+    State(state): State<AppState>,
+    Path(file_id): Path<String>,
+    Query(query): Query<DiffQuery>,
+) -> Response {
+    let _guard = state.ocpt_edits.lock().await;
+    run_blocking(move || {
+        let Some(history) = history_or_version0(&file_id)? else {
+            return Ok(no_ocpt(&file_id));
+        };
+        let latest = history.latest().map_or(0, |v| v.version);
+        let to = query.to.unwrap_or(latest);
+        let from = query.from.unwrap_or(to.saturating_sub(1));
+        let (Some(from_version), Some(to_version)) = (history.get(from), history.get(to)) else {
+            return Ok((StatusCode::NOT_FOUND, format!("OCPT {file_id} has versions 0 to {latest}")).into_response());
+        };
+        let resp = serde_json::json!({
+            "file_id": file_id,
+            "from": from,
+            "to": to,
+            "diff": diff_ocpts(&from_version.ocpt, &to_version.ocpt),
+        });
+        Ok((StatusCode::OK, Json(resp)).into_response())
+    })
+    .await
+}
+
+/// The history of the stored OCPT or, if it was never edited, a view with the stored OCPT as
+/// only version 0. Nothing is written here; the first applied edit stores both.
+fn history_or_version0(file_id: &str) -> anyhow::Result<Option<OcptHistory>> {
+    if let Some(history) = load_history(file_id)? {
+        return Ok(Some(history));
+    }
+    Ok(load_ocpt(file_id)?.map(OcptHistory::new))
+}
+
+fn no_ocpt(file_id: &str) -> Response {
+    (StatusCode::NOT_FOUND, format!("No OCPT found for fileId: {file_id}")).into_response()
+}
+
+async fn run_blocking(f: impl FnOnce() -> anyhow::Result<Response> + Send + 'static) -> Response {
+    match tokio::task::spawn_blocking(f).await.map_err(anyhow::Error::from).and_then(|r| r) {
+        Ok(resp) => resp,
+        Err(e) => {
+            eprintln!("❌ OCPT edit failed: {e:?}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to edit stored OCPT").into_response()
+        }
+    }
+}
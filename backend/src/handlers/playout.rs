@@ -0,0 +1,71 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+
+use crate::core::ocpt::playout::{playout_ocpt, PlayoutOptions};
+use crate::core::struct_converters::ocel_storage::store_ocel;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct PlayoutQuery {
+    /// Id under which the simulated OCEL is stored (default: a new uuid)
+    target_file_id: Option<String>,
+}
+
+/// POST /v1/ocpt/{file_id}/playout?target_file_id=...  (optional JSON body: `PlayoutOptions`,
+/// see `core/ocpt/playout.rs`)
+/// -> simulates an OCEL 2.0 log from the stored OCPT and stores it like an uploaded OCEL
+pub async fn post_ocpt_playout(
+    State(state): State<AppState>,
+    Path(file_id): Path<String>,
+    Query(query): Query<PlayoutQuery>,
+    options: Option<Json<PlayoutOptions>>,
+) -> Response {
+    let options = options.map(|Json(options)| options).unwrap_or_default();
+    if let Err(msg) = options.check() {
+        return (StatusCode::BAD_REQUEST, msg).into_response();
+    }
+    let ocpt = match state.ocpt(&file_id).await {
+        Ok(Some(ocpt)) => ocpt,
+        Ok(None) => return (StatusCode::NOT_FOUND, format!("No OCPT found for fileId: {file_id}")).into_response(),
+        Err(e) => {
+            eprintln!("❌ Failed to load OCPT {file_id}: {e:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to load stored OCPT").into_response();
+        }
+    };
+
+    let target = query.target_file_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let id = target.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        playout_ocpt(&ocpt, &options).map(|ocel| {
+            let counts = (ocel.events.len(), ocel.objects.len());
+            (store_ocel(&id, &ocel), counts)
+        })
+    })
+    .await;
+    match result {
+        Ok(Ok((Ok(_), (events, objects)))) => {
+            state.invalidate(&target);
+            let resp = serde_json::json!({
+                "file_id": target,
+                "source_ocpt": file_id,
+                "events": events,
+                "objects": objects,
+            });
+            (StatusCode::OK, Json(resp)).into_response()
+        }
+        Ok(Err(msg)) => (StatusCode::UNPROCESSABLE_ENTITY, msg).into_response(),
+        Ok(Ok((Err(e), _))) => {
+            eprintln!("❌ Failed to store simulated OCEL: {e:?}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to store simulated OCEL").into_response()
+        }
+        Err(e) => {
+            eprintln!("❌ OCPT playout failed: {e:?}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "OCPT playout failed").into_response()
+        }
+    }
+}
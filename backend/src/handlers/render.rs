@@ -0,0 +1,107 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+
+use crate::core::dfg::discovery::{discover_oc_dfg, DfgOptions};
+use crate::core::render::dfg_dot::oc_dfg_to_dot;
+use crate::core::render::ocpt_dot::ocpt_to_dot;
+use crate::core::render::RenderFormat;
+use crate::handlers::download::content_disposition;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct RenderQuery {
+    /// `svg` (default) or `dot`
+    format: Option<String>,
+}
+
+impl RenderQuery {
+    fn render_format(&self) -> Result<RenderFormat, String> {
+        match self.format.as_deref() {
+            None => Ok(RenderFormat::default()),
+            Some(name) => RenderFormat::from_name(name).ok_or_else(|| format!("Unknown render format: {name}")),
+        }
+    }
+}
+
+/// GET /v1/render/ocpt/{file_id}?format=svg|dot
+pub async fn render_ocpt(
+    State(state): State<AppState>,
+    Path(file_id): Path<String>,
+    Query(query): Query<RenderQuery>,
+) -> Response {
+    let format = match query.render_format() {
+        Ok(f) => f,
+        Err(msg) => return (StatusCode::BAD_REQUEST, msg).into_response(),
+    };
+    let ocpt = match state.ocpt(&file_id).await {
+        Ok(Some(ocpt)) => ocpt,
+        Ok(None) => return (StatusCode::NOT_FOUND, format!("No OCPT found for fileId: {file_id}")).into_response(),
+        Err(e) => {
+            eprintln!("❌ load stored OCPT failed: {e:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Stored file is not a valid OCPT").into_response();
+        }
+    };
+    let rendered = tokio::task::spawn_blocking(move || format.render(ocpt_to_dot(&ocpt))).await;
+    rendered_response(rendered, format, &format!("ocpt_{file_id}"))
+}
+
+/// GET /v1/render/dfg/{file_id}?format=svg|dot
+/// -> the object-centric DFG discovered from the stored OCEL; takes the options of
+/// `GET /v1/dfg/ocel/{file_id}` (object types, frequency thresholds)
+pub async fn render_dfg(
+    State(state): State<AppState>,
+    Path(file_id): Path<String>,
+    Query(query): Query<RenderQuery>,
+    Query(options): Query<DfgOptions>,
+) -> Response {
+    let format = match query.render_format() {
+        Ok(f) => f,
+        Err(msg) => return (StatusCode::BAD_REQUEST, msg).into_response(),
+    };
+    let locel = match state.linked_ocel(&file_id).await {
+        Ok(Some(locel)) => locel,
+        Ok(None) => return (StatusCode::NOT_FOUND, format!("No OCEL v2 file found for fileId: {file_id}")).into_response(),
+        Err(e) => {
+            eprintln!("❌ load stored OCEL failed: {e:?}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Stored file is not a valid OCEL").into_response();
+        }
+    };
+    if let Err(msg) = options.selected_object_types(&locel) {
+        return (StatusCode::BAD_REQUEST, msg).into_response();
+    }
+    let rendered = tokio::task::spawn_blocking(move || {
+        let dfg = discover_oc_dfg(&locel, &options).map_err(anyhow::Error::msg)?;
+        format.render(oc_dfg_to_dot(&dfg))
+    })
+    .await;
+    rendered_response(rendered, format, &format!("ocdfg_{file_id}"))
+}
+
+fn rendered_response(
+    rendered: Result<anyhow::Result<String>, tokio::task::JoinError>,
+    format: RenderFormat,
+    file_name: &str,
+) -> Response {
+    match rendered.map_err(anyhow::Error::from).and_then(|r| r) {
+        Ok(document) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, format.content_type().to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    content_disposition("inline", &format!("{file_name}.{}", format.extension())),
+                ),
+            ],
+            document,
+        )
+            .into_response(),
+        Err(e) => {
+            eprintln!("❌ render ({format:?}) failed: {e:?}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to render graph").into_response()
+        }
+    }
+}
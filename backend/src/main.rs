@@ -2,19 +2,118 @@ mod routes;
 mod handlers;
 mod models;
 mod core;
+mod state;
+#[cfg(test)]
+mod tests;
 
 use core::struct_converters::ocel_1_ocel_2_converter::convert_file;
+use core::struct_converters::ocel_2_ocel_1_converter::{
+    downgrade_file, AttributeCollapsePolicy, DowngradeOptions,
+};
+use core::render::RenderFormat;
+use core::render::dfg_dot::oc_dfg_to_dot;
+use core::render::ocpt_dot::ocpt_to_dot;
+use core::df2_miner::cuts::differential_run;
+use core::ocpt::playout::{playout_ocpt, PlayoutOptions};
+use core::struct_converters::ocel_formats::{export_ocel, import_ocel, OcelFormat};
+use core::struct_converters::ocel_1_ocel_2_converter::ConversionOptions;
+use core::struct_converters::ocpt_storage::parse_ocpt;
+use models::dfg::OCDirectlyFollowsGraph;
+use models::ocel::IndexLinkedOCEL;
 use std::path::Path;
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // If args are provided: run the converter and exit.
+    // Input and output formats are picked from the file extensions (.json / .xml / .sqlite).
     let mut args = std::env::args().skip(1);
-    if let Some(in_path) = args.next() {
+    if let Some(first) = args.next() {
+        // `downgrade <in> [out] [first|last|error]`: OCEL 2.0 -> OCEL 1.0 JSON
+        if first == "downgrade" {
+            let in_path = args
+                .next()
+                .ok_or_else(|| anyhow!("Usage: downgrade <in> [out] [first|last|error]"))?;
+            let out_path: String = args.next().unwrap_or_else(|| "out.jsonocel".to_string());
+            let policy = match args.next() {
+                Some(p) => AttributeCollapsePolicy::from_name(&p)
+                    .ok_or_else(|| anyhow!("Unknown object attribute policy: {p}"))?,
+                None => AttributeCollapsePolicy::default(),
+            };
+            let options = DowngradeOptions { object_attribute_policy: policy };
+            let report = downgrade_file(Path::new(&in_path), Path::new(&out_path), &options)?;
+            for warning in &report.warnings {
+                eprintln!("⚠️ {warning}");
+            }
+            println!("Wrote: {}", out_path);
+            return Ok(());
+        }
+
+        // `render ocpt|dfg <in> [out]`: OCPT (JSON) or OC-DFG of an OCEL 2.0 log as SVG or DOT
+        if first == "render" {
+            let usage = || anyhow!("Usage: render ocpt|dfg <in> [out.svg|out.dot]");
+            let kind = args.next().ok_or_else(usage)?;
+            let in_path = args.next().ok_or_else(usage)?;
+            let out_path: String = args.next().unwrap_or_else(|| "out.svg".to_string());
+            let format = Path::new(&out_path)
+                .extension()
+                .and_then(|ext| RenderFormat::from_name(&ext.to_string_lossy()))
+                .unwrap_or_default();
+            let bytes = std::fs::read(&in_path).with_context(|| format!("reading {in_path}"))?;
+            let dot = match kind.as_str() {
+                "ocpt" => ocpt_to_dot(&parse_ocpt(&String::from_utf8_lossy(&bytes))?),
+                "dfg" => {
+                    let ocel_format = OcelFormat::from_file_name(&in_path)
+                        .or_else(|| OcelFormat::sniff(&bytes))
+                        .ok_or_else(|| anyhow!("Unknown OCEL file format"))?;
+                    let (ocel, _) = import_ocel(&bytes, ocel_format, &ConversionOptions::default())?;
+                    let locel = IndexLinkedOCEL::from_ocel(ocel);
+                    oc_dfg_to_dot(&OCDirectlyFollowsGraph::create_from_locel(&locel))
+                }
+                _ => return Err(usage()),
+            };
+            std::fs::write(&out_path, format.render(dot)?).with_context(|| format!("writing {out_path}"))?;
+            println!("Wrote: {}", out_path);
+            return Ok(());
+        }
+
+        // `simulate <ocpt.json> [out] [options.json]`: synthetic OCEL 2.0 log played out from an OCPT
+        if first == "simulate" {
+            let usage = || anyhow!("Usage: simulate <ocpt.json> [out.json|out.xml|out.sqlite] [options.json]");
+            let in_path = args.next().ok_or_else(usage)?;
+            let out_path: String = args.next().unwrap_or_else(|| "out.ocel.json".to_string());
+            let options: PlayoutOptions = match args.next() {
+                Some(p) => serde_json::from_str(&std::fs::read_to_string(&p).with_context(|| format!("reading {p}"))?)
+                    .with_context(|| format!("parsing playout options {p}"))?,
+                None => PlayoutOptions::default(),
+            };
+            let ocpt = parse_ocpt(&std::fs::read_to_string(&in_path).with_context(|| format!("reading {in_path}"))?)?;
+            let ocel = playout_ocpt(&ocpt, &options).map_err(|e| anyhow!(e))?;
+            let format = OcelFormat::from_file_name(&out_path).unwrap_or(OcelFormat::Json);
+            std::fs::write(&out_path, export_ocel(&ocel, format)?).with_context(|| format!("writing {out_path}"))?;
+            println!("Wrote: {} ({} events, {} objects)", out_path, ocel.events.len(), ocel.objects.len());
+            return Ok(());
+        }
+
+        // `cut-diff [runs] [max_activities] [seed]`: cross-checks exact and optimised cut detection
+        if first == "cut-diff" {
+            let mut number = |default: u64| -> Result<u64> {
+                args.next().map_or(Ok(default), |n| n.parse().with_context(|| format!("not a number: {n}")))
+            };
+            let (runs, max_activities, seed) = (number(1000)?, number(6)?, number(0)?);
+            let disagreements = differential_run(runs as usize, max_activities as usize, seed);
+            for disagreement in &disagreements {
+                println!("{disagreement}");
+            }
+            println!("{} of {} random DFGs with disagreeing cut finders", disagreements.len(), runs);
+            return Ok(());
+        }
+
+        let in_path = first;
         let out_path: String = args.next().unwrap_or_else(|| "out.ocel.json".to_string());
-        convert_file(Path::new(&in_path), Path::new(&out_path))?;
+        let report = convert_file(Path::new(&in_path), Path::new(&out_path))?;
         println!("Wrote: {}", out_path);
+        println!("Conversion report: {}", serde_json::to_string_pretty(&report)?);
         return Ok(()); // done
     }
 
@@ -1,8 +1,8 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::{BTreeMap, HashMap};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Ocel1 {
     #[serde(rename = "ocel:global-log")]
     pub global_log: Value,
@@ -14,7 +14,7 @@ pub struct Ocel1 {
     pub objects: HashMap<String, Ocel1Object>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Ocel1Event {
     #[serde(rename = "ocel:activity")]
     pub activity: String,
@@ -29,7 +29,7 @@ pub struct Ocel1Event {
     pub vmap: BTreeMap<String, Value>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Ocel1Object {
     #[serde(rename = "ocel:type")]
     pub object_type: String,
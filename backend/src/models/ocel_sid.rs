@@ -1,6 +1,7 @@
 use serde::Deserialize;
 use std::collections::{HashMap, HashSet};
 use serde::Serialize;
+use process_mining::OCEL;
 
 // OCEL 2.0 structures
 #[derive(Debug, Deserialize)]
@@ -13,6 +14,81 @@ pub struct OcelJson {
     pub objects: Vec<Object>,
 }
 
+impl OcelJson {
+    /// Builds the miner's view of a loaded OCEL, with values and timestamps rendered as they
+    /// appear in OCEL 2.0 JSON
+    pub fn from_ocel(ocel: &OCEL) -> Self {
+        let definitions = |attrs: &[process_mining::ocel::ocel_struct::OCELTypeAttribute]| {
+            attrs
+                .iter()
+                .map(|a| AttributeDefinition { name: a.name.clone(), attr_type: a.value_type.clone() })
+                .collect()
+        };
+        OcelJson {
+            object_types: ocel
+                .object_types
+                .iter()
+                .map(|t| ObjectType { name: t.name.clone(), attributes: definitions(&t.attributes) })
+                .collect(),
+            event_types: ocel
+                .event_types
+                .iter()
+                .map(|t| EventType { name: t.name.clone(), attributes: definitions(&t.attributes) })
+                .collect(),
+            events: ocel
+                .events
+                .iter()
+                .map(|e| Event {
+                    id: e.id.clone(),
+                    activity: e.event_type.clone(),
+                    time: json_string(&e.time),
+                    attributes: Some(
+                        e.attributes
+                            .iter()
+                            .map(|a| Attribute { name: a.name.clone(), value: to_json(&a.value), time: None })
+                            .collect(),
+                    ),
+                    relationships: e
+                        .relationships
+                        .iter()
+                        .map(|r| Relationship { object_id: r.object_id.clone(), qualifier: r.qualifier.clone() })
+                        .collect(),
+                })
+                .collect(),
+            objects: ocel
+                .objects
+                .iter()
+                .map(|o| Object {
+                    id: o.id.clone(),
+                    object_type: o.object_type.clone(),
+                    attributes: Some(
+                        o.attributes
+                            .iter()
+                            .map(|a| Attribute {
+                                name: a.name.clone(),
+                                value: to_json(&a.value),
+                                time: Some(json_string(&a.time)),
+                            })
+                            .collect(),
+                    ),
+                })
+                .collect(),
+        }
+    }
+}
+
+fn to_json<T: Serialize>(v: &T) -> serde_json::Value {
+    serde_json::to_value(v).unwrap_or(serde_json::Value::Null)
+}
+
+/// The JSON string form of a value (e.g. a timestamp exactly as stored in OCEL 2.0 JSON)
+fn json_string<T: Serialize>(v: &T) -> String {
+    match to_json(v) {
+        serde_json::Value::String(s) => s,
+        other => other.to_string(),
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ObjectType {
     pub name: String,
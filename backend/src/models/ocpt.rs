@@ -213,6 +213,9 @@ pub enum OCPTOperatorType {
 pub struct OCPT {
     /// The root of the object-centric process tree
     pub root: OCPTNode,
+    /// Further fields of the frontend shape (e.g. layout metadata), kept for round trips
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl OCPT {
@@ -220,7 +223,7 @@ impl OCPT {
     /// Initializes the object-centric process tree with the given node as root
     ///
     pub fn new(root: OCPTNode) -> Self {
-        Self { root }
+        Self { root, extra: serde_json::Map::new() }
     }
 
     ///
@@ -357,6 +360,9 @@ pub struct OCPTOperator {
     pub operator_type: OCPTOperatorType,
     /// The children nodes of the operator node
     pub children: Vec<OCPTNode>,
+    /// Further fields of the frontend node (e.g. layout metadata), kept for round trips
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl OCPTOperator {
@@ -369,6 +375,7 @@ impl OCPTOperator {
             uuid: Uuid::new_v4(),
             operator_type,
             children: Vec::new(),
+            extra: serde_json::Map::new(),
         }
     }
 
@@ -1272,6 +1279,9 @@ pub struct OCPTLeaf {
     pub convergent_ob_types: HashSet<ObjectType>,
     /// The deficient object types of the leaf
     pub deficient_ob_types: HashSet<ObjectType>,
+    /// Further fields of the frontend node (e.g. layout metadata), kept for round trips
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl OCPTLeaf {
@@ -1288,6 +1298,7 @@ impl OCPTLeaf {
                 divergent_ob_types: HashSet::new(),
                 convergent_ob_types: HashSet::new(),
                 deficient_ob_types: HashSet::new(),
+                extra: serde_json::Map::new(),
             }
         } else {
             Self {
@@ -1297,6 +1308,7 @@ impl OCPTLeaf {
                 divergent_ob_types: HashSet::new(),
                 convergent_ob_types: HashSet::new(),
                 deficient_ob_types: HashSet::new(),
+                extra: serde_json::Map::new(),
             }
         }
     }
@@ -1402,27 +1414,43 @@ impl OCPTLeaf {
 
 
 /////////////////// frontend struct ////////////////
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OcptFE {
     /// Object Types
     pub ots: Vec<String>,
     /// OCPT Node
     pub hierarchy: HierarchyNode,
+    /// Any further fields, passed through to the backend OCPT and back
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum HierarchyNode {
     Operator {
         value: String,
+        /// The uuid of the backend node, fresh if missing
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        uuid: Option<Uuid>,
+        /// Maximum number of repetitions of a loop
+        #[serde(default, rename = "loopBound", skip_serializing_if = "Option::is_none")]
+        loop_bound: Option<u32>,
         children: Vec<HierarchyNode>,
+        /// Any further fields of the node (e.g. `id`, `isExpanded`)
+        #[serde(flatten)]
+        extra: serde_json::Map<String, serde_json::Value>,
     },
     Activity {
         value: ActivityValue,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        uuid: Option<Uuid>,
+        #[serde(flatten)]
+        extra: serde_json::Map<String, serde_json::Value>,
     },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ActivityValue {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub isSilent: Option<bool>,
@@ -1430,11 +1458,14 @@ pub struct ActivityValue {
     pub ots: Vec<ObjectTypeFE>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ObjectTypeFE {
     pub ot: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub exhibits: Option<Vec<String>>,
+    /// `false` if the object type only has exhibits but is not related to the activity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub related: Option<bool>,
 }
 
 ////////// sid ///////////////////////////
@@ -2,6 +2,7 @@ use axum::Router;
 use tower_http::cors::{CorsLayer, Any};
 use axum::http::Method;
 use axum::http::HeaderValue;
+use crate::state::AppState;
 pub mod v1;
 
 
@@ -13,5 +14,6 @@ pub fn create_routes() -> Router {
 
     Router::new()
         .nest("/v1", v1::router())
+        .with_state(AppState::from_env())
         .layer(cors)
 }
\ No newline at end of file
@@ -5,8 +5,9 @@ use axum::{
 
 };
 use crate::handlers::conformance::{get_conformance_ocpt_ocel, get_conformance_ocpt_ocpt};
+use crate::state::AppState;
 
-pub fn router() -> Router {
+pub fn router() -> Router<AppState> {
     Router::new()
         .route("/ocpt/{ocpt_id}/ocel/{ocel_id}", get(get_conformance_ocpt_ocel))
         .route("/ocpt_1/{ocpt_id_1}/ocpt_2/{ocpt_id_2}", get(get_conformance_ocpt_ocpt))
@@ -0,0 +1,11 @@
+use axum::{
+    Router,
+    routing::get,
+};
+use crate::handlers::dfg::get_oc_dfg;
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/ocel/{file_id}", get(get_oc_dfg))
+}
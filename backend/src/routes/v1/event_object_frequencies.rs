@@ -2,11 +2,14 @@ use axum::{
     Router,
     routing::{get, post},
 };
-use crate::handlers::event_object_frequencies::{get_event_object_frequencies, post_ocel_filter};
+use crate::handlers::event_object_frequencies::{get_event_object_frequencies, get_object_event_frequencies, get_sublog_suggestions, post_ocel_filter};
+use crate::state::AppState;
 
-pub fn router() -> Router {
+pub fn router() -> Router<AppState> {
     Router::new()
         .route("/ocel/{file_id}", get(get_event_object_frequencies))
+        .route("/ocel_object_event/{file_id}", get(get_object_event_frequencies))
+        .route("/ocel_suggestions/{file_id}", get(get_sublog_suggestions))
         .route("/ocel_filter/{file_id}", post(post_ocel_filter))
         
 }
\ No newline at end of file
@@ -0,0 +1,12 @@
+use axum::{
+    Router,
+    routing::get,
+};
+use crate::handlers::conformance::{get_language_ocel, get_language_ocpt};
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/ocpt/{file_id}", get(get_language_ocpt))
+        .route("/ocel/{file_id}", get(get_language_ocel))
+}
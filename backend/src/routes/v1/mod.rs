@@ -2,14 +2,23 @@ pub mod upload;
 pub mod objects;
 pub mod conformance;
 pub mod event_object_frequencies;
+pub mod render;
+pub mod dfg;
+pub mod ocpt;
+pub mod language;
 use axum::Router;
+use crate::state::AppState;
 
 
 
-pub fn router() -> Router {
+pub fn router() -> Router<AppState> {
     Router::new()
         .nest("/upload", upload::router())
         .nest("/objects", objects::router())
         .nest("/conformance", conformance::router())
         .nest("/event_object_frequencies", event_object_frequencies::router())
+        .nest("/render", render::router())
+        .nest("/dfg", dfg::router())
+        .nest("/ocpt", ocpt::router())
+        .nest("/language", language::router())
 }
\ No newline at end of file
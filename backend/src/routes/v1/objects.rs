@@ -4,13 +4,15 @@ use axum::{
 
 };
 use crate::handlers::ocpt::{get_ocpt,delete_ocpt};
-use crate::handlers::ocel::{get_ocel,delete_ocel};
+use crate::handlers::ocel::{get_ocel,get_ocel1,delete_ocel};
+use crate::state::AppState;
 
 
-pub fn router() -> Router {
+pub fn router() -> Router<AppState> {
     Router::new()
         .route("/ocel/{file_id}", get(get_ocel))
         .route("/ocpt/{file_id}", get(get_ocpt))
+        .route("/ocel1/{file_id}", get(get_ocel1))
         .route("/ocel/{file_id}", delete(delete_ocel))
         .route("/ocpt/{file_id}", delete(delete_ocpt))
 }
\ No newline at end of file
@@ -0,0 +1,19 @@
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use crate::handlers::ocpt::get_ocpt_flattened;
+use crate::handlers::playout::post_ocpt_playout;
+use crate::handlers::ocpt_edit::{get_ocpt_diff, get_ocpt_version, get_ocpt_versions, post_ocpt_edit, post_ocpt_undo};
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/{file_id}/edits", post(post_ocpt_edit))
+        .route("/{file_id}/undo", post(post_ocpt_undo))
+        .route("/{file_id}/versions", get(get_ocpt_versions))
+        .route("/{file_id}/versions/{version}", get(get_ocpt_version))
+        .route("/{file_id}/diff", get(get_ocpt_diff))
+        .route("/{file_id}/playout", post(post_ocpt_playout))
+        .route("/{file_id}/flattened", get(get_ocpt_flattened))
+}
@@ -0,0 +1,12 @@
+use axum::{
+    Router,
+    routing::get,
+};
+use crate::handlers::render::{render_dfg, render_ocpt};
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/ocpt/{file_id}", get(render_ocpt))
+        .route("/dfg/{file_id}", get(render_dfg))
+}
@@ -6,10 +6,13 @@ use axum::{
 };
 use crate::handlers::ocel::{post_ocel_binary};
 use crate::handlers::ocpt::{post_ocpt};
+use crate::core::struct_converters::upload_stream::upload_limit_bytes;
+use crate::state::AppState;
 
-pub fn router() -> Router {
+pub fn router() -> Router<AppState> {
     Router::new()
-        .route("/ocel", post(post_ocel_binary).layer(DefaultBodyLimit::max(50_000 * 1024)),)
+        // OCEL uploads are streamed to disk; the form fields next to the file get 1 MB of slack
+        .route("/ocel", post(post_ocel_binary).layer(DefaultBodyLimit::max(upload_limit_bytes() + 1024 * 1024)),)
         .route("/ocpt", post(post_ocpt).layer(DefaultBodyLimit::max(50_000 * 1024)),)
         
 }
\ No newline at end of file
@@ -0,0 +1,300 @@
+//! Shared application state: an LRU cache of parsed logs and models.
+//!
+//! Handlers get parsed [`OCEL`]s, [`IndexLinkedOCEL`]s and [`OCPT`]s through [`AppState`]
+//! instead of reading and parsing the stored files on every request. Entries are evicted least
+//! recently used first once their estimated size exceeds the memory budget, and all entries of
+//! an id are invalidated when it is uploaded or deleted.
+use anyhow::Result;
+use lru::LruCache;
+use std::mem::size_of;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::core::struct_converters::ocel_storage::load_ocel_async;
+use crate::core::struct_converters::ocpt_storage::load_ocpt;
+use crate::models::ocel::{
+    IndexLinkedOCEL, OCELAttributeValue, OCELEvent, OCELEventAttribute, OCELObject,
+    OCELObjectAttribute, OCELRelationship, OCEL,
+};
+use crate::models::ocpt::OCPT;
+
+/// Environment variable overriding the memory budget of the cache (in MB)
+pub const CACHE_BUDGET_ENV: &str = "OCEL_CACHE_BUDGET_MB";
+
+/// Memory budget of the cache (in MB) if [`CACHE_BUDGET_ENV`] is not set
+pub const DEFAULT_CACHE_BUDGET_MB: usize = 512;
+
+/// The memory budget of the cache in bytes, read from [`CACHE_BUDGET_ENV`]
+pub fn cache_budget_bytes() -> usize {
+    std::env::var(CACHE_BUDGET_ENV)
+        .ok()
+        .and_then(|mb| mb.trim().parse::<usize>().ok())
+        .unwrap_or(DEFAULT_CACHE_BUDGET_MB)
+        .saturating_mul(1024 * 1024)
+}
+
+/// State shared by all handlers
+#[derive(Clone)]
+pub struct AppState {
+    pub cache: Arc<ArtifactCache>,
+    /// Serializes edits of stored OCPTs and their version histories
+    pub ocpt_edits: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl AppState {
+    /// State with a cache budget read from [`CACHE_BUDGET_ENV`]
+    pub fn from_env() -> Self {
+        Self {
+            cache: Arc::new(ArtifactCache::new(cache_budget_bytes())),
+            ocpt_edits: Arc::new(tokio::sync::Mutex::new(())),
+        }
+    }
+
+    /// The OCEL stored under `id`, or `None` if there is none
+    pub async fn ocel(&self, id: &str) -> Result<Option<Arc<OCEL>>> {
+        let key = ArtifactKey::new(ArtifactKind::Ocel, id);
+        if let Some(CachedArtifact::Ocel(ocel)) = self.cache.get(&key) {
+            return Ok(Some(ocel));
+        }
+        let generation = self.cache.generation();
+        let Some(ocel) = load_ocel_async(id).await? else {
+            return Ok(None);
+        };
+        let ocel = Arc::new(ocel);
+        let size = estimate_ocel_bytes(&ocel);
+        self.cache.insert(key, CachedArtifact::Ocel(ocel.clone()), size, generation);
+        Ok(Some(ocel))
+    }
+
+    /// The OCEL stored under `id`, indexed for linked access, or `None` if there is none
+    pub async fn linked_ocel(&self, id: &str) -> Result<Option<Arc<IndexLinkedOCEL>>> {
+        let key = ArtifactKey::new(ArtifactKind::LinkedOcel, id);
+        if let Some(CachedArtifact::LinkedOcel(locel)) = self.cache.get(&key) {
+            return Ok(Some(locel));
+        }
+        let generation = self.cache.generation();
+        let Some(ocel) = self.ocel(id).await? else {
+            return Ok(None);
+        };
+        // Owns a copy of the OCEL plus id and relationship indices
+        let size = 2 * estimate_ocel_bytes(&ocel);
+        let locel = tokio::task::spawn_blocking(move || IndexLinkedOCEL::from_ocel((*ocel).clone())).await?;
+        let locel = Arc::new(locel);
+        self.cache.insert(key, CachedArtifact::LinkedOcel(locel.clone()), size, generation);
+        Ok(Some(locel))
+    }
+
+    /// The OCPT stored under `id` (as backend OCPT), or `None` if there is none
+    pub async fn ocpt(&self, id: &str) -> Result<Option<Arc<OCPT>>> {
+        let key = ArtifactKey::new(ArtifactKind::Ocpt, id);
+        if let Some(CachedArtifact::Ocpt(ocpt)) = self.cache.get(&key) {
+            return Ok(Some(ocpt));
+        }
+        let generation = self.cache.generation();
+        let owned_id = id.to_string();
+        let Some(ocpt) = tokio::task::spawn_blocking(move || load_ocpt(&owned_id)).await?? else {
+            return Ok(None);
+        };
+        let ocpt = Arc::new(ocpt);
+        // The serialized model is a reasonable proxy for its size in memory
+        let size = serde_json::to_vec(&*ocpt).map_or(0, |v| v.len());
+        self.cache.insert(key, CachedArtifact::Ocpt(ocpt.clone()), size, generation);
+        Ok(Some(ocpt))
+    }
+
+    /// Drops everything cached for `id`; call after the stored artifact was written or deleted
+    pub fn invalidate(&self, id: &str) {
+        self.cache.invalidate(id);
+    }
+}
+
+/// Kind of a cached artifact
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArtifactKind {
+    Ocel,
+    LinkedOcel,
+    Ocpt,
+}
+
+impl ArtifactKind {
+    const ALL: [Self; 3] = [Self::Ocel, Self::LinkedOcel, Self::Ocpt];
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArtifactKey {
+    kind: ArtifactKind,
+    id: String,
+}
+
+impl ArtifactKey {
+    pub fn new(kind: ArtifactKind, id: &str) -> Self {
+        Self { kind, id: id.to_string() }
+    }
+}
+
+/// A parsed artifact, shared with the handlers using it
+#[derive(Clone)]
+pub enum CachedArtifact {
+    Ocel(Arc<OCEL>),
+    LinkedOcel(Arc<IndexLinkedOCEL>),
+    Ocpt(Arc<OCPT>),
+}
+
+struct CacheEntry {
+    artifact: CachedArtifact,
+    size: usize,
+}
+
+struct CacheInner {
+    entries: LruCache<ArtifactKey, CacheEntry>,
+    used: usize,
+    /// Bumped on every invalidation, so loads racing with an upload/delete are not cached
+    generation: u64,
+}
+
+/// LRU cache of parsed artifacts bounded by the sum of their estimated sizes
+pub struct ArtifactCache {
+    budget: usize,
+    inner: Mutex<CacheInner>,
+}
+
+impl ArtifactCache {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            inner: Mutex::new(CacheInner { entries: LruCache::unbounded(), used: 0, generation: 0 }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CacheInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The cached artifact, marking it as most recently used
+    pub fn get(&self, key: &ArtifactKey) -> Option<CachedArtifact> {
+        self.lock().entries.get(key).map(|e| e.artifact.clone())
+    }
+
+    /// Current generation, to be passed to [`Self::insert`] after loading
+    pub fn generation(&self) -> u64 {
+        self.lock().generation
+    }
+
+    /// Caches an artifact loaded at `generation`, evicting least recently used entries to stay
+    /// within the budget. Artifacts larger than the budget, or loaded before an invalidation,
+    /// are not cached.
+    pub fn insert(&self, key: ArtifactKey, artifact: CachedArtifact, size: usize, generation: u64) {
+        if size > self.budget {
+            return;
+        }
+        let mut inner = self.lock();
+        if inner.generation != generation {
+            return;
+        }
+        if let Some(old) = inner.entries.put(key, CacheEntry { artifact, size }) {
+            inner.used -= old.size;
+        }
+        inner.used += size;
+        while inner.used > self.budget {
+            match inner.entries.pop_lru() {
+                Some((_, evicted)) => inner.used -= evicted.size,
+                None => break,
+            }
+        }
+    }
+
+    /// Removes all artifacts cached for `id`
+    pub fn invalidate(&self, id: &str) {
+        let mut inner = self.lock();
+        inner.generation += 1;
+        for kind in ArtifactKind::ALL {
+            if let Some(old) = inner.entries.pop(&ArtifactKey::new(kind, id)) {
+                inner.used -= old.size;
+            }
+        }
+    }
+}
+
+/// Rough estimate of the heap and inline size of an OCEL
+pub fn estimate_ocel_bytes(ocel: &OCEL) -> usize {
+    let value = |v: &OCELAttributeValue| match v {
+        OCELAttributeValue::String(s) => s.len(),
+        _ => 0,
+    };
+    let relationships = |rels: &[OCELRelationship]| -> usize {
+        rels.iter()
+            .map(|r| size_of::<OCELRelationship>() + r.object_id.len() + r.qualifier.len())
+            .sum()
+    };
+    let events: usize = ocel
+        .events
+        .iter()
+        .map(|e| {
+            size_of::<OCELEvent>()
+                + e.id.len()
+                + e.event_type.len()
+                + e.attributes
+                    .iter()
+                    .map(|a| size_of::<OCELEventAttribute>() + a.name.len() + value(&a.value))
+                    .sum::<usize>()
+                + relationships(&e.relationships)
+        })
+        .sum();
+    let objects: usize = ocel
+        .objects
+        .iter()
+        .map(|o| {
+            size_of::<OCELObject>()
+                + o.id.len()
+                + o.object_type.len()
+                + o.attributes
+                    .iter()
+                    .map(|a| size_of::<OCELObjectAttribute>() + a.name.len() + value(&a.value))
+                    .sum::<usize>()
+                + relationships(&o.relationships)
+        })
+        .sum();
+    size_of::<OCEL>() + events + objects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_ocel() -> CachedArtifact {
+        CachedArtifact::Ocel(Arc::new(OCEL {
+            event_types: Vec::new(),
+            object_types: Vec::new(),
+            events: Vec::new(),
+            objects: Vec::new(),
+        }))
+    }
+
+    #[test]
+    fn test_lru_budget_and_invalidation() {
+        let cache = ArtifactCache::new(100);
+        let a = ArtifactKey::new(ArtifactKind::Ocel, "a");
+        let b = ArtifactKey::new(ArtifactKind::Ocel, "b");
+        let c = ArtifactKey::new(ArtifactKind::Ocpt, "c");
+
+        cache.insert(a.clone(), empty_ocel(), 40, cache.generation());
+        cache.insert(b.clone(), empty_ocel(), 40, cache.generation());
+        // `a` becomes most recently used, so `b` is evicted for `c`
+        assert!(cache.get(&a).is_some());
+        cache.insert(c.clone(), empty_ocel(), 40, cache.generation());
+        assert!(cache.get(&b).is_none());
+        assert!(cache.get(&c).is_some());
+        assert_eq!(cache.lock().used, 80);
+
+        // Larger than the whole budget: not cached
+        cache.insert(b.clone(), empty_ocel(), 101, cache.generation());
+        assert!(cache.get(&b).is_none());
+
+        // A load started before an invalidation is not cached
+        let stale = cache.generation();
+        cache.invalidate("a");
+        assert!(cache.get(&a).is_none());
+        cache.insert(a.clone(), empty_ocel(), 10, stale);
+        assert!(cache.get(&a).is_none());
+        assert_eq!(cache.lock().used, 40);
+    }
+}
@@ -0,0 +1,384 @@
+//! HTTP-level tests of the v1 routes, sending requests to the router in-process.
+//!
+//! Every test stores its files under fresh ids in `./temp` and deletes them again.
+use axum::body::{to_bytes, Body, Bytes};
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+use super::{assert_golden, read_fixture};
+use crate::core::ocpt::history::history_path;
+use crate::routes::create_routes;
+
+const BOUNDARY: &str = "test-boundary-7MA4YWxkTrZu0gW";
+
+struct Response {
+    status: StatusCode,
+    content_type: String,
+    content_disposition: String,
+    body: Bytes,
+}
+
+impl Response {
+    fn json(&self) -> Value {
+        serde_json::from_slice(&self.body)
+            .unwrap_or_else(|e| panic!("response is not JSON ({e}): {}", String::from_utf8_lossy(&self.body)))
+    }
+
+    fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+struct TestApp {
+    router: Router,
+}
+
+impl TestApp {
+    fn new() -> Self {
+        Self { router: create_routes() }
+    }
+
+    async fn send(&self, request: Request<Body>) -> Response {
+        let response = self.router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let content_disposition = response
+            .headers()
+            .get(header::CONTENT_DISPOSITION)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        Response { status, content_type, content_disposition, body }
+    }
+
+    async fn request(&self, method: Method, uri: &str, body: Option<Value>) -> Response {
+        let builder = Request::builder().method(method).uri(uri);
+        let request = match body {
+            Some(body) => builder
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => builder.body(Body::empty()),
+        };
+        self.send(request.unwrap()).await
+    }
+
+    async fn get(&self, uri: &str) -> Response {
+        self.request(Method::GET, uri, None).await
+    }
+
+    async fn post(&self, uri: &str, body: Option<Value>) -> Response {
+        self.request(Method::POST, uri, body).await
+    }
+
+    async fn delete(&self, uri: &str) -> Response {
+        self.request(Method::DELETE, uri, None).await
+    }
+
+    /// Posts a multipart form; fields with a file name are sent as files
+    async fn upload(&self, uri: &str, fields: &[(&str, Option<&str>, &str)]) -> Response {
+        let mut body = String::new();
+        for (name, file_name, content) in fields {
+            body.push_str(&format!("--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"{name}\""));
+            if let Some(file_name) = file_name {
+                body.push_str(&format!("; filename=\"{file_name}\"\r\nContent-Type: application/octet-stream"));
+            }
+            body.push_str(&format!("\r\n\r\n{content}\r\n"));
+        }
+        body.push_str(&format!("--{BOUNDARY}--\r\n"));
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(uri)
+            .header(header::CONTENT_TYPE, format!("multipart/form-data; boundary={BOUNDARY}"))
+            .body(Body::from(body))
+            .unwrap();
+        self.send(request).await
+    }
+
+    async fn upload_orders_ocel(&self, id: &str) {
+        let log = read_fixture("orders.jsonocel");
+        let resp = self
+            .upload("/v1/upload/ocel", &[("file_id", None, id), ("file", Some("orders.jsonocel"), &log)])
+            .await;
+        assert_eq!(resp.status, StatusCode::OK, "{}", resp.text());
+    }
+
+    async fn upload_orders_ocpt(&self, id: &str) {
+        let ocpt = read_fixture("orders_ocpt_frontend.json");
+        let resp = self
+            .upload("/v1/upload/ocpt", &[("file_id", None, id), ("file", Some("ocpt.json"), &ocpt)])
+            .await;
+        assert_eq!(resp.status, StatusCode::OK, "{}", resp.text());
+        assert_eq!(resp.json()["is_valid"], true);
+    }
+}
+
+fn unique_id(prefix: &str) -> String {
+    format!("test_{prefix}_{}", uuid::Uuid::new_v4().simple())
+}
+
+/// The uuid of the frontend node with the given activity
+fn activity_uuid(node: &Value, activity: &str) -> Option<String> {
+    if node["value"]["activity"] == activity {
+        return node["uuid"].as_str().map(str::to_string);
+    }
+    node["children"].as_array()?.iter().find_map(|child| activity_uuid(child, activity))
+}
+
+#[tokio::test]
+async fn test_http_ocel_routes() {
+    let app = TestApp::new();
+    let id = unique_id("ocel");
+    app.upload_orders_ocel(&id).await;
+
+    let resp = app.get(&format!("/v1/objects/ocel/{id}")).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(resp.json()["events"].as_array().unwrap().len(), 14);
+    let resp = app.get(&format!("/v1/objects/ocel/{id}?format=xml")).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert!(resp.content_type.contains("xml"), "{}", resp.content_type);
+    assert!(resp.content_disposition.ends_with(&format!("filename*=UTF-8''ocel_{id}.xml")));
+    let resp = app.get(&format!("/v1/objects/ocel1/{id}")).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(resp.json()["ocel:events"].as_object().unwrap().len(), 14);
+    assert!(resp.content_disposition.ends_with(&format!("filename*=UTF-8''ocel1_{id}.jsonocel")));
+    let resp = app.get(&format!("/v1/objects/ocel1/{id}?object_attribute_policy=first&report=true")).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    let wrapped = resp.json();
+    assert_eq!(wrapped["ocel"]["ocel:events"].as_object().unwrap().len(), 14);
+    assert!(wrapped["report"]["warnings"].is_array());
+
+    let resp = app.get(&format!("/v1/event_object_frequencies/ocel/{id}")).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_golden("orders_event_object_histograms", &resp.json());
+    let resp = app.get(&format!("/v1/event_object_frequencies/ocel_object_event/{id}")).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_golden("orders_object_event_histograms", &resp.json());
+    let resp = app.get(&format!("/v1/event_object_frequencies/ocel_suggestions/{id}")).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert!(resp.json().is_object() || resp.json().is_array());
+    let selection = json!({ "selections": [{ "name": "large_orders", "filters": [
+        { "event_type": "place order", "object_type": "item", "ranges": [[2, 3]] }
+    ]}]});
+    let resp = app.post(&format!("/v1/event_object_frequencies/ocel_filter/{id}"), Some(selection)).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(resp.json().as_array().unwrap().len(), 1);
+    let selection = json!({ "selections": [{ "filters": [
+        { "event_type": "place order", "object_type": "item", "direction": "sideways", "ranges": [[2, 3]] }
+    ]}]});
+    let resp = app.post(&format!("/v1/event_object_frequencies/ocel_filter/{id}"), Some(selection)).await;
+    assert_eq!(resp.status, StatusCode::BAD_REQUEST);
+
+    let resp = app.get(&format!("/v1/dfg/ocel/{id}")).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    let resp = app.get(&format!("/v1/render/dfg/{id}?format=dot")).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert!(resp.text().contains("digraph"));
+    assert!(resp.content_disposition.starts_with("inline; "));
+    let resp = app.get(&format!("/v1/language/ocel/{id}")).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(resp.json()["object_types"]["order"]["start"], json!(["place order"]));
+
+    assert_eq!(app.delete(&format!("/v1/objects/ocel/{id}")).await.status, StatusCode::NO_CONTENT);
+    assert_eq!(app.get(&format!("/v1/objects/ocel/{id}")).await.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_http_upload_validation_modes() {
+    // "o2" is referenced by no event: it keeps the `log_start` fallback time unless the upload
+    // asks for lenient validation, which removes it
+    let log = json!({
+        "ocel:global-log": { "ocel:attribute-names": ["customer"], "ocel:object-types": ["order"] },
+        "ocel:events": {
+            "e1": { "ocel:activity": "place order", "ocel:timestamp": "2024-01-01T08:00:00Z", "ocel:omap": ["o1"], "ocel:vmap": {} }
+        },
+        "ocel:objects": {
+            "o1": { "ocel:type": "order", "ocel:ovmap": {} },
+            "o2": { "ocel:type": "order", "ocel:ovmap": { "customer": "bob" } }
+        }
+    })
+    .to_string();
+    let options = r#"{"unreferenced_object_time":"log_start"}"#;
+    let app = TestApp::new();
+
+    for (validation, kept) in [(None, true), (Some("report"), true), (Some("lenient"), false)] {
+        let id = unique_id("validation");
+        let mut fields = vec![("file_id", None, id.as_str()), ("file", Some("log.jsonocel"), log.as_str())];
+        fields.push(("conversion_options", None, options));
+        if let Some(mode) = validation {
+            fields.push(("validation", None, mode));
+        }
+        let resp = app.upload("/v1/upload/ocel", &fields).await;
+        assert_eq!(resp.status, StatusCode::OK, "{}", resp.text());
+        let body = resp.json();
+        assert_eq!(body["conversion_report"]["unreferenced_objects"], 1);
+        assert_eq!(body["validation_report"]["counts"]["orphan_object"], 1);
+        assert_eq!(body["validation_report"]["repaired"], !kept);
+
+        let stored = app.get(&format!("/v1/objects/ocel/{id}")).await.json();
+        let o2 = stored["objects"].as_array().unwrap().iter().find(|o| o["id"] == "o2");
+        assert_eq!(o2.is_some(), kept, "{validation:?}");
+        if let Some(o2) = o2 {
+            assert!(o2["attributes"][0]["time"].as_str().unwrap().starts_with("2024-01-01T08:00:00"));
+        }
+        assert_eq!(app.delete(&format!("/v1/objects/ocel/{id}")).await.status, StatusCode::NO_CONTENT);
+    }
+}
+
+#[tokio::test]
+async fn test_http_reupload_over_legacy_ocel() {
+    use crate::core::struct_converters::ocel_storage::{binary_path, legacy_json_path};
+
+    let app = TestApp::new();
+    let id = unique_id("legacy");
+    std::fs::create_dir_all("./temp").unwrap();
+    std::fs::write(legacy_json_path(&id), serde_json::to_string(&super::orders_ocel()).unwrap()).unwrap();
+    assert_eq!(app.get(&format!("/v1/objects/ocel/{id}")).await.status, StatusCode::OK);
+
+    // The binary re-upload replaces the legacy JSON file, and deleting removes the log
+    app.upload_orders_ocel(&id).await;
+    assert!(!legacy_json_path(&id).exists());
+    std::fs::write(legacy_json_path(&id), "{}").unwrap();
+    assert_eq!(app.delete(&format!("/v1/objects/ocel/{id}")).await.status, StatusCode::NO_CONTENT);
+    assert!(!legacy_json_path(&id).exists() && !binary_path(&id).exists());
+}
+
+#[tokio::test]
+async fn test_http_ocpt_routes() {
+    let app = TestApp::new();
+    let id = unique_id("ocpt");
+    app.upload_orders_ocpt(&id).await;
+
+    let resp = app.get(&format!("/v1/objects/ocpt/{id}")).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    let hierarchy = resp.json()["ocpt"]["hierarchy"].clone();
+    let ship = activity_uuid(&hierarchy, "ship order").expect("uuid of ship order");
+    for format in ["json", "ptml", "pnml", "bpmn"] {
+        let resp = app.get(&format!("/v1/objects/ocpt/{id}?format={format}")).await;
+        assert_eq!(resp.status, StatusCode::OK, "{format}");
+        // PTML without object type is a JSON map of documents rather than a download
+        if format != "ptml" {
+            assert!(resp.content_disposition.contains(&format!("ocpt_{id}.{format}")), "{format}");
+        }
+    }
+    let resp = app.get(&format!("/v1/objects/ocpt/{id}?format=ptml&object_type=order")).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(
+        resp.content_disposition,
+        format!("attachment; filename=\"ocpt_{id}_order.ptml\"; filename*=UTF-8''ocpt_{id}_order.ptml")
+    );
+    let resp = app.get(&format!("/v1/render/ocpt/{id}?format=dot")).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert!(resp.text().contains("digraph"));
+    assert!(resp.content_disposition.starts_with("inline; "));
+    let resp = app.get(&format!("/v1/ocpt/{id}/flattened?object_type=order")).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(
+        resp.json()["object_types"]["order"]["notation"],
+        "->( 'place order', X( 'pay order', tau ), 'ship order' )"
+    );
+    let resp = app.get(&format!("/v1/language/ocpt/{id}")).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(resp.json()["object_types"]["order"]["end"], json!(["ship order"]));
+
+    // Edits and their history; reading it before the first edit does not persist anything
+    let resp = app.get(&format!("/v1/ocpt/{id}/versions")).await;
+    assert_eq!(resp.json()["versions"].as_array().unwrap().len(), 1);
+    assert_eq!(app.get(&format!("/v1/ocpt/{id}/diff?from=0&to=0")).await.status, StatusCode::OK);
+    assert!(!history_path(&id).exists());
+    let edit = json!({ "op": "wrap_in_operator", "node": ship, "operator": "loop", "loop_bound": 2 });
+    let resp = app.post(&format!("/v1/ocpt/{id}/edits"), Some(edit)).await;
+    assert_eq!(resp.status, StatusCode::OK, "{}", resp.text());
+    assert_eq!(resp.json()["version"], 1);
+    let resp = app.get(&format!("/v1/ocpt/{id}/versions")).await;
+    assert_eq!(resp.json()["versions"].as_array().unwrap().len(), 2);
+    assert_eq!(app.get(&format!("/v1/ocpt/{id}/versions/0")).await.status, StatusCode::OK);
+    let resp = app.get(&format!("/v1/ocpt/{id}/diff")).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert_eq!(resp.json()["diff"]["added"].as_object().unwrap().len(), 2);
+    let resp = app.post(&format!("/v1/ocpt/{id}/undo"), None).await;
+    assert_eq!(resp.json()["version"], 0);
+    assert_eq!(app.post(&format!("/v1/ocpt/{id}/undo"), None).await.status, StatusCode::CONFLICT);
+
+    // Playout and conformance of the simulated log
+    let sim = unique_id("sim");
+    let resp = app
+        .post(&format!("/v1/ocpt/{id}/playout?target_file_id={sim}"), Some(json!({ "cases": 5, "seed": 1 })))
+        .await;
+    assert_eq!(resp.status, StatusCode::OK, "{}", resp.text());
+    assert_eq!(resp.json()["file_id"], sim.as_str());
+    let huge = json!({ "default_objects_per_type": { "min": 4294967295u32, "max": 4294967295u32 } });
+    assert_eq!(app.post(&format!("/v1/ocpt/{id}/playout"), Some(huge)).await.status, StatusCode::BAD_REQUEST);
+    let resp = app.get(&format!("/v1/conformance/ocpt/{id}/ocel/{sim}")).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    assert!(resp.json()["fitness"].as_f64().unwrap() > 0.0);
+    let resp = app.get(&format!("/v1/conformance/ocpt_1/{id}/ocpt_2/{id}")).await;
+    assert_eq!(resp.status, StatusCode::OK);
+    let fitness = resp.json()["fitness"].as_f64().unwrap();
+    assert!((0.0..=1.0).contains(&fitness), "{fitness}");
+
+    assert_eq!(app.delete(&format!("/v1/objects/ocel/{sim}")).await.status, StatusCode::NO_CONTENT);
+    assert_eq!(app.delete(&format!("/v1/objects/ocpt/{id}")).await.status, StatusCode::NO_CONTENT);
+    assert_eq!(app.get(&format!("/v1/ocpt/{id}/versions")).await.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_http_mining() {
+    let app = TestApp::new();
+    let id = unique_id("mining");
+    app.upload_orders_ocel(&id).await;
+
+    // Without a stored OCPT the OCPT of the log is mined and stored under a new id
+    let resp = app.get(&format!("/v1/objects/ocpt/{id}")).await;
+    assert_eq!(resp.status, StatusCode::OK, "{}", resp.text());
+    let mined = resp.json()["file_id"].as_str().unwrap().to_string();
+    let content_hash = resp.json()["content_hash"].as_str().unwrap().to_string();
+    assert_ne!(mined, id);
+    assert_eq!(app.get(&format!("/v1/objects/ocpt/{mined}")).await.status, StatusCode::OK);
+    let resp = app.get(&format!("/v1/objects/ocpt/{id}?cuts=exact")).await;
+    assert_eq!(resp.status, StatusCode::OK, "{}", resp.text());
+    let exact = resp.json()["file_id"].as_str().unwrap().to_string();
+    assert_eq!(app.get(&format!("/v1/objects/ocpt/{id}?cuts=fastest")).await.status, StatusCode::BAD_REQUEST);
+
+    // Mining the same log again yields the same tree
+    let resp = app.get(&format!("/v1/objects/ocpt/{id}")).await;
+    let again = resp.json()["file_id"].as_str().unwrap().to_string();
+    assert_eq!(resp.json()["content_hash"], content_hash.as_str());
+    assert_eq!(app.delete(&format!("/v1/objects/ocpt/{again}")).await.status, StatusCode::NO_CONTENT);
+
+    assert_eq!(app.delete(&format!("/v1/objects/ocpt/{exact}")).await.status, StatusCode::NO_CONTENT);
+
+    assert_eq!(app.delete(&format!("/v1/objects/ocpt/{mined}")).await.status, StatusCode::NO_CONTENT);
+    assert_eq!(app.delete(&format!("/v1/objects/ocel/{id}")).await.status, StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn test_http_missing_files() {
+    let app = TestApp::new();
+    let id = unique_id("missing");
+    for uri in [
+        format!("/v1/objects/ocel/{id}"),
+        format!("/v1/objects/ocpt/{id}"),
+        format!("/v1/event_object_frequencies/ocel/{id}"),
+        format!("/v1/dfg/ocel/{id}"),
+        format!("/v1/render/ocpt/{id}"),
+        format!("/v1/language/ocel/{id}"),
+        format!("/v1/language/ocpt/{id}"),
+        format!("/v1/ocpt/{id}/versions"),
+        format!("/v1/ocpt/{id}/flattened"),
+    ] {
+        assert_eq!(app.get(&uri).await.status, StatusCode::NOT_FOUND, "{uri}");
+    }
+    assert_eq!(app.post(&format!("/v1/ocpt/{id}/playout"), None).await.status, StatusCode::NOT_FOUND);
+    assert_eq!(app.delete(&format!("/v1/objects/ocel/{id}")).await.status, StatusCode::NOT_FOUND);
+
+    let resp = app.upload("/v1/upload/ocel", &[("file_id", None, &id)]).await;
+    assert_eq!(resp.status, StatusCode::BAD_REQUEST);
+}
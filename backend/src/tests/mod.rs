@@ -0,0 +1,47 @@
+//! Tests across modules on the fixture logs and models in `tests/fixtures`:
+//! golden-output tests of the processing pipeline (`tests/golden`) and HTTP-level tests of the
+//! v1 routes.
+//!
+//! Run with `UPDATE_GOLDEN=1` to (re)write the golden files after an intended change of output.
+mod http;
+mod pipeline;
+
+use std::path::{Path, PathBuf};
+
+use crate::core::struct_converters::ocel_1_ocel_2_converter::{convert_ocel1_str_to_ocel, ConversionOptions};
+use crate::models::ocel::OCEL;
+
+pub fn fixture_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+}
+
+pub fn read_fixture(name: &str) -> String {
+    let path = fixture_path(name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("read fixture {}: {e}", path.display()))
+}
+
+/// The OCEL 1.0 fixture log converted to OCEL 2.0 with the default options
+pub fn orders_ocel() -> OCEL {
+    convert_ocel1_str_to_ocel(&read_fixture("orders.jsonocel"), &ConversionOptions::default())
+        .expect("convert fixture log")
+        .0
+}
+
+/// Compares `actual` with `tests/golden/{name}.json`, or writes it there if `UPDATE_GOLDEN` is set
+pub fn assert_golden(name: &str, actual: &serde_json::Value) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(format!("{name}.json"));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        let json = serde_json::to_string_pretty(actual).unwrap() + "\n";
+        std::fs::write(&path, json).unwrap_or_else(|e| panic!("write {}: {e}", path.display()));
+        return;
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("read {} (run with UPDATE_GOLDEN=1 to create it): {e}", path.display()));
+    let expected: serde_json::Value = serde_json::from_str(&expected).unwrap();
+    assert!(
+        &expected == actual,
+        "output differs from {} (run with UPDATE_GOLDEN=1 to update it):\n{}",
+        path.display(),
+        serde_json::to_string_pretty(actual).unwrap()
+    );
+}
@@ -0,0 +1,174 @@
+//! Golden-output tests of the processing pipeline on the fixture order log
+use serde_json::{json, Value};
+
+use super::{assert_golden, orders_ocel, read_fixture};
+use crate::core::conformance::object_centric_language_abstraction_struct::{
+    compute_fitness_precision, OCLanguageAbstraction,
+};
+use crate::core::df2_miner::cuts::CutFinder;
+use crate::core::df2_miner::ocpt_generator::{mine_ocpt_json, MiningOptions};
+use crate::core::event_object_frequencies::histogram_builder::{
+    build_event_object_histograms, build_object_event_histograms,
+};
+use crate::core::event_object_frequencies::histogram_filtering::filter_ocel_histograms;
+use crate::core::ocpt::reduce::canonical_string;
+use crate::core::struct_converters::ocel_1_ocel_2_converter::{convert_ocel1_str_to_ocel, ConversionOptions};
+use crate::core::struct_converters::ocpt_frontend_backend::{backend_to_frontend, frontend_to_backend};
+use crate::core::struct_converters::ocpt_storage::parse_ocpt;
+use crate::models::ocel::{IndexLinkedOCEL, OCEL};
+use crate::models::ocpt::{OCPTLeafLabel, OcptFE, OCPT};
+
+fn fixture_ocpt() -> OCPT {
+    let fe: OcptFE = serde_json::from_str(&read_fixture("orders_ocpt_frontend.json")).unwrap();
+    frontend_to_backend(fe).unwrap()
+}
+
+/// Removes the (random) node uuids from a serialized OCPT
+fn without_uuids(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.remove("uuid");
+            map.values_mut().for_each(without_uuids);
+        }
+        Value::Array(items) => items.iter_mut().for_each(without_uuids),
+        _ => {}
+    }
+}
+
+/// Event and object ids of a log
+fn ids(ocel: &OCEL) -> Value {
+    json!({
+        "events": ocel.events.iter().map(|e| &e.id).collect::<Vec<_>>(),
+        "objects": ocel.objects.iter().map(|o| &o.id).collect::<Vec<_>>(),
+    })
+}
+
+/// Fitness and precision, rounded so that the summation order does not matter
+fn conformance(log: &OCEL, model: &OCPT) -> Value {
+    let round = |x: f64| (x * 1e6).round() / 1e6;
+    let log = OCLanguageAbstraction::create_from_ocel(&IndexLinkedOCEL::from_ocel(log.clone()));
+    let model = OCLanguageAbstraction::create_from_oc_process_tree(model);
+    let (fitness, precision) = compute_fitness_precision(&log, &model);
+    json!({ "fitness": round(fitness), "precision": round(precision) })
+}
+
+#[test]
+fn test_golden_ocel1_conversion() {
+    let (ocel, report) =
+        convert_ocel1_str_to_ocel(&read_fixture("orders.jsonocel"), &ConversionOptions::default()).unwrap();
+    assert_eq!((ocel.events.len(), ocel.objects.len()), (14, 9));
+    assert_golden("orders_ocel2", &serde_json::to_value(&ocel).unwrap());
+    assert_golden("orders_conversion_report", &serde_json::to_value(&report).unwrap());
+}
+
+#[test]
+fn test_golden_histograms() {
+    let ocel = orders_ocel();
+    assert_golden("orders_event_object_histograms", &build_event_object_histograms(&ocel));
+    assert_golden("orders_object_event_histograms", &build_object_event_histograms(&ocel));
+}
+
+#[test]
+fn test_golden_filtering() {
+    let ocel = orders_ocel();
+    let selection = json!({ "selections": [
+        { "name": "large_orders", "filters": [
+            { "event_type": "place order", "object_type": "item", "ranges": [[2, 3]] }
+        ]},
+        { "name": "paid_orders", "filters": [
+            { "event_type": "pay order", "object_type": "order", "direction": "object_to_event", "ranges": [[1, 1]] }
+        ]}
+    ]});
+    let filtered = filter_ocel_histograms(&ocel, &selection.to_string()).unwrap();
+    assert_eq!(filtered.len(), 2);
+    assert_golden("orders_filtered", &Value::Array(filtered.iter().map(ids).collect()));
+}
+
+#[test]
+fn test_filtering_drops_events_without_relationships() {
+    let mut ocel = orders_ocel();
+    let mut lonely = ocel.events[0].clone();
+    lonely.id = "lonely".to_string();
+    lonely.event_type = "note".to_string();
+    lonely.relationships.clear();
+    ocel.events.push(lonely);
+
+    // Neither an event-to-object nor an object-to-event filter keeps an event without objects
+    for direction in ["event_to_object", "object_to_event"] {
+        let selection = json!({ "selections": [{ "filters": [
+            { "event_type": "place order", "object_type": "item", "direction": direction, "ranges": [[0, 10]] }
+        ]}]});
+        let filtered = filter_ocel_histograms(&ocel, &selection.to_string()).unwrap();
+        assert!(filtered[0].events.iter().all(|e| e.id != "lonely"), "{direction}");
+        assert!(filtered[0].events.iter().all(|e| !e.relationships.is_empty()), "{direction}");
+    }
+
+    let unknown = json!({ "selections": [{ "filters": [
+        { "event_type": "place order", "object_type": "item", "direction": "sideways", "ranges": [[0, 1]] }
+    ]}]});
+    assert!(filter_ocel_histograms(&ocel, &unknown.to_string()).is_err());
+}
+
+#[test]
+fn test_golden_mining() {
+    let mut mined = parse_ocpt(&mine_ocpt_json(&orders_ocel(), &MiningOptions::default())).unwrap();
+    assert!(mined.is_valid());
+    mined.canonicalize();
+    assert_golden("orders_mined_ocpt", &Value::String(canonical_string(&mined.root)));
+}
+
+#[test]
+fn test_mining_is_deterministic() {
+    let ocel = orders_ocel();
+    for cut_finder in [CutFinder::Optimised, CutFinder::Exact] {
+        let options = MiningOptions { cut_finder };
+        let first = mine_ocpt_json(&ocel, &options);
+        for _ in 0..5 {
+            assert_eq!(mine_ocpt_json(&ocel, &options), first, "{cut_finder:?}");
+        }
+    }
+}
+
+#[test]
+fn test_mining_with_exact_cuts() {
+    let options = MiningOptions { cut_finder: CutFinder::Exact };
+    let mined = parse_ocpt(&mine_ocpt_json(&orders_ocel(), &options)).unwrap();
+    assert!(mined.is_valid());
+    let mut activities: Vec<&String> = mined
+        .find_all_leaves()
+        .into_iter()
+        .filter_map(|leaf| match &leaf.activity_label {
+            OCPTLeafLabel::Activity(label) => Some(label),
+            OCPTLeafLabel::Tau => None,
+        })
+        .collect();
+    activities.sort();
+    assert_eq!(activities, ["pay order", "pick item", "place order", "ship order"]);
+}
+
+#[test]
+fn test_golden_frontend_backend_conversion() {
+    let ocpt = fixture_ocpt();
+    assert!(ocpt.is_valid());
+    assert_golden("orders_ocpt_backend", &Value::String(canonical_string(&ocpt.root)));
+
+    let frontend = backend_to_frontend(&ocpt);
+    let mut value = serde_json::to_value(&frontend).unwrap();
+    without_uuids(&mut value);
+    assert_golden("orders_ocpt_frontend_normalized", &value);
+
+    // The frontend tree keeps the uuids of the backend tree
+    let again = frontend_to_backend(frontend).unwrap();
+    assert_eq!(again.find_all_node_uuids(), ocpt.find_all_node_uuids());
+}
+
+#[test]
+fn test_golden_conformance() {
+    let ocel = orders_ocel();
+    let mined = parse_ocpt(&mine_ocpt_json(&ocel, &MiningOptions::default())).unwrap();
+    let scores = json!({
+        "fixture_model": conformance(&ocel, &fixture_ocpt()),
+        "mined_model": conformance(&ocel, &mined),
+    });
+    assert_golden("orders_conformance", &scores);
+}